@@ -0,0 +1,193 @@
+//! Queries on `rkyv` archives (feature `rkyv`).
+//!
+//! With the `rkyv` feature `SortedVec` and `SortedSet` derive `Archive`,
+//! `Serialize` and `Deserialize`. The archived `ArchivedSortedVec` and
+//! `ArchivedSortedSet` dereference to a slice of archived elements and can be
+//! searched in place, e.g. in a memory-mapped file, without deserializing.
+//!
+//! Validating an archive with `rkyv::access` checks its layout but not that
+//! the elements are sorted, use `is_sorted` for archives from untrusted
+//! sources.
+//!
+//! ```
+//! use rkyv::rancor::Error;
+//! use sorted_vec2::{Ascending, ArchivedSortedVec, SortedVec};
+//!
+//! let v = SortedVec::from_unsorted(vec![30u32, 10, 20]);
+//! let bytes = rkyv::to_bytes::<Error>(&v).unwrap();
+//! let archived = rkyv::access::<ArchivedSortedVec<u32, Ascending>, Error>(&bytes).unwrap();
+//! assert!(archived.contains(&20u32.into()));
+//! assert_eq!(archived.binary_search(&25u32.into()), Err(2));
+//! assert_eq!(archived.range(15u32.into()..).len(), 2);
+//! ```
+
+use std::cmp::Ordering;
+use std::ops::{Bound, RangeBounds};
+
+use rkyv::Archive;
+
+use crate::{ArchivedSortedSet, ArchivedSortedVec, SortOrder};
+
+/// Index range of the elements of `slice`, sorted by `O`, within `range`
+fn range_indices<T, O, R>(slice: &[T], range: R) -> std::ops::Range<usize>
+where
+    T: Ord,
+    O: SortOrder,
+    R: RangeBounds<T>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(x) => slice.partition_point(|y| O::compare(y, x).is_lt()),
+        Bound::Excluded(x) => slice.partition_point(|y| O::compare(y, x).is_le()),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(x) => slice.partition_point(|y| O::compare(y, x).is_le()),
+        Bound::Excluded(x) => slice.partition_point(|y| O::compare(y, x).is_lt()),
+        Bound::Unbounded => slice.len(),
+    };
+    start..end.max(start)
+}
+
+impl<T, O> ArchivedSortedVec<T, O>
+where
+    T: Ord + Archive,
+    T::Archived: Ord,
+    O: SortOrder,
+{
+    #[inline]
+    pub fn as_slice(&self) -> &[T::Archived] {
+        self.vec.as_slice()
+    }
+    #[inline]
+    pub fn binary_search(&self, x: &T::Archived) -> Result<usize, usize> {
+        self.as_slice().binary_search_by(|y| O::compare(y, x))
+    }
+    #[inline]
+    pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T::Archived) -> Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+    #[inline]
+    pub fn contains(&self, x: &T::Archived) -> bool {
+        self.binary_search(x).is_ok()
+    }
+    /// The elements within `range`, bounds are taken in the order of the
+    /// container.
+    #[inline]
+    pub fn range<R: RangeBounds<T::Archived>>(&self, range: R) -> &[T::Archived] {
+        &self.as_slice()[range_indices::<_, O, _>(self.as_slice(), range)]
+    }
+    /// Checks that the elements are sorted, which `rkyv` validation does not.
+    pub fn is_sorted(&self) -> bool {
+        self.as_slice()
+            .windows(2)
+            .all(|w| O::compare(&w[0], &w[1]).is_le())
+    }
+}
+
+impl<T, O> std::ops::Deref for ArchivedSortedVec<T, O>
+where
+    T: Ord + Archive,
+    T::Archived: Ord,
+    O: SortOrder,
+{
+    type Target = [T::Archived];
+    fn deref(&self) -> &[T::Archived] {
+        self.as_slice()
+    }
+}
+
+impl<T, O> ArchivedSortedSet<T, O>
+where
+    T: Ord + Archive,
+    T::Archived: Ord,
+    O: SortOrder,
+{
+    #[inline]
+    pub fn as_slice(&self) -> &[T::Archived] {
+        self.set.as_slice()
+    }
+    #[inline]
+    pub fn binary_search(&self, x: &T::Archived) -> Result<usize, usize> {
+        self.set.binary_search(x)
+    }
+    #[inline]
+    pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T::Archived) -> Ordering,
+    {
+        self.set.binary_search_by(f)
+    }
+    #[inline]
+    pub fn contains(&self, x: &T::Archived) -> bool {
+        self.set.contains(x)
+    }
+    /// The elements within `range`, bounds are taken in the order of the
+    /// container.
+    #[inline]
+    pub fn range<R: RangeBounds<T::Archived>>(&self, range: R) -> &[T::Archived] {
+        self.set.range(range)
+    }
+    /// Checks that the elements are sorted and unique, which `rkyv`
+    /// validation does not.
+    pub fn is_sorted(&self) -> bool {
+        self.as_slice()
+            .windows(2)
+            .all(|w| O::compare(&w[0], &w[1]).is_lt())
+    }
+}
+
+impl<T, O> std::ops::Deref for ArchivedSortedSet<T, O>
+where
+    T: Ord + Archive,
+    T::Archived: Ord,
+    O: SortOrder,
+{
+    type Target = [T::Archived];
+    fn deref(&self) -> &[T::Archived] {
+        self.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ascending, Descending, ReverseSortedVec, SortedSet, SortedVec};
+    use rkyv::rancor::Error;
+    use rkyv::Archived;
+
+    fn native(slice: &[Archived<i64>]) -> Vec<i64> {
+        slice.iter().map(|x| x.to_native()).collect()
+    }
+
+    #[test]
+    fn test_archived() {
+        let v = SortedVec::from_unsorted(vec![5i64, -3, 12, 5]);
+        let bytes = rkyv::to_bytes::<Error>(&v).unwrap();
+        let archived = rkyv::access::<ArchivedSortedVec<i64, Ascending>, Error>(&bytes).unwrap();
+        assert!(archived.is_sorted());
+        assert_eq!(archived.len(), 4);
+        assert_eq!(archived.binary_search(&12i64.into()), Ok(3));
+        assert!(!archived.contains(&0i64.into()));
+        assert_eq!(
+            native(archived.range(0i64.into()..12i64.into())),
+            vec![5, 5]
+        );
+        let v2 = rkyv::deserialize::<SortedVec<i64>, Error>(archived).unwrap();
+        assert_eq!(v2, v);
+
+        let s = SortedSet::from_unsorted(vec!["b".to_string(), "a".to_string()]);
+        let bytes = rkyv::to_bytes::<Error>(&s).unwrap();
+        let archived = rkyv::access::<ArchivedSortedSet<String, Ascending>, Error>(&bytes).unwrap();
+        assert!(archived.is_sorted());
+        assert_eq!(archived[0].as_str(), "a");
+
+        let d = ReverseSortedVec::from(vec![1i64, 3, 2]);
+        let bytes = rkyv::to_bytes::<Error>(&d).unwrap();
+        let archived = rkyv::access::<ArchivedSortedVec<i64, Descending>, Error>(&bytes).unwrap();
+        assert_eq!(archived.binary_search(&1i64.into()), Ok(2));
+        assert_eq!(native(archived.range(2i64.into()..)), vec![2, 1]);
+    }
+}
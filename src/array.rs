@@ -0,0 +1,327 @@
+//! Fixed capacity sorted vector stored inline.
+//!
+//! `SortedArrayVec<T, N>` holds up to `N` elements in ascending order in an
+//! inline array and never allocates, for hot loops and interrupt contexts
+//! where allocation is not allowed. Insertion is fallible: `try_insert`
+//! returns the element with `Err` when the vector is full.
+//!
+//! ```
+//! use sorted_vec2::array::SortedArrayVec;
+//!
+//! let mut v = SortedArrayVec::<u32, 3>::new();
+//! assert_eq!(v.try_insert(20), Ok(0));
+//! assert_eq!(v.try_insert(10), Ok(0));
+//! assert_eq!(v.try_insert(15), Ok(1));
+//! assert_eq!(v.try_insert(5), Err(5));
+//! assert_eq!(*v, [10, 15, 20]);
+//! assert_eq!(v.binary_search(&15), Ok(1));
+//! ```
+
+use std::borrow::Borrow;
+use std::mem::MaybeUninit;
+use std::ptr;
+
+use crate::storage::Storage;
+use crate::FindOrInsert;
+
+/// Vector of at most `N` elements sorted in ascending order, may contain
+/// duplicates
+pub struct SortedArrayVec<T: Ord, const N: usize> {
+    len: usize,
+    buf: [MaybeUninit<T>; N],
+}
+
+impl<T: Ord, const N: usize> SortedArrayVec<T, N> {
+    pub const fn new() -> Self {
+        SortedArrayVec {
+            len: 0,
+            buf: [const { MaybeUninit::uninit() }; N],
+        }
+    }
+    /// Uses `sort_unstable()` to sort in place, filling the vector.
+    pub fn from_array(mut array: [T; N]) -> Self {
+        array.sort_unstable();
+        SortedArrayVec {
+            len: N,
+            buf: array.map(MaybeUninit::new),
+        }
+    }
+    /// Insert an element into sorted position, returning the order index at which
+    /// it was placed, or returning the element with `Err` if the vector is full.
+    #[inline]
+    pub fn try_insert(&mut self, element: T) -> Result<usize, T> {
+        self.insert_sorted(element)
+    }
+    /// Find the element and return the index with `Ok`, otherwise insert the
+    /// element and return the new element index with `Err`. If the element
+    /// is not found and the vector is full it is returned with `Err`.
+    #[inline]
+    pub fn try_find_or_insert(&mut self, element: T) -> Result<FindOrInsert, T> {
+        self.find_or_insert_sorted(element)
+    }
+    /// Binary search for `x`, which may be any borrowed form of the element
+    /// type. See `slice::binary_search`.
+    #[inline]
+    pub fn binary_search<Q>(&self, x: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(x)
+    }
+    /// Binary search for `x`, shadowing the linear `slice::contains` reached
+    /// through `Deref`. O(log n).
+    #[inline]
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(x).is_ok()
+    }
+    #[inline]
+    pub fn remove_item<Q>(&mut self, item: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_sorted(item)
+    }
+    /// Panics if index is out of bounds
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> T {
+        Storage::remove(self, index)
+    }
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            Some(unsafe { self.buf[self.len].assume_init_read() })
+        }
+    }
+    pub fn clear(&mut self) {
+        let len = self.len;
+        // leak rather than double drop if a destructor panics
+        self.len = 0;
+        unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.as_mut_ptr(), len)) }
+    }
+    #[inline]
+    pub fn dedup(&mut self) {
+        self.retain_with_prev(|prev, x| prev != Some(x))
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_with_prev(|_, x| f(x))
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+    /// The elements in sorted order
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.buf.as_ptr() as *const T, self.len) }
+    }
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        self.buf.as_mut_ptr() as *mut T
+    }
+    /// Keeps the elements for which `f` returns true given the last kept
+    /// element, leaking the remainder if `f` panics
+    fn retain_with_prev<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Option<&T>, &T) -> bool,
+    {
+        let len = self.len;
+        self.len = 0;
+        let base = self.as_mut_ptr();
+        let mut kept = 0;
+        for i in 0..len {
+            unsafe {
+                let p = base.add(i);
+                let prev = if kept == 0 {
+                    None
+                } else {
+                    Some(&*base.add(kept - 1))
+                };
+                if f(prev, &*p) {
+                    ptr::copy(p, base.add(kept), 1);
+                    kept += 1;
+                } else {
+                    ptr::drop_in_place(p);
+                }
+            }
+        }
+        self.len = kept;
+    }
+}
+
+impl<T: Ord, const N: usize> Storage for SortedArrayVec<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+    #[inline]
+    fn last(&self) -> Option<&T> {
+        self.as_slice().last()
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> &mut T {
+        assert!(index < self.len, "index out of bounds");
+        unsafe { self.buf[index].assume_init_mut() }
+    }
+    #[inline]
+    fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> std::cmp::Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+    fn insert(&mut self, index: usize, element: T) -> Result<(), T> {
+        assert!(index <= self.len, "index out of bounds");
+        if self.is_full() {
+            return Err(element);
+        }
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            ptr::copy(p, p.add(1), self.len - index);
+            ptr::write(p, element);
+        }
+        self.len += 1;
+        Ok(())
+    }
+    #[inline]
+    fn push(&mut self, element: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(element);
+        }
+        self.buf[self.len].write(element);
+        self.len += 1;
+        Ok(())
+    }
+    fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            let element = ptr::read(p);
+            ptr::copy(p.add(1), p, self.len - index - 1);
+            self.len -= 1;
+            element
+        }
+    }
+}
+impl<T: Ord, const N: usize> Drop for SortedArrayVec<T, N> {
+    fn drop(&mut self) {
+        self.clear()
+    }
+}
+impl<T: Ord, const N: usize> Default for SortedArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Ord + Clone, const N: usize> Clone for SortedArrayVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut out = Self::new();
+        for x in self.as_slice() {
+            out.buf[out.len].write(x.clone());
+            out.len += 1;
+        }
+        out
+    }
+}
+impl<T: Ord + std::fmt::Debug, const N: usize> std::fmt::Debug for SortedArrayVec<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+impl<T: Ord, const N: usize> PartialEq for SortedArrayVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+impl<T: Ord, const N: usize> Eq for SortedArrayVec<T, N> {}
+impl<T: Ord + std::hash::Hash, const N: usize> std::hash::Hash for SortedArrayVec<T, N> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+impl<T: Ord, const N: usize> std::ops::Deref for SortedArrayVec<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+impl<T: Ord, const N: usize> From<[T; N]> for SortedArrayVec<T, N> {
+    fn from(array: [T; N]) -> Self {
+        Self::from_array(array)
+    }
+}
+impl<'a, T: Ord, const N: usize> IntoIterator for &'a SortedArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+    fn into_iter(self) -> std::slice::Iter<'a, T> {
+        self.as_slice().iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_array_vec() {
+        let mut v = SortedArrayVec::<i32, 4>::from([5, 1, 3, 1]);
+        assert!(v.is_full());
+        assert_eq!(v.try_insert(2), Err(2));
+        assert_eq!(v.try_find_or_insert(3), Ok(FindOrInsert::Found(2)));
+        assert_eq!(v.try_find_or_insert(4), Err(4));
+        v.dedup();
+        assert_eq!(*v, [1, 3, 5]);
+        assert_eq!(v.try_find_or_insert(4), Ok(FindOrInsert::Inserted(2)));
+        assert_eq!(v.remove_item(&3), Some(3));
+        assert_eq!(v.remove_index(0), 1);
+        assert_eq!(v.pop(), Some(5));
+        let mut fours = SortedArrayVec::from_array([4, 4, 4, 4]);
+        fours.dedup();
+        assert_eq!(v.clone(), fours);
+    }
+
+    #[test]
+    fn test_array_vec_drop() {
+        let rc = Rc::new(());
+        let mut v = SortedArrayVec::<_, 8>::new();
+        for i in 0..6 {
+            v.try_insert((i % 3, rc.clone())).unwrap();
+        }
+        assert_eq!(Rc::strong_count(&rc), 7);
+        v.retain(|(i, _)| *i != 1);
+        assert_eq!(Rc::strong_count(&rc), 5);
+        let w = v.clone();
+        assert_eq!(v.pop().map(|(i, _)| i), Some(2));
+        v.clear();
+        assert_eq!(Rc::strong_count(&rc), 5);
+        drop(w);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
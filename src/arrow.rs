@@ -0,0 +1,135 @@
+//! Apache Arrow interop (feature `arrow`).
+//!
+//! Integer `SortedVec`s and float `partial::SortedVec`s convert to and from the
+//! corresponding Arrow `PrimitiveArray`s. Conversion into an array never
+//! copies. Conversion from an array checks that the values are sorted and
+//! reuses the array buffer when it is uniquely owned and not sliced, otherwise
+//! the values are copied.
+//!
+//! ```
+//! use arrow_array::Int64Array;
+//! use sorted_vec2::SortedVec;
+//!
+//! let v = SortedVec::from_unsorted(vec![3i64, 1, 2]);
+//! let array = Int64Array::from(v);
+//! let v = SortedVec::try_from(array).unwrap();
+//! assert_eq!(*v, vec![1, 2, 3]);
+//! ```
+
+use arrow_array::types::*;
+use arrow_array::{ArrowPrimitiveType, PrimitiveArray};
+use arrow_buffer::ScalarBuffer;
+
+use crate::{partial, SortedVec};
+
+/// Error converting an Arrow array into a sorted container
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FromArrowError {
+    /// The array contains null values
+    Nulls,
+    /// The array values are not sorted, or contain incomparable (NaN) values
+    Unsorted,
+}
+
+impl std::fmt::Display for FromArrowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FromArrowError::Nulls => f.write_str("array contains null values"),
+            FromArrowError::Unsorted => f.write_str("array values are not sorted"),
+        }
+    }
+}
+
+impl std::error::Error for FromArrowError {}
+
+/// Take the values of a sorted array without nulls
+fn into_sorted_vec<A>(array: PrimitiveArray<A>) -> Result<Vec<A::Native>, FromArrowError>
+where
+    A: ArrowPrimitiveType,
+    A::Native: PartialOrd,
+{
+    let (_, values, nulls) = array.into_parts();
+    if nulls.is_some_and(|nulls| nulls.null_count() > 0) {
+        return Err(FromArrowError::Nulls);
+    }
+    if !values.windows(2).all(|w| w[0] <= w[1]) {
+        return Err(FromArrowError::Unsorted);
+    }
+    Ok(match values.into_inner().into_vec() {
+        Ok(vec) => vec,
+        Err(buffer) => ScalarBuffer::<A::Native>::from(buffer).to_vec(),
+    })
+}
+
+macro_rules! impl_arrow {
+    ($container:ident, $($native:ty => $arrow:ty),*) => {
+        $(
+            impl From<$container<$native>> for PrimitiveArray<$arrow> {
+                fn from(sorted: $container<$native>) -> Self {
+                    PrimitiveArray::new(ScalarBuffer::from(sorted.into_vec()), None)
+                }
+            }
+            impl TryFrom<PrimitiveArray<$arrow>> for $container<$native> {
+                type Error = FromArrowError;
+                fn try_from(array: PrimitiveArray<$arrow>) -> Result<Self, FromArrowError> {
+                    let vec = into_sorted_vec(array)?;
+                    Ok(unsafe { $container::from_unsorted_unchecked(vec) })
+                }
+            }
+        )*
+    };
+}
+
+impl_arrow!(SortedVec,
+    i8 => Int8Type,
+    i16 => Int16Type,
+    i32 => Int32Type,
+    i64 => Int64Type,
+    u8 => UInt8Type,
+    u16 => UInt16Type,
+    u32 => UInt32Type,
+    u64 => UInt64Type
+);
+
+use partial::SortedVec as PartialSortedVec;
+impl_arrow!(PartialSortedVec,
+    f32 => Float32Type,
+    f64 => Float64Type
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Array, Float64Array, UInt32Array};
+
+    #[test]
+    fn test_arrow() {
+        let v = SortedVec::from_unsorted(vec![5u32, 1, 3]);
+        let array = UInt32Array::from(v.clone());
+        assert_eq!(array.values().as_ref(), [1, 3, 5]);
+        assert_eq!(SortedVec::try_from(array.clone()), Ok(v));
+        assert_eq!(
+            SortedVec::<u32>::try_from(array.slice(1, 2)).map(SortedVec::into_vec),
+            Ok(vec![3, 5])
+        );
+        assert_eq!(
+            SortedVec::<u32>::try_from(UInt32Array::from(vec![2, 1])),
+            Err(FromArrowError::Unsorted)
+        );
+        assert_eq!(
+            SortedVec::<u32>::try_from(UInt32Array::from(vec![Some(1), None])),
+            Err(FromArrowError::Nulls)
+        );
+        let f = partial::SortedVec::from_unsorted(vec![2.0, -1.0]);
+        let array = Float64Array::from(f);
+        assert_eq!(array.len(), 2);
+        assert_eq!(
+            *partial::SortedVec::try_from(array).unwrap(),
+            vec![-1.0, 2.0]
+        );
+        assert_eq!(
+            partial::SortedVec::try_from(Float64Array::from(vec![1.0, f64::NAN])),
+            Err(FromArrowError::Unsorted)
+        );
+    }
+}
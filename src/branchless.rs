@@ -0,0 +1,217 @@
+//! Branchless binary search for primitive keys (feature `branchless`).
+//!
+//! `slice::binary_search` exits early on equality and branches on every
+//! comparison, which mispredicts about half of the time on random lookups.
+//! The searches in this module halve the range a fixed number of times and
+//! select the next base with a comparison the compiler turns into a
+//! conditional move, so the only branch is the loop counter. They always
+//! return the leftmost equal element, like `SortedVec::lower_bound`.
+//!
+//! Keys implement `PrimitiveKey`: the integer types and `f32`/`f64`, whose
+//! keys follow `total_cmp` so that they can be searched in a `SortedF64Vec`
+//! or `SortedF32Vec`.
+//!
+//! ```
+//! use sorted_vec2::SortedVec;
+//!
+//! let v = SortedVec::from_unsorted(vec![40u32, 10, 30, 20, 30]);
+//! assert_eq!(v.binary_search_branchless(30), Ok(2));
+//! assert_eq!(v.rank_branchless(35), 4);
+//! assert_eq!(
+//!     v.binary_search_batch_branchless(&[5, 20, 45]),
+//!     vec![Err(0), Ok(1), Err(5)]
+//! );
+//! ```
+//!
+//! `binary_search_batch_branchless` advances several searches in lockstep so
+//! that their memory loads overlap instead of waiting on each other.
+
+use crate::compare::SortedVecBy;
+use crate::float::{Float, TotalCmp};
+use crate::SortedVec;
+
+/// Number of searches advanced together by `binary_search_batch`
+const LANES: usize = 8;
+
+/// Primitive types that can be compared without branching
+pub trait PrimitiveKey: Copy {
+    /// Whether `self` is ordered before `other`
+    fn key_lt(self, other: Self) -> bool;
+}
+
+macro_rules! impl_primitive_key {
+    ($($t:ty),*) => {
+        $(
+            impl PrimitiveKey for $t {
+                #[inline(always)]
+                fn key_lt(self, other: Self) -> bool {
+                    self < other
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_key!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl PrimitiveKey for f32 {
+    /// Same order as `f32::total_cmp`
+    #[inline(always)]
+    fn key_lt(self, other: Self) -> bool {
+        #[inline(always)]
+        fn key(x: f32) -> i32 {
+            let bits = x.to_bits() as i32;
+            bits ^ (((bits >> 31) as u32) >> 1) as i32
+        }
+        key(self) < key(other)
+    }
+}
+
+impl PrimitiveKey for f64 {
+    /// Same order as `f64::total_cmp`
+    #[inline(always)]
+    fn key_lt(self, other: Self) -> bool {
+        #[inline(always)]
+        fn key(x: f64) -> i64 {
+            let bits = x.to_bits() as i64;
+            bits ^ (((bits >> 63) as u64) >> 1) as i64
+        }
+        key(self) < key(other)
+    }
+}
+
+/// Number of elements of the sorted `slice` that are less than `x`
+#[inline]
+pub fn lower_bound<T: PrimitiveKey>(slice: &[T], x: T) -> usize {
+    if slice.is_empty() {
+        return 0;
+    }
+    let mut base = 0;
+    let mut size = slice.len();
+    while size > 1 {
+        let half = size / 2;
+        let mid = base + half;
+        base = if slice[mid].key_lt(x) { mid } else { base };
+        size -= half;
+    }
+    base + slice[base].key_lt(x) as usize
+}
+
+/// Search the sorted `slice` for `x`, returning the index of the leftmost
+/// equal element with `Ok`, or the insertion index with `Err`
+#[inline]
+pub fn binary_search<T: PrimitiveKey>(slice: &[T], x: T) -> Result<usize, usize> {
+    let index = lower_bound(slice, x);
+    match slice.get(index) {
+        Some(y) if !x.key_lt(*y) => Ok(index),
+        _ => Err(index),
+    }
+}
+
+/// `binary_search` for each of the `needles`, which need not be sorted
+pub fn binary_search_batch<T: PrimitiveKey>(
+    slice: &[T],
+    needles: &[T],
+) -> Vec<Result<usize, usize>> {
+    let mut results = Vec::with_capacity(needles.len());
+    if slice.is_empty() {
+        results.resize(needles.len(), Err(0));
+        return results;
+    }
+    for chunk in needles.chunks(LANES) {
+        let mut bases = [0; LANES];
+        let mut size = slice.len();
+        // every search over the same length takes the same number of steps
+        while size > 1 {
+            let half = size / 2;
+            for (base, x) in bases.iter_mut().zip(chunk) {
+                let mid = *base + half;
+                *base = if slice[mid].key_lt(*x) { mid } else { *base };
+            }
+            size -= half;
+        }
+        for (base, x) in bases.iter().zip(chunk) {
+            let index = base + slice[*base].key_lt(*x) as usize;
+            results.push(match slice.get(index) {
+                Some(y) if !x.key_lt(*y) => Ok(index),
+                _ => Err(index),
+            });
+        }
+    }
+    results
+}
+
+impl<T: Ord + PrimitiveKey> SortedVec<T> {
+    /// Same as `binary_search`, returning the leftmost equal element, without
+    /// branching on the comparisons.
+    #[inline]
+    pub fn binary_search_branchless(&self, x: T) -> Result<usize, usize> {
+        binary_search(&self.vec, x)
+    }
+    /// Same as `rank`, without branching on the comparisons.
+    #[inline]
+    pub fn rank_branchless(&self, x: T) -> usize {
+        lower_bound(&self.vec, x)
+    }
+    /// Same as `binary_search_batch`, with interleaved branchless searches.
+    /// The needles need not be sorted.
+    pub fn binary_search_batch_branchless(&self, needles: &[T]) -> Vec<Result<usize, usize>> {
+        binary_search_batch(&self.vec, needles)
+    }
+}
+
+impl<F: Float + PrimitiveKey> SortedVecBy<F, TotalCmp> {
+    /// Same as `binary_search`, returning the leftmost equal element, without
+    /// branching on the comparisons.
+    #[inline]
+    pub fn binary_search_branchless(&self, x: F) -> Result<usize, usize> {
+        binary_search(self, x)
+    }
+    /// Number of elements ordered before `x` by `total_cmp`.
+    #[inline]
+    pub fn rank_branchless(&self, x: F) -> usize {
+        lower_bound(self, x)
+    }
+    /// `binary_search_branchless` for each of the `needles`.
+    pub fn binary_search_batch_branchless(&self, needles: &[F]) -> Vec<Result<usize, usize>> {
+        binary_search_batch(self, needles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::float::SortedF64Vec;
+
+    #[test]
+    fn test_branchless() {
+        let v = SortedVec::from_unsorted((0..100i64).map(|x| x / 3 * 2).collect());
+        let needles: Vec<i64> = (-2..70).rev().collect();
+        let batch = v.binary_search_batch_branchless(&needles);
+        for (x, result) in needles.iter().zip(batch) {
+            let expected = match v.binary_search(x) {
+                Ok(_) => Ok(v.lower_bound(x)),
+                Err(index) => Err(index),
+            };
+            assert_eq!(v.binary_search_branchless(*x), expected);
+            assert_eq!(v.rank_branchless(*x), v.rank(x));
+            assert_eq!(result, expected);
+        }
+        let e = SortedVec::<u8>::new();
+        assert_eq!(e.binary_search_branchless(1), Err(0));
+        assert_eq!(e.binary_search_batch_branchless(&[1, 2]), vec![Err(0); 2]);
+        let f: SortedF64Vec = vec![f64::NAN, 1.5, -0.0, 0.0, -f64::INFINITY]
+            .into_iter()
+            .collect();
+        assert_eq!(f.binary_search_branchless(-f64::INFINITY), Ok(0));
+        assert_eq!(f.binary_search_branchless(0.0), Ok(2));
+        assert_eq!(f.rank_branchless(0.0), 2);
+        assert_eq!(f.binary_search_branchless(1.0), Err(3));
+        assert_eq!(f.binary_search_branchless(f64::NAN), Ok(4));
+        assert_eq!(
+            f.binary_search_batch_branchless(&[-0.0, 2.0]),
+            vec![Ok(1), Err(4)]
+        );
+        assert!((-1.0f32).key_lt(-0.0) && (-0.0f32).key_lt(0.0));
+    }
+}
@@ -0,0 +1,274 @@
+//! Branded indices.
+//!
+//! `SortedVec::with_branded` and `SortedSet::with_branded` give access to a
+//! view of the container whose search and insert methods return a
+//! `SortedIndex<'brand>` instead of a bare `usize`. The `'brand` lifetime is
+//! unique to each call, so an index obtained from one container can not be
+//! used to index another:
+//!
+//! ```compile_fail
+//! use sorted_vec2::SortedVec;
+//!
+//! let mut a = SortedVec::from_unsorted(vec![1, 2, 3]);
+//! let mut b = SortedVec::from_unsorted(vec![4, 5, 6]);
+//! a.with_branded(|a| {
+//!     b.with_branded(|b| {
+//!         let i = a.binary_search(&2).unwrap();
+//!         let _ = b[i];
+//!     })
+//! });
+//! ```
+//!
+//! The views only allow operations that grow the container, so every index
+//! remains in bounds for the lifetime of the view and indexing does not need
+//! a bounds check. Note that inserting an element shifts the elements that
+//! follow it, so an existing index may afterwards refer to a different
+//! element.
+
+use std::marker::PhantomData;
+
+use crate::{FindOrInsert, SortedSet, SortedVec};
+
+/// Invariant lifetime marker
+type Brand<'brand> = PhantomData<fn(&'brand ()) -> &'brand ()>;
+
+/// An index that is known to be in bounds for the branded view it was
+/// obtained from.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SortedIndex<'brand> {
+    index: usize,
+    brand: Brand<'brand>,
+}
+
+impl<'brand> SortedIndex<'brand> {
+    #[inline]
+    fn new(index: usize) -> Self {
+        SortedIndex {
+            index,
+            brand: PhantomData,
+        }
+    }
+    /// The underlying position
+    #[inline]
+    pub fn get(self) -> usize {
+        self.index
+    }
+}
+
+impl From<SortedIndex<'_>> for usize {
+    fn from(index: SortedIndex) -> usize {
+        index.index
+    }
+}
+
+/// Result of a branded `find_or_insert`
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum BrandedFindOrInsert<'brand> {
+    Found(SortedIndex<'brand>),
+    Inserted(SortedIndex<'brand>),
+}
+
+impl<'brand> BrandedFindOrInsert<'brand> {
+    /// Get the index of the element that was either found or inserted.
+    pub fn index(self) -> SortedIndex<'brand> {
+        match self {
+            BrandedFindOrInsert::Found(index) | BrandedFindOrInsert::Inserted(index) => index,
+        }
+    }
+    fn new(result: FindOrInsert) -> Self {
+        match result {
+            FindOrInsert::Found(index) => BrandedFindOrInsert::Found(SortedIndex::new(index)),
+            FindOrInsert::Inserted(index) => BrandedFindOrInsert::Inserted(SortedIndex::new(index)),
+        }
+    }
+}
+
+impl<'brand> From<BrandedFindOrInsert<'brand>> for FindOrInsert {
+    fn from(result: BrandedFindOrInsert<'brand>) -> Self {
+        match result {
+            BrandedFindOrInsert::Found(index) => FindOrInsert::Found(index.get()),
+            BrandedFindOrInsert::Inserted(index) => FindOrInsert::Inserted(index.get()),
+        }
+    }
+}
+
+macro_rules! impl_branded_common {
+    ($name:ident, $inner:ident) => {
+        impl<'brand, 'a, T: Ord> $name<'brand, 'a, T> {
+            /// Binary search returning a branded index when the element is found
+            #[inline]
+            pub fn binary_search(&self, x: &T) -> Result<SortedIndex<'brand>, usize> {
+                self.$inner.binary_search(x).map(SortedIndex::new)
+            }
+            /// Returns a branded index if `index` is in bounds
+            #[inline]
+            pub fn index_of(&self, index: usize) -> Option<SortedIndex<'brand>> {
+                if index < self.$inner.len() {
+                    Some(SortedIndex::new(index))
+                } else {
+                    None
+                }
+            }
+            /// Branded index of the first element
+            #[inline]
+            pub fn first_index(&self) -> Option<SortedIndex<'brand>> {
+                self.index_of(0)
+            }
+            /// Branded index of the last element
+            #[inline]
+            pub fn last_index(&self) -> Option<SortedIndex<'brand>> {
+                self.$inner.len().checked_sub(1).map(SortedIndex::new)
+            }
+            #[inline]
+            pub fn get(&self, index: SortedIndex<'brand>) -> &T {
+                // branded indices are only created in bounds and the view can not
+                // shrink the container
+                unsafe { self.$inner.get_unchecked(index.index) }
+            }
+            #[inline]
+            pub fn as_slice(&self) -> &[T] {
+                self.$inner.as_slice()
+            }
+        }
+        impl<'brand, 'a, T: Ord> std::ops::Index<SortedIndex<'brand>> for $name<'brand, 'a, T> {
+            type Output = T;
+            #[inline]
+            fn index(&self, index: SortedIndex<'brand>) -> &T {
+                self.get(index)
+            }
+        }
+    };
+}
+
+/// Branded view of a `SortedVec`
+#[derive(Debug)]
+pub struct BrandedSortedVec<'brand, 'a, T: Ord> {
+    vec: &'a mut SortedVec<T>,
+    brand: Brand<'brand>,
+}
+impl_branded_common!(BrandedSortedVec, vec);
+
+impl<'brand, 'a, T: Ord> BrandedSortedVec<'brand, 'a, T> {
+    /// See `SortedVec::insert`
+    #[inline]
+    pub fn insert(&mut self, element: T) -> SortedIndex<'brand> {
+        SortedIndex::new(self.vec.insert(element))
+    }
+    /// See `SortedVec::push`
+    #[inline]
+    pub fn push(&mut self, element: T) -> SortedIndex<'brand> {
+        SortedIndex::new(self.vec.push(element))
+    }
+    /// See `SortedVec::find_or_insert`
+    #[inline]
+    pub fn find_or_insert(&mut self, element: T) -> BrandedFindOrInsert<'brand> {
+        BrandedFindOrInsert::new(self.vec.find_or_insert(element))
+    }
+    /// See `SortedVec::find_or_push`
+    #[inline]
+    pub fn find_or_push(&mut self, element: T) -> BrandedFindOrInsert<'brand> {
+        BrandedFindOrInsert::new(self.vec.find_or_push(element))
+    }
+}
+
+/// Branded view of a `SortedSet`
+#[derive(Debug)]
+pub struct BrandedSortedSet<'brand, 'a, T: Ord> {
+    set: &'a mut SortedSet<T>,
+    brand: Brand<'brand>,
+}
+impl_branded_common!(BrandedSortedSet, set);
+
+impl<'brand, 'a, T: Ord> BrandedSortedSet<'brand, 'a, T> {
+    /// See `SortedSet::replace`
+    #[inline]
+    pub fn replace(&mut self, element: T) -> (SortedIndex<'brand>, Option<T>) {
+        let (index, replaced) = self.set.replace(element);
+        (SortedIndex::new(index), replaced)
+    }
+    /// See `SortedSet::push`
+    #[inline]
+    pub fn push(&mut self, element: T) -> (SortedIndex<'brand>, Option<T>) {
+        let (index, replaced) = self.set.push(element);
+        (SortedIndex::new(index), replaced)
+    }
+    /// See `SortedSet::find_or_insert`
+    #[inline]
+    pub fn find_or_insert(&mut self, element: T) -> BrandedFindOrInsert<'brand> {
+        BrandedFindOrInsert::new(self.set.find_or_insert(element))
+    }
+    /// See `SortedSet::find_or_push`
+    #[inline]
+    pub fn find_or_push(&mut self, element: T) -> BrandedFindOrInsert<'brand> {
+        BrandedFindOrInsert::new(self.set.find_or_push(element))
+    }
+}
+
+impl<T: Ord> SortedVec<T> {
+    /// Call `f` with a view of the container returning indices branded with a
+    /// lifetime unique to this call. See the `branded` module.
+    pub fn with_branded<F, R>(&mut self, f: F) -> R
+    where
+        F: for<'brand> FnOnce(BrandedSortedVec<'brand, '_, T>) -> R,
+    {
+        f(BrandedSortedVec {
+            vec: self,
+            brand: PhantomData,
+        })
+    }
+}
+
+impl<T: Ord> SortedSet<T> {
+    /// Call `f` with a view of the container returning indices branded with a
+    /// lifetime unique to this call. See the `branded` module.
+    pub fn with_branded<F, R>(&mut self, f: F) -> R
+    where
+        F: for<'brand> FnOnce(BrandedSortedSet<'brand, '_, T>) -> R,
+    {
+        f(BrandedSortedSet {
+            set: self,
+            brand: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branded_vec() {
+        let mut v = SortedVec::from_unsorted(vec![5, 1, 3]);
+        let found = v.with_branded(|mut v| {
+            let i = v.binary_search(&3).unwrap();
+            assert_eq!(v[i], 3);
+            let j = v.insert(4);
+            assert_eq!((j.get(), v[j]), (2, 4));
+            assert!(v.find_or_insert(4).index() == j);
+            let k = v.find_or_push(7);
+            assert_eq!(k, BrandedFindOrInsert::Inserted(v.last_index().unwrap()));
+            assert!(v.index_of(5).is_none());
+            assert_eq!(v.as_slice(), [1, 3, 4, 5, 7]);
+            v[v.first_index().unwrap()]
+        });
+        assert_eq!(found, 1);
+        assert_eq!(*v, vec![1, 3, 4, 5, 7]);
+    }
+
+    #[test]
+    fn test_branded_set() {
+        let mut s = SortedSet::from_unsorted(vec![5, 1, 3]);
+        s.with_branded(|mut s| {
+            let (i, replaced) = s.replace(3);
+            assert_eq!((s[i], replaced), (3, Some(3)));
+            let (j, replaced) = s.push(9);
+            assert_eq!((usize::from(j), replaced), (3, None));
+            assert_eq!(
+                FindOrInsert::from(s.find_or_insert(2)),
+                FindOrInsert::Inserted(1)
+            );
+            assert_eq!(s.binary_search(&4), Err(3));
+        });
+        assert_eq!(**s, vec![1, 2, 3, 5, 9]);
+    }
+}
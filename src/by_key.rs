@@ -0,0 +1,262 @@
+//! Vectors sorted by a projected key.
+//!
+//! `SortedByKeyVec<T, K, F>` keeps its elements sorted by the key returned by
+//! `F: Fn(&T) -> K`, so the element type does not need to implement `Ord`,
+//! and lookups take the key alone. Elements with equal keys are kept in
+//! insertion order.
+//!
+//! The key function is called on every comparison and should be cheap, e.g.
+//! copying a field. Non-capturing closures coerce to `fn` pointers, which
+//! give the type a nameable form:
+//!
+//! ```
+//! use sorted_vec2::by_key::SortedByKeyVec;
+//!
+//! struct User {
+//!     id: u32,
+//!     name: &'static str,
+//! }
+//!
+//! let mut users: SortedByKeyVec<User, u32, fn(&User) -> u32> =
+//!     SortedByKeyVec::new(|user| user.id);
+//! users.insert(User { id: 7, name: "b" });
+//! users.insert(User { id: 3, name: "a" });
+//! assert_eq!(users.get(&7).map(|user| user.name), Some("b"));
+//! assert_eq!(users[0].id, 3);
+//! ```
+
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+/// Vector of elements sorted by the key `F` projects them to
+pub struct SortedByKeyVec<T, K, F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    vec: Vec<T>,
+    key: F,
+    marker: PhantomData<fn() -> K>,
+}
+
+impl<T, K, F> SortedByKeyVec<T, K, F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    #[inline]
+    pub fn new(key: F) -> Self {
+        SortedByKeyVec {
+            vec: Vec::new(),
+            key,
+            marker: PhantomData,
+        }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize, key: F) -> Self {
+        SortedByKeyVec {
+            vec: Vec::with_capacity(capacity),
+            key,
+            marker: PhantomData,
+        }
+    }
+    /// Uses the stable `sort_by_key()` to sort in place.
+    pub fn from_unsorted(mut vec: Vec<T>, key: F) -> Self {
+        vec.sort_by_key(&key);
+        SortedByKeyVec {
+            vec,
+            key,
+            marker: PhantomData,
+        }
+    }
+    /// The key function
+    #[inline]
+    pub fn key_fn(&self) -> &F {
+        &self.key
+    }
+    /// Insert an element after any elements with an equal key, returning the
+    /// index at which it was placed.
+    pub fn insert(&mut self, element: T) -> usize {
+        let k = (self.key)(&element);
+        let insert_at = self.vec.partition_point(|x| (self.key)(x) <= k);
+        self.vec.insert(insert_at, element);
+        insert_at
+    }
+    /// Binary search by key. If several elements have the key, the index of
+    /// any of them may be returned.
+    #[inline]
+    pub fn binary_search<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec
+            .binary_search_by(|x| (self.key)(x).borrow().cmp(key))
+    }
+    /// An element with the given key
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&T>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(key).ok().map(|index| &self.vec[index])
+    }
+    /// All elements with the given key, in insertion order
+    pub fn get_all<Q>(&self, key: &Q) -> &[T]
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let start = self.vec.partition_point(|x| (self.key)(x).borrow() < key);
+        let end = start + self.vec[start..].partition_point(|x| (self.key)(x).borrow() <= key);
+        &self.vec[start..end]
+    }
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(key).is_ok()
+    }
+    /// Remove an element with the given key
+    #[inline]
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<T>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.binary_search(key).ok()?;
+        Some(self.vec.remove(index))
+    }
+    /// Panics if index is out of bounds
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> T {
+        self.vec.remove(index)
+    }
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    #[inline]
+    pub fn drain<R>(&mut self, range: R) -> std::vec::Drain<'_, T>
+    where
+        R: std::ops::RangeBounds<usize>,
+    {
+        self.vec.drain(range)
+    }
+    #[inline]
+    pub fn retain<P>(&mut self, f: P)
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.vec.retain(f)
+    }
+    /// NOTE: to_vec() is a slice method that is accessible through deref, use
+    /// this instead to avoid cloning
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+    /// Apply a closure mutating the vector and re-sort it with the stable
+    /// `sort_by_key()`
+    pub fn mutate_vec<G, O>(&mut self, f: G) -> O
+    where
+        G: FnOnce(&mut Vec<T>) -> O,
+    {
+        let res = f(&mut self.vec);
+        let key = &self.key;
+        self.vec.sort_by_key(&key);
+        res
+    }
+}
+
+impl<T, K, F> std::ops::Deref for SortedByKeyVec<T, K, F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    type Target = Vec<T>;
+    fn deref(&self) -> &Vec<T> {
+        &self.vec
+    }
+}
+impl<T, K, F> Clone for SortedByKeyVec<T, K, F>
+where
+    T: Clone,
+    K: Ord,
+    F: Fn(&T) -> K + Clone,
+{
+    fn clone(&self) -> Self {
+        SortedByKeyVec {
+            vec: self.vec.clone(),
+            key: self.key.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+impl<T, K, F> std::fmt::Debug for SortedByKeyVec<T, K, F>
+where
+    T: std::fmt::Debug,
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SortedByKeyVec")
+            .field("vec", &self.vec)
+            .finish_non_exhaustive()
+    }
+}
+impl<T, K, F> Extend<T> for SortedByKeyVec<T, K, F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for t in iter {
+            let _ = self.insert(t);
+        }
+    }
+}
+impl<T, K, F> IntoIterator for SortedByKeyVec<T, K, F>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+    fn into_iter(self) -> std::vec::IntoIter<T> {
+        self.vec.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sorted_by_key_vec() {
+        let mut v =
+            SortedByKeyVec::from_unsorted(vec![(3, 'c'), (1, 'a'), (3, 'b')], |x: &(i32, char)| {
+                x.0
+            });
+        assert_eq!(*v, vec![(1, 'a'), (3, 'c'), (3, 'b')]);
+        assert_eq!(v.insert((3, 'd')), 3);
+        assert_eq!(v.insert((2, 'x')), 1);
+        assert_eq!(v.get_all(&3), [(3, 'c'), (3, 'b'), (3, 'd')]);
+        assert_eq!(v.get(&2), Some(&(2, 'x')));
+        assert_eq!(v.binary_search(&0), Err(0));
+        assert_eq!(v.remove(&1), Some((1, 'a')));
+        assert!(!v.contains_key(&1));
+        v.mutate_vec(|v| v[0].0 = 5);
+        assert_eq!(v.last(), Some(&(5, 'x')));
+        let names =
+            SortedByKeyVec::from_unsorted(vec!["b".to_string(), "a".to_string()], String::clone);
+        assert!(names.contains_key("a"));
+    }
+}
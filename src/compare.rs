@@ -0,0 +1,216 @@
+//! Vectors sorted by a stored comparator.
+//!
+//! `SortedVecBy<T, C>` keeps its elements sorted by a `C: Compare<T>`
+//! instead of `T: Ord`, so arbitrary orderings (field based, descending,
+//! locale aware, ...) can be used without newtype wrappers. Every insert,
+//! search and removal goes through the comparator, which must implement a
+//! total order.
+//!
+//! `Compare<T>` is implemented for closures `Fn(&T, &T) -> Ordering`:
+//!
+//! ```
+//! use sorted_vec2::compare::SortedVecBy;
+//!
+//! let mut v = SortedVecBy::new(|a: &i32, b: &i32| b.cmp(a));
+//! v.insert(1);
+//! v.insert(3);
+//! v.insert(2);
+//! assert_eq!(*v, vec![3, 2, 1]);
+//! assert_eq!(v.binary_search(&1), Ok(2));
+//! ```
+
+use std::cmp::Ordering;
+
+/// A total order on `T`
+pub trait Compare<T: ?Sized> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+impl<T: ?Sized, F> Compare<T> for F
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    #[inline]
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// Vector sorted by the comparator `C`, may contain duplicates
+#[derive(Clone, Debug)]
+pub struct SortedVecBy<T, C: Compare<T>> {
+    vec: Vec<T>,
+    compare: C,
+}
+
+impl<T, C: Compare<T>> SortedVecBy<T, C> {
+    #[inline]
+    pub fn new(compare: C) -> Self {
+        SortedVecBy {
+            vec: Vec::new(),
+            compare,
+        }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize, compare: C) -> Self {
+        SortedVecBy {
+            vec: Vec::with_capacity(capacity),
+            compare,
+        }
+    }
+    /// Uses `sort_unstable_by()` to sort in place.
+    pub fn from_unsorted(mut vec: Vec<T>, compare: C) -> Self {
+        vec.sort_unstable_by(|a, b| compare.compare(a, b));
+        SortedVecBy { vec, compare }
+    }
+    /// The comparator
+    #[inline]
+    pub fn comparator(&self) -> &C {
+        &self.compare
+    }
+    /// Binary search using the comparator
+    #[inline]
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize> {
+        self.vec.binary_search_by(|y| self.compare.compare(y, x))
+    }
+    #[inline]
+    pub fn contains(&self, x: &T) -> bool {
+        self.binary_search(x).is_ok()
+    }
+    /// Insert an element into sorted position, returning the order index at
+    /// which it was placed.
+    pub fn insert(&mut self, element: T) -> usize {
+        let insert_at = match self.binary_search(&element) {
+            Ok(insert_at) | Err(insert_at) => insert_at,
+        };
+        self.vec.insert(insert_at, element);
+        insert_at
+    }
+    /// Find the element and return the index with `Found`, otherwise insert
+    /// the element and return the new element index with `Inserted`.
+    pub fn find_or_insert(&mut self, element: T) -> crate::FindOrInsert {
+        match self.binary_search(&element) {
+            Ok(index) => crate::FindOrInsert::Found(index),
+            Err(insert_at) => {
+                self.vec.insert(insert_at, element);
+                crate::FindOrInsert::Inserted(insert_at)
+            }
+        }
+    }
+    #[inline]
+    pub fn remove_item(&mut self, item: &T) -> Option<T> {
+        let index = self.binary_search(item).ok()?;
+        Some(self.vec.remove(index))
+    }
+    /// Panics if index is out of bounds
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> T {
+        self.vec.remove(index)
+    }
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    /// Remove consecutive elements that the comparator considers equal
+    pub fn dedup(&mut self) {
+        let compare = &self.compare;
+        self.vec
+            .dedup_by(|a, b| compare.compare(a, b) == Ordering::Equal);
+    }
+    #[inline]
+    pub fn drain<R>(&mut self, range: R) -> std::vec::Drain<'_, T>
+    where
+        R: std::ops::RangeBounds<usize>,
+    {
+        self.vec.drain(range)
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.vec.retain(f)
+    }
+    /// NOTE: to_vec() is a slice method that is accessible through deref, use
+    /// this instead to avoid cloning
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+    /// Apply a closure mutating the sorted vector and use `sort_unstable_by()`
+    /// to re-sort the mutated vector
+    pub fn mutate_vec<F, O>(&mut self, f: F) -> O
+    where
+        F: FnOnce(&mut Vec<T>) -> O,
+    {
+        let res = f(&mut self.vec);
+        let compare = &self.compare;
+        self.vec.sort_unstable_by(|a, b| compare.compare(a, b));
+        res
+    }
+}
+
+impl<T, C: Compare<T>> std::ops::Deref for SortedVecBy<T, C> {
+    type Target = Vec<T>;
+    fn deref(&self) -> &Vec<T> {
+        &self.vec
+    }
+}
+impl<T, C: Compare<T> + Default> Default for SortedVecBy<T, C> {
+    fn default() -> Self {
+        Self::new(C::default())
+    }
+}
+impl<T, C: Compare<T> + Default> FromIterator<T> for SortedVecBy<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_unsorted(iter.into_iter().collect(), C::default())
+    }
+}
+impl<T, C: Compare<T>> Extend<T> for SortedVecBy<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for t in iter {
+            let _ = self.insert(t);
+        }
+    }
+}
+impl<T, C: Compare<T>> IntoIterator for SortedVecBy<T, C> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+    fn into_iter(self) -> std::vec::IntoIter<T> {
+        self.vec.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FindOrInsert;
+
+    #[test]
+    fn test_sorted_vec_by() {
+        let by_len = |a: &&str, b: &&str| a.len().cmp(&b.len());
+        let mut v = SortedVecBy::from_unsorted(vec!["ccc", "a", "bb"], by_len);
+        assert_eq!(*v, vec!["a", "bb", "ccc"]);
+        assert_eq!(v.find_or_insert("xx"), FindOrInsert::Found(1));
+        assert_eq!(v.insert("dddd"), 3);
+        assert!(v.contains(&"zzz"));
+        assert_eq!(v.remove_item(&"yy"), Some("bb"));
+        v.mutate_vec(|v| v.push("e"));
+        v.dedup();
+        assert_eq!(*v, vec!["a", "ccc", "dddd"]);
+
+        struct ByAbs;
+        impl Compare<i32> for ByAbs {
+            fn compare(&self, a: &i32, b: &i32) -> Ordering {
+                a.abs().cmp(&b.abs())
+            }
+        }
+        let mut v = SortedVecBy::new(ByAbs);
+        v.extend([-3, 2, -1]);
+        assert_eq!(v.into_vec(), vec![-1, 2, -3]);
+    }
+}
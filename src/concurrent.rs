@@ -0,0 +1,535 @@
+//! Sharded concurrent sorted containers.
+//!
+//! `ConcurrentSortedSet<T>` and `ConcurrentSortedMap<K, V>` split the key
+//! space into a fixed number of contiguous key ranges, each stored in its own
+//! shard behind an `RwLock`. Operations on a single key lock only the shard
+//! owning that key, so threads working on different key ranges do not
+//! contend. Because the shards cover consecutive key ranges, iterating the
+//! shards in order yields all elements in sorted order; `read` locks every
+//! shard for a consistent view.
+//!
+//! The shard boundaries are chosen when the container is created, either
+//! explicitly with `with_boundaries` or from the initial elements with
+//! `from_unsorted`, and do not change afterwards.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use sorted_vec2::concurrent::ConcurrentSortedSet;
+//!
+//! let set = Arc::new(ConcurrentSortedSet::with_boundaries(vec![100, 200, 300]));
+//! assert_eq!(set.shard_count(), 4);
+//! let handles: Vec<_> = (0..4)
+//!     .map(|t| {
+//!         let set = Arc::clone(&set);
+//!         std::thread::spawn(move || {
+//!             for i in 0..50 {
+//!                 set.insert(t * 100 + i);
+//!             }
+//!         })
+//!     })
+//!     .collect();
+//! for h in handles {
+//!     h.join().unwrap();
+//! }
+//! assert!(set.contains(&301));
+//! let all = set.read();
+//! assert_eq!(all.len(), 200);
+//! assert!(all.iter().zip(all.iter().skip(1)).all(|(a, b)| a < b));
+//! ```
+
+use std::borrow::Borrow;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::map::SortedVecMap;
+use crate::{SortedSet, SortedVec};
+
+/// Shards covering consecutive key ranges: shard `i` holds the keys `k` with
+/// `boundaries[i - 1] <= k < boundaries[i]`.
+struct Shards<K, S> {
+    boundaries: Vec<K>,
+    shards: Vec<RwLock<S>>,
+}
+
+impl<K: Ord, S> Shards<K, S> {
+    fn new(mut boundaries: Vec<K>, empty: impl Fn() -> S) -> Self {
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        let shards = (0..=boundaries.len())
+            .map(|_| RwLock::new(empty()))
+            .collect();
+        Shards { boundaries, shards }
+    }
+    /// Split `sorted` unique keys into at most `shard_count` shards of roughly
+    /// equal length.
+    fn split<T>(
+        sorted: Vec<T>,
+        shard_count: usize,
+        key: impl Fn(&T) -> &K,
+        shard: impl Fn(Vec<T>) -> S,
+    ) -> Self
+    where
+        K: Clone,
+    {
+        let shard_count = shard_count.clamp(1, sorted.len().max(1));
+        let boundaries: Vec<K> = (1..shard_count)
+            .map(|i| key(&sorted[i * sorted.len() / shard_count]).clone())
+            .collect();
+        let mut rest = sorted;
+        let mut shards = Vec::with_capacity(shard_count);
+        for boundary in boundaries.iter().rev() {
+            let at = rest.partition_point(|x| key(x) < boundary);
+            shards.push(RwLock::new(shard(rest.split_off(at))));
+        }
+        shards.push(RwLock::new(shard(rest)));
+        shards.reverse();
+        Shards { boundaries, shards }
+    }
+    fn shard<Q>(&self, key: &Q) -> &RwLock<S>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        &self.shards[self.boundaries.partition_point(|b| b.borrow() <= key)]
+    }
+    /// A panic while a shard was locked leaves it poisoned; each operation
+    /// leaves the shard sorted even if a comparison panics, so the poison is
+    /// ignored.
+    fn read<Q>(&self, key: &Q) -> RwLockReadGuard<'_, S>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.shard(key).read().unwrap_or_else(|e| e.into_inner())
+    }
+    fn write<Q>(&self, key: &Q) -> RwLockWriteGuard<'_, S>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.shard(key).write().unwrap_or_else(|e| e.into_inner())
+    }
+    /// Lock every shard for reading, in key order.
+    fn read_all(&self) -> Vec<RwLockReadGuard<'_, S>> {
+        self.shards
+            .iter()
+            .map(|s| s.read().unwrap_or_else(|e| e.into_inner()))
+            .collect()
+    }
+    /// Apply `f` to every shard in key order, locking one shard at a time.
+    fn for_each_mut(&self, mut f: impl FnMut(&mut S)) {
+        for s in &self.shards {
+            f(&mut s.write().unwrap_or_else(|e| e.into_inner()))
+        }
+    }
+}
+
+//
+//  impl ConcurrentSortedSet
+//
+
+/// Set of unique elements split into key-range shards that can be accessed
+/// concurrently
+pub struct ConcurrentSortedSet<T: Ord> {
+    shards: Shards<T, SortedSet<T>>,
+}
+
+impl<T: Ord> ConcurrentSortedSet<T> {
+    /// A set with a single shard, so every operation locks the whole set.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_boundaries(Vec::new())
+    }
+    /// A set with one shard per key range between consecutive `boundaries`,
+    /// which are sorted and deduplicated first; `n` distinct boundaries give
+    /// `n + 1` shards.
+    pub fn with_boundaries(boundaries: Vec<T>) -> Self {
+        ConcurrentSortedSet {
+            shards: Shards::new(boundaries, SortedSet::new),
+        }
+    }
+    /// Sort and deduplicate `vec`, then split it into at most `shard_count`
+    /// shards of roughly equal length. The boundaries between the shards are
+    /// copies of the first element of each shard but the first.
+    pub fn from_unsorted(vec: Vec<T>, shard_count: usize) -> Self
+    where
+        T: Clone,
+    {
+        let sorted = SortedSet::from_unsorted(vec).into_vec();
+        ConcurrentSortedSet {
+            shards: Shards::split(
+                sorted,
+                shard_count,
+                |x| x,
+                |v| SortedSet {
+                    set: unsafe { SortedVec::from_unsorted_unchecked(v) },
+                },
+            ),
+        }
+    }
+    #[inline]
+    pub fn shard_count(&self) -> usize {
+        self.shards.shards.len()
+    }
+    /// Insert the element if it is not already present, returning whether it
+    /// was inserted.
+    pub fn insert(&self, element: T) -> bool {
+        self.shards
+            .write(&element)
+            .find_or_insert(element)
+            .is_inserted()
+    }
+    /// Insert the element, returning the equal element it replaced, if any.
+    pub fn replace(&self, element: T) -> Option<T> {
+        self.shards.write(&element).replace(element).1
+    }
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.shards.read(x).binary_search(x).is_ok()
+    }
+    /// A copy of the element equal to `x`.
+    pub fn get<Q>(&self, x: &Q) -> Option<T>
+    where
+        T: Borrow<Q> + Clone,
+        Q: Ord + ?Sized,
+    {
+        let shard = self.shards.read(x);
+        shard.binary_search(x).ok().map(|i| shard[i].clone())
+    }
+    pub fn remove<Q>(&self, x: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.shards.write(x).remove_item(x)
+    }
+    /// Retain only the elements for which `f` returns true. Shards are locked
+    /// one at a time, so other threads may observe a partially filtered set.
+    pub fn retain<F>(&self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.shards.for_each_mut(|s| s.retain(&mut f))
+    }
+    /// Remove all elements, one shard at a time.
+    pub fn clear(&self) {
+        self.shards.for_each_mut(|s| s.clear())
+    }
+    /// Lock every shard for reading. Writers are blocked until the guard is
+    /// dropped.
+    pub fn read(&self) -> SetReadGuard<'_, T> {
+        SetReadGuard {
+            shards: self.shards.read_all(),
+        }
+    }
+    /// Copy the elements into a `SortedSet`.
+    pub fn to_sorted_set(&self) -> SortedSet<T>
+    where
+        T: Clone,
+    {
+        let guard = self.read();
+        let set = unsafe { SortedVec::from_unsorted_unchecked(guard.iter().cloned().collect()) };
+        SortedSet { set }
+    }
+}
+
+impl<T: Ord> Default for ConcurrentSortedSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Ord + std::fmt::Debug> std::fmt::Debug for ConcurrentSortedSet<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_set().entries(self.read().iter()).finish()
+    }
+}
+impl<T: Ord + Clone> From<SortedSet<T>> for ConcurrentSortedSet<T> {
+    /// A single shard holding all of the elements
+    fn from(set: SortedSet<T>) -> Self {
+        Self::from_unsorted(set.into_vec(), 1)
+    }
+}
+
+/// Read lock on every shard of a `ConcurrentSortedSet`
+pub struct SetReadGuard<'a, T: Ord> {
+    shards: Vec<RwLockReadGuard<'a, SortedSet<T>>>,
+}
+
+impl<T: Ord> SetReadGuard<'_, T> {
+    /// Iterator over all elements in sorted order
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.shards.iter().flat_map(|s| s.iter())
+    }
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.len()).sum()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|s| s.is_empty())
+    }
+    pub fn first(&self) -> Option<&T> {
+        self.shards.iter().find_map(|s| s.first())
+    }
+    pub fn last(&self) -> Option<&T> {
+        self.shards.iter().rev().find_map(|s| s.last())
+    }
+}
+
+//
+//  impl ConcurrentSortedMap
+//
+
+/// Map with unique keys split into key-range shards that can be accessed
+/// concurrently
+pub struct ConcurrentSortedMap<K: Ord, V> {
+    shards: Shards<K, SortedVecMap<K, V>>,
+}
+
+impl<K: Ord, V> ConcurrentSortedMap<K, V> {
+    /// A map with a single shard, so every operation locks the whole map.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_boundaries(Vec::new())
+    }
+    /// A map with one shard per key range between consecutive `boundaries`,
+    /// which are sorted and deduplicated first; `n` distinct boundaries give
+    /// `n + 1` shards.
+    pub fn with_boundaries(boundaries: Vec<K>) -> Self {
+        ConcurrentSortedMap {
+            shards: Shards::new(boundaries, SortedVecMap::new),
+        }
+    }
+    /// Sort the pairs by key as `SortedVecMap::from_unsorted` does, then split
+    /// them into at most `shard_count` shards of roughly equal length.
+    pub fn from_unsorted(vec: Vec<(K, V)>, shard_count: usize) -> Self
+    where
+        K: Clone,
+    {
+        let sorted = SortedVecMap::from_unsorted(vec).into_vec();
+        ConcurrentSortedMap {
+            shards: Shards::split(
+                sorted,
+                shard_count,
+                |(k, _)| k,
+                |v| unsafe { SortedVecMap::from_unsorted_unchecked(v) },
+            ),
+        }
+    }
+    #[inline]
+    pub fn shard_count(&self) -> usize {
+        self.shards.shards.len()
+    }
+    /// Insert a key-value pair, returning the previous value of the key.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.shards.write(&key).insert(key, value)
+    }
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.shards.read(key).contains_key(key)
+    }
+    /// A copy of the value of `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        V: Clone,
+    {
+        self.shards.read(key).get(key).cloned()
+    }
+    /// Call `f` with the value of `key` while its shard is locked for reading.
+    pub fn get_with<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        F: FnOnce(&V) -> R,
+    {
+        self.shards.read(key).get(key).map(f)
+    }
+    /// Call `f` with the value of `key` while its shard is locked for writing.
+    pub fn update<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        F: FnOnce(&mut V) -> R,
+    {
+        self.shards.write(key).get_mut(key).map(f)
+    }
+    /// Call `f` with the value of `key`, inserting `default()` first if the
+    /// key is not present, while its shard is locked for writing.
+    pub fn upsert<D, F, R>(&self, key: K, default: D, f: F) -> R
+    where
+        D: FnOnce() -> V,
+        F: FnOnce(&mut V) -> R,
+    {
+        let mut shard = self.shards.write(&key);
+        f(shard.entry(key).or_insert_with(default))
+    }
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.shards.write(key).remove(key)
+    }
+    /// Retain only the pairs for which `f` returns true. Shards are locked one
+    /// at a time, so other threads may observe a partially filtered map.
+    pub fn retain<F>(&self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.shards.for_each_mut(|s| s.retain(&mut f))
+    }
+    /// Remove all pairs, one shard at a time.
+    pub fn clear(&self) {
+        self.shards.for_each_mut(|s| s.clear())
+    }
+    /// Lock every shard for reading. Writers are blocked until the guard is
+    /// dropped.
+    pub fn read(&self) -> MapReadGuard<'_, K, V> {
+        MapReadGuard {
+            shards: self.shards.read_all(),
+        }
+    }
+    /// Copy the pairs into a `SortedVecMap`.
+    pub fn to_sorted_map(&self) -> SortedVecMap<K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let guard = self.read();
+        let pairs = guard.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        unsafe { SortedVecMap::from_unsorted_unchecked(pairs) }
+    }
+}
+
+impl<K: Ord, V> Default for ConcurrentSortedMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<K: Ord + std::fmt::Debug, V: std::fmt::Debug> std::fmt::Debug for ConcurrentSortedMap<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_map().entries(self.read().iter()).finish()
+    }
+}
+impl<K: Ord + Clone, V> From<SortedVecMap<K, V>> for ConcurrentSortedMap<K, V> {
+    /// A single shard holding all of the pairs
+    fn from(map: SortedVecMap<K, V>) -> Self {
+        Self::from_unsorted(map.into_vec(), 1)
+    }
+}
+
+/// Read lock on every shard of a `ConcurrentSortedMap`
+pub struct MapReadGuard<'a, K: Ord, V> {
+    shards: Vec<RwLockReadGuard<'a, SortedVecMap<K, V>>>,
+}
+
+impl<K: Ord, V> MapReadGuard<'_, K, V> {
+    /// Iterator over all pairs in key order
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> {
+        self.shards.iter().flat_map(|s| s.iter())
+    }
+    /// Iterator over all keys in order
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+    /// Iterator over all values in key order
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.len()).sum()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|s| s.is_empty())
+    }
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.shards.iter().find_map(|s| s.get(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_concurrent_set() {
+        let set = ConcurrentSortedSet::from_unsorted(vec![9, 3, 7, 1, 5, 3], 3);
+        assert_eq!(set.shard_count(), 3);
+        assert_eq!(set.shards.boundaries, vec![3, 7]);
+        assert!(set.insert(4));
+        assert!(!set.insert(4));
+        assert_eq!(set.replace(5), Some(5));
+        assert!(set.contains(&7) && !set.contains(&8));
+        assert_eq!(set.get(&9), Some(9));
+        assert_eq!(set.remove(&1), Some(1));
+        assert_eq!(
+            set.read().iter().copied().collect::<Vec<_>>(),
+            vec![3, 4, 5, 7, 9]
+        );
+        set.retain(|x| x % 2 == 1);
+        assert_eq!(set.to_sorted_set()[..], [3, 5, 7, 9]);
+        assert_eq!(
+            (set.read().first(), set.read().last()),
+            (Some(&3), Some(&9))
+        );
+        set.clear();
+        assert!(set.read().is_empty());
+        let few = ConcurrentSortedSet::from_unsorted(vec![1], 8);
+        assert_eq!(few.shard_count(), 1);
+        let empty = ConcurrentSortedSet::<u8>::from_unsorted(vec![], 8);
+        assert!(empty.insert(0));
+    }
+
+    #[test]
+    fn test_concurrent_map() {
+        let map = Arc::new(ConcurrentSortedMap::with_boundaries(vec![
+            "m".to_string(),
+            "g".to_string(),
+        ]));
+        assert_eq!(map.shard_count(), 3);
+        let words = ["apple", "kiwi", "zucchini", "grape", "melon", "fig"];
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let map = Arc::clone(&map);
+                std::thread::spawn(move || {
+                    for w in words {
+                        map.upsert(w.to_string(), || 0, |n| *n += 1);
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(map.get("kiwi"), Some(4));
+        assert_eq!(map.update("fig", |n| std::mem::replace(n, 10)), Some(4));
+        assert_eq!(map.insert("pear".to_string(), 1), None);
+        assert_eq!(map.get_with("fig", |n| n * 2), Some(20));
+        assert_eq!(map.remove("melon"), Some(4));
+        assert!(!map.contains_key("melon"));
+        let guard = map.read();
+        assert_eq!(
+            guard.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+            vec!["apple", "fig", "grape", "kiwi", "pear", "zucchini"]
+        );
+        assert_eq!(guard.get("pear"), Some(&1));
+        drop(guard);
+        map.retain(|_, n| *n > 1);
+        assert_eq!(map.to_sorted_map().len(), 5);
+        let split = ConcurrentSortedMap::from_unsorted(vec![(2, 'b'), (1, 'a'), (2, 'c')], 2);
+        assert_eq!(
+            split.read().iter().collect::<Vec<_>>(),
+            vec![(&1, &'a'), (&2, &'c')]
+        );
+    }
+}
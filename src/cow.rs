@@ -0,0 +1,186 @@
+//! Copy-on-write sorted vector.
+//!
+//! `CowSortedVec<'a, T>` either borrows a sorted slice, such as a static
+//! table, or owns a `SortedVec`. Queries work on either, and the borrowed
+//! elements are only cloned into an owned `SortedVec` on the first mutation.
+//!
+//! ```
+//! use sorted_vec2::cow::CowSortedVec;
+//!
+//! static PRIMES: [u32; 4] = [2, 3, 5, 7];
+//!
+//! let mut primes = CowSortedVec::from_sorted_slice(&PRIMES).unwrap();
+//! assert!(primes.contains(&5));
+//! assert!(primes.is_borrowed());
+//! primes.to_mut().insert(11);
+//! assert!(primes.is_owned());
+//! assert_eq!(*primes, [2, 3, 5, 7, 11]);
+//! assert!(CowSortedVec::from_sorted_slice(&[3, 2]).is_none());
+//! ```
+
+use std::borrow::Borrow;
+
+use crate::SortedVec;
+
+/// Sorted vector that borrows its elements until it is mutated
+#[derive(Clone, Debug)]
+pub struct CowSortedVec<'a, T: Ord> {
+    inner: Inner<'a, T>,
+}
+
+#[derive(Clone, Debug)]
+enum Inner<'a, T: Ord> {
+    Borrowed(&'a [T]),
+    Owned(SortedVec<T>),
+}
+
+impl<'a, T: Ord> CowSortedVec<'a, T> {
+    /// Borrow `slice` if it is sorted, otherwise return `None`. O(n).
+    pub fn from_sorted_slice(slice: &'a [T]) -> Option<Self> {
+        if slice.windows(2).all(|w| w[0] <= w[1]) {
+            Some(CowSortedVec {
+                inner: Inner::Borrowed(slice),
+            })
+        } else {
+            None
+        }
+    }
+    /// # Safety
+    ///
+    /// The caller must ensure that the provided slice is already sorted.
+    #[inline]
+    pub const unsafe fn from_sorted_slice_unchecked(slice: &'a [T]) -> Self {
+        CowSortedVec {
+            inner: Inner::Borrowed(slice),
+        }
+    }
+    #[inline]
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.inner, Inner::Borrowed(_))
+    }
+    #[inline]
+    pub fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+    /// Binary search for `x`, which may be any borrowed form of the element
+    /// type. See `slice::binary_search`.
+    #[inline]
+    pub fn binary_search<Q>(&self, x: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.as_slice().binary_search_by(|y| y.borrow().cmp(x))
+    }
+    /// Binary search for `x`, shadowing the linear `slice::contains` reached
+    /// through `Deref`. O(log n).
+    #[inline]
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(x).is_ok()
+    }
+    /// The elements in sorted order
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        match &self.inner {
+            Inner::Borrowed(slice) => slice,
+            Inner::Owned(vec) => vec.as_slice(),
+        }
+    }
+    /// Mutable access to an owned `SortedVec`, cloning the elements first if
+    /// they are borrowed.
+    pub fn to_mut(&mut self) -> &mut SortedVec<T>
+    where
+        T: Clone,
+    {
+        if let Inner::Borrowed(slice) = self.inner {
+            let vec = unsafe { SortedVec::from_unsorted_unchecked(slice.to_vec()) };
+            self.inner = Inner::Owned(vec);
+        }
+        match &mut self.inner {
+            Inner::Owned(vec) => vec,
+            Inner::Borrowed(_) => unreachable!(),
+        }
+    }
+    /// The owned `SortedVec`, cloning the elements if they are borrowed.
+    pub fn into_owned(self) -> SortedVec<T>
+    where
+        T: Clone,
+    {
+        match self.inner {
+            Inner::Borrowed(slice) => unsafe { SortedVec::from_unsorted_unchecked(slice.to_vec()) },
+            Inner::Owned(vec) => vec,
+        }
+    }
+}
+
+impl<T: Ord> Default for CowSortedVec<'_, T> {
+    fn default() -> Self {
+        CowSortedVec {
+            inner: Inner::Borrowed(&[]),
+        }
+    }
+}
+impl<T: Ord> std::ops::Deref for CowSortedVec<'_, T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+impl<T: Ord> PartialEq for CowSortedVec<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+impl<T: Ord> Eq for CowSortedVec<'_, T> {}
+impl<T: Ord + std::hash::Hash> std::hash::Hash for CowSortedVec<'_, T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+impl<T: Ord> From<SortedVec<T>> for CowSortedVec<'_, T> {
+    fn from(sorted: SortedVec<T>) -> Self {
+        CowSortedVec {
+            inner: Inner::Owned(sorted),
+        }
+    }
+}
+impl<'a, T: Ord> From<&'a SortedVec<T>> for CowSortedVec<'a, T> {
+    fn from(sorted: &'a SortedVec<T>) -> Self {
+        CowSortedVec {
+            inner: Inner::Borrowed(sorted.as_slice()),
+        }
+    }
+}
+impl<'a, 'b, T: Ord> IntoIterator for &'b CowSortedVec<'a, T> {
+    type Item = &'b T;
+    type IntoIter = std::slice::Iter<'b, T>;
+    fn into_iter(self) -> std::slice::Iter<'b, T> {
+        self.as_slice().iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cow() {
+        let v = SortedVec::from_unsorted(vec![4, 2, 8]);
+        let mut cow = CowSortedVec::from(&v);
+        assert!(cow.is_borrowed());
+        assert_eq!(cow.binary_search(&8), Ok(2));
+        assert_eq!(cow, CowSortedVec::from(v.clone()));
+        assert_eq!(cow.clone().into_owned(), v);
+        assert_eq!(cow.to_mut().remove_item(&4), Some(4));
+        assert!(cow.is_owned());
+        assert_eq!(*cow, [2, 8]);
+        assert_eq!(*v, vec![2, 4, 8]);
+        let empty = CowSortedVec::<String>::default();
+        assert!(empty.is_empty() && empty.is_borrowed());
+        assert!(CowSortedVec::from_sorted_slice(&[1, 1, 2]).is_some());
+    }
+}
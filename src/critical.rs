@@ -0,0 +1,169 @@
+//! Interrupt-safe shared sorted set (feature `critical-section`).
+//!
+//! `CriticalSortedSet<T, N>` stores up to `N` unique elements in a fixed
+//! capacity `heapless::Vec` guarded by a `critical_section::Mutex`, so it can
+//! be placed in a `static` and shared between interrupt handlers and the main
+//! loop. Every access runs inside a critical section and never allocates.
+//!
+//! ```
+//! use sorted_vec2::critical::CriticalSortedSet;
+//!
+//! static EVENTS: CriticalSortedSet<u32, 8> = CriticalSortedSet::new();
+//!
+//! assert_eq!(EVENTS.insert(20), Ok(true));
+//! assert_eq!(EVENTS.insert(10), Ok(true));
+//! assert_eq!(EVENTS.insert(10), Ok(false));
+//! assert_eq!(EVENTS.pop_first(), Some(10));
+//! EVENTS.with(|set| assert_eq!(set.as_slice(), [20]));
+//! ```
+
+use std::cell::RefCell;
+
+use critical_section::Mutex;
+
+use crate::storage::Storage;
+
+/// Sorted set of at most `N` elements shared through a critical section
+pub struct CriticalSortedSet<T, const N: usize> {
+    inner: Mutex<RefCell<heapless::Vec<T, N>>>,
+}
+
+/// Access to the set contents for the duration of a critical section
+pub struct LockedSortedSet<'a, T, const N: usize> {
+    vec: &'a mut heapless::Vec<T, N>,
+}
+
+impl<T: Ord, const N: usize> LockedSortedSet<'_, T, N> {
+    /// Insert an element, returning `Ok(false)` if an equal element was already
+    /// present, or returning the element with `Err` if the set is full.
+    pub fn insert(&mut self, element: T) -> Result<bool, T> {
+        self.vec
+            .find_or_insert_sorted(element)
+            .map(|result| result.is_inserted())
+    }
+    pub fn remove(&mut self, element: &T) -> Option<T> {
+        self.vec.remove_sorted(element)
+    }
+    #[inline]
+    pub fn contains(&self, element: &T) -> bool {
+        self.vec.search(element).is_ok()
+    }
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.vec.is_empty() {
+            None
+        } else {
+            Some(self.vec.remove(0))
+        }
+    }
+    #[inline]
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.vec.retain(f)
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.vec.is_full()
+    }
+    /// The elements in sorted order
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+}
+
+impl<T, const N: usize> CriticalSortedSet<T, N> {
+    pub const fn new() -> Self {
+        CriticalSortedSet {
+            inner: Mutex::new(RefCell::new(heapless::Vec::new())),
+        }
+    }
+}
+
+impl<T: Ord, const N: usize> CriticalSortedSet<T, N> {
+    /// Run `f` with exclusive access to the set inside a critical section.
+    ///
+    /// Panics if called re-entrantly from within `f`.
+    pub fn with<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut LockedSortedSet<T, N>) -> R,
+    {
+        critical_section::with(|cs| {
+            let mut vec = self.inner.borrow_ref_mut(cs);
+            f(&mut LockedSortedSet { vec: &mut vec })
+        })
+    }
+    /// See `LockedSortedSet::insert`
+    pub fn insert(&self, element: T) -> Result<bool, T> {
+        self.with(|set| set.insert(element))
+    }
+    pub fn remove(&self, element: &T) -> Option<T> {
+        self.with(|set| set.remove(element))
+    }
+    pub fn contains(&self, element: &T) -> bool {
+        self.with(|set| set.contains(element))
+    }
+    pub fn pop_first(&self) -> Option<T> {
+        self.with(|set| set.pop_first())
+    }
+    pub fn pop_last(&self) -> Option<T> {
+        self.with(|set| set.pop_last())
+    }
+    pub fn clear(&self) {
+        self.with(|set| set.clear())
+    }
+    pub fn len(&self) -> usize {
+        self.with(|set| set.len())
+    }
+    pub fn is_empty(&self) -> bool {
+        self.with(|set| set.is_empty())
+    }
+}
+
+impl<T, const N: usize> Default for CriticalSortedSet<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_critical_sorted_set() {
+        let set = CriticalSortedSet::<i32, 4>::new();
+        assert_eq!(set.insert(3), Ok(true));
+        assert_eq!(set.insert(1), Ok(true));
+        assert_eq!(set.insert(3), Ok(false));
+        assert_eq!(set.insert(2), Ok(true));
+        assert_eq!(set.insert(0), Ok(true));
+        assert_eq!(set.insert(5), Err(5));
+        assert!(set.contains(&2));
+        assert_eq!(set.remove(&2), Some(2));
+        assert_eq!(set.pop_last(), Some(3));
+        set.with(|set| {
+            assert_eq!(set.insert(-1), Ok(true));
+            assert_eq!(set.as_slice(), [-1, 0, 1]);
+        });
+        assert_eq!(set.pop_first(), Some(-1));
+        assert_eq!(set.len(), 2);
+    }
+}
@@ -0,0 +1,73 @@
+//! Deserialization helpers shared by the containers (feature `serde`).
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{Deserializer, Error, SeqAccess, Visitor};
+use serde::Deserialize;
+
+/// Preallocate at most this many bytes from an untrusted size hint
+const MAX_PREALLOCATION: usize = 1024 * 1024;
+
+/// Deserialize a sequence, calling `check` with the previous element (if any)
+/// and each new element as it arrives, and failing on the first error instead
+/// of collecting the whole input first. Fails as soon as the sequence has more
+/// than `max_len` elements.
+pub(crate) fn deserialize_sorted_vec<'de, D, T, F>(
+    deserializer: D,
+    max_len: usize,
+    check: F,
+) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+    F: Fn(Option<&T>, &T) -> Result<(), &'static str>,
+{
+    struct SortedVisitor<T, F> {
+        max_len: usize,
+        check: F,
+        element: PhantomData<T>,
+    }
+
+    impl<'de, T, F> Visitor<'de> for SortedVisitor<T, F>
+    where
+        T: Deserialize<'de>,
+        F: Fn(Option<&T>, &T) -> Result<(), &'static str>,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            if self.max_len == usize::MAX {
+                f.write_str("a sorted sequence")
+            } else {
+                write!(f, "a sorted sequence of at most {} elements", self.max_len)
+            }
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Vec<T>, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let hint = seq.size_hint().unwrap_or(0);
+            if hint > self.max_len {
+                return Err(A::Error::invalid_length(hint, &self));
+            }
+            let cautious = MAX_PREALLOCATION / std::mem::size_of::<T>().max(1);
+            let mut vec: Vec<T> = Vec::with_capacity(hint.min(cautious));
+            while let Some(element) = seq.next_element()? {
+                if vec.len() == self.max_len {
+                    return Err(A::Error::invalid_length(self.max_len + 1, &self));
+                }
+                (self.check)(vec.last(), &element).map_err(A::Error::custom)?;
+                vec.push(element);
+            }
+            Ok(vec)
+        }
+    }
+
+    deserializer.deserialize_seq(SortedVisitor {
+        max_len,
+        check,
+        element: PhantomData,
+    })
+}
@@ -0,0 +1,247 @@
+//! Sorted double-ended queue.
+//!
+//! `SortedDeque<T>` keeps its elements in ascending order in a `VecDeque`, so
+//! both the least and the greatest element are popped in O(1), and inserting
+//! near either end only shifts the elements on that side. This suits sliding
+//! windows where new elements arrive near the back while old ones expire from
+//! the front.
+//!
+//! ```
+//! use sorted_vec2::deque::SortedDeque;
+//!
+//! let mut window = SortedDeque::new();
+//! for t in [100, 105, 103, 110] {
+//!     window.insert(t);
+//! }
+//! while window.front().is_some_and(|t| *t < 104) {
+//!     window.pop_front();
+//! }
+//! assert_eq!(window.iter().copied().collect::<Vec<_>>(), vec![105, 110]);
+//! ```
+
+use std::borrow::Borrow;
+use std::collections::VecDeque;
+
+use crate::storage::{grow, Storage};
+use crate::{FindOrInsert, SortedVec};
+
+/// Double-ended queue sorted in ascending order, may contain duplicates
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SortedDeque<T: Ord> {
+    deque: VecDeque<T>,
+}
+
+impl<T: Ord> SortedDeque<T> {
+    #[inline]
+    pub fn new() -> Self {
+        SortedDeque {
+            deque: VecDeque::new(),
+        }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedDeque {
+            deque: VecDeque::with_capacity(capacity),
+        }
+    }
+    /// Uses `sort_unstable()` to sort in place.
+    #[inline]
+    pub fn from_unsorted(mut vec: Vec<T>) -> Self {
+        vec.sort_unstable();
+        SortedDeque { deque: vec.into() }
+    }
+    /// Insert an element into sorted position, returning the order index at which
+    /// it was placed. Elements are shifted towards whichever end is closer.
+    #[inline]
+    pub fn insert(&mut self, element: T) -> usize {
+        grow(self.deque.insert_sorted(element))
+    }
+    /// Find the element and return the index with `Ok`, otherwise insert the
+    /// element and return the new element index with `Err`.
+    #[inline]
+    pub fn find_or_insert(&mut self, element: T) -> FindOrInsert {
+        grow(self.deque.find_or_insert_sorted(element))
+    }
+    /// Same as insert, except performance is O(1) when the element belongs at
+    /// the front or the back of the container.
+    pub fn push(&mut self, element: T) -> usize {
+        match (self.deque.front(), self.deque.back()) {
+            (_, None) => {
+                self.deque.push_back(element);
+                0
+            }
+            (_, Some(back)) if element >= *back => {
+                self.deque.push_back(element);
+                self.deque.len() - 1
+            }
+            (Some(front), _) if element <= *front => {
+                self.deque.push_front(element);
+                0
+            }
+            _ => self.insert(element),
+        }
+    }
+    /// Reserves additional capacity in the underlying deque.
+    /// See std::collections::VecDeque::reserve.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.deque.reserve(additional);
+    }
+    /// Binary search for `x`, which may be any borrowed form of the element
+    /// type. See `VecDeque::binary_search`.
+    #[inline]
+    pub fn binary_search<Q>(&self, x: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.deque.search(x)
+    }
+    /// Binary search for `x`, shadowing the linear `VecDeque::contains` reached
+    /// through `Deref`. O(log n).
+    #[inline]
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(x).is_ok()
+    }
+    #[inline]
+    pub fn remove_item<Q>(&mut self, item: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.deque.remove_sorted(item)
+    }
+    /// Panics if index is out of bounds
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> T {
+        self.deque.remove(index).expect("index out of bounds")
+    }
+    /// Remove and return the least element. O(1).
+    #[inline]
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+    /// Remove and return the greatest element. O(1).
+    #[inline]
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.deque.pop_back()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.deque.clear()
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.deque.retain(f)
+    }
+    #[inline]
+    pub fn dedup(&mut self) {
+        let mut vec = Vec::from(std::mem::take(&mut self.deque));
+        vec.dedup();
+        self.deque = vec.into();
+    }
+    /// Rearranges the elements so that they are contiguous, returning them in
+    /// sorted order
+    #[inline]
+    pub fn make_contiguous(&mut self) -> &[T] {
+        self.deque.make_contiguous()
+    }
+    /// NOTE: to_vec() is a slice method that is accessible through deref, use
+    /// this instead to avoid cloning
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.deque.into()
+    }
+    #[inline]
+    pub fn into_deque(self) -> VecDeque<T> {
+        self.deque
+    }
+}
+
+impl<T: Ord> Default for SortedDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Ord> std::ops::Deref for SortedDeque<T> {
+    type Target = VecDeque<T>;
+    fn deref(&self) -> &VecDeque<T> {
+        &self.deque
+    }
+}
+impl<T: Ord> Extend<T> for SortedDeque<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for t in iter {
+            let _ = self.push(t);
+        }
+    }
+}
+impl<T: Ord> FromIterator<T> for SortedDeque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_unsorted(iter.into_iter().collect())
+    }
+}
+impl<T: Ord> From<Vec<T>> for SortedDeque<T> {
+    fn from(unsorted: Vec<T>) -> Self {
+        Self::from_unsorted(unsorted)
+    }
+}
+impl<T: Ord> From<SortedVec<T>> for SortedDeque<T> {
+    fn from(sorted: SortedVec<T>) -> Self {
+        SortedDeque {
+            deque: sorted.into_vec().into(),
+        }
+    }
+}
+impl<T: Ord> From<SortedDeque<T>> for SortedVec<T> {
+    fn from(sorted: SortedDeque<T>) -> Self {
+        unsafe { SortedVec::from_unsorted_unchecked(sorted.into_vec()) }
+    }
+}
+impl<T: Ord> IntoIterator for SortedDeque<T> {
+    type Item = T;
+    type IntoIter = std::collections::vec_deque::IntoIter<T>;
+    fn into_iter(self) -> std::collections::vec_deque::IntoIter<T> {
+        self.deque.into_iter()
+    }
+}
+impl<'a, T: Ord> IntoIterator for &'a SortedDeque<T> {
+    type Item = &'a T;
+    type IntoIter = std::collections::vec_deque::Iter<'a, T>;
+    fn into_iter(self) -> std::collections::vec_deque::Iter<'a, T> {
+        self.deque.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sorted_deque() {
+        let mut d: SortedDeque<i32> = vec![5, 1, 3].into_iter().collect();
+        assert_eq!(d.push(7), 3);
+        assert_eq!(d.push(0), 0);
+        assert_eq!(d.push(4), 3);
+        assert_eq!(d.find_or_insert(3), FindOrInsert::Found(2));
+        assert_eq!(d.insert(3), 2);
+        assert_eq!(d.make_contiguous(), [0, 1, 3, 3, 4, 5, 7]);
+        assert_eq!(d.pop_front(), Some(0));
+        assert_eq!(d.pop_back(), Some(7));
+        d.dedup();
+        assert!(d.contains(&4));
+        assert_eq!(d.remove_item(&4), Some(4));
+        assert_eq!(d.remove_index(0), 1);
+        d.extend([9, 2]);
+        let v = SortedVec::from(d);
+        assert_eq!(*v, vec![2, 3, 5, 9]);
+        assert_eq!(SortedDeque::from(v).back(), Some(&9));
+    }
+}
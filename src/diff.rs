@@ -0,0 +1,279 @@
+//! Differences between two sorted containers.
+//!
+//! `SortedVec::diff` compares an old and a new version of a container in a
+//! single merge pass, yielding the elements that were added and removed. With
+//! `Diff::with_unchanged` the elements present in both are yielded as well,
+//! so that the whole new version can be reconstructed in order. Duplicates
+//! are matched one to one.
+//!
+//! ```
+//! use sorted_vec2::diff::DiffOp;
+//! use sorted_vec2::SortedSet;
+//!
+//! let server = SortedSet::from_unsorted(vec![1, 2, 4]);
+//! let client = SortedSet::from_unsorted(vec![2, 3, 4]);
+//! let ops: Vec<_> = server.diff(&client).collect();
+//! assert_eq!(ops, vec![DiffOp::Removed(&1), DiffOp::Added(&3)]);
+//! ```
+//!
+//! `SortedVec::delta` collects the changes into a `SortedVecDelta`, which can
+//! be serialized (feature `serde`) and applied to another copy of the old
+//! version, so that replicas exchange updates instead of full snapshots.
+//!
+//! ```
+//! use sorted_vec2::SortedVec;
+//!
+//! let v1 = SortedVec::from_unsorted(vec![10, 20, 30]);
+//! let v2 = SortedVec::from_unsorted(vec![10, 25, 30, 40]);
+//! let delta = v1.delta(&v2);
+//! assert_eq!(**delta.removed(), vec![20]);
+//! let mut replica = v1.clone();
+//! delta.apply(&mut replica);
+//! assert_eq!(replica, v2);
+//! ```
+
+use std::cmp::Ordering;
+
+use crate::{SortedSet, SortedVec};
+
+/// A change between an old and a new container
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum DiffOp<T> {
+    /// The element is only in the new container
+    Added(T),
+    /// The element is only in the old container
+    Removed(T),
+    /// The element is in both containers
+    Unchanged(T),
+}
+
+impl<T> DiffOp<T> {
+    /// The element that was added, removed or left unchanged
+    pub fn value(&self) -> &T {
+        match self {
+            DiffOp::Added(x) | DiffOp::Removed(x) | DiffOp::Unchanged(x) => x,
+        }
+    }
+    pub fn into_value(self) -> T {
+        match self {
+            DiffOp::Added(x) | DiffOp::Removed(x) | DiffOp::Unchanged(x) => x,
+        }
+    }
+}
+
+/// Iterator over the changes from an old to a new container, in ascending
+/// order of the elements, see `SortedVec::diff`
+#[derive(Clone, Debug)]
+pub struct Diff<'a, T> {
+    old: &'a [T],
+    new: &'a [T],
+    unchanged: bool,
+}
+
+impl<T: Ord> Diff<'_, T> {
+    /// Also yield `DiffOp::Unchanged` for the elements in both containers.
+    pub fn with_unchanged(mut self) -> Self {
+        self.unchanged = true;
+        self
+    }
+}
+
+impl<'a, T: Ord> Iterator for Diff<'a, T> {
+    type Item = DiffOp<&'a T>;
+    fn next(&mut self) -> Option<DiffOp<&'a T>> {
+        loop {
+            let order = match (self.old.first(), self.new.first()) {
+                (Some(old), Some(new)) => old.cmp(new),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => return None,
+            };
+            match order {
+                Ordering::Less => {
+                    let (x, rest) = self.old.split_first().unwrap();
+                    self.old = rest;
+                    return Some(DiffOp::Removed(x));
+                }
+                Ordering::Greater => {
+                    let (x, rest) = self.new.split_first().unwrap();
+                    self.new = rest;
+                    return Some(DiffOp::Added(x));
+                }
+                Ordering::Equal => {
+                    let x = &self.old[0];
+                    self.old = &self.old[1..];
+                    self.new = &self.new[1..];
+                    if self.unchanged {
+                        return Some(DiffOp::Unchanged(x));
+                    }
+                }
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (old, new) = (self.old.len(), self.new.len());
+        let lower = if self.unchanged {
+            old.max(new)
+        } else {
+            old.abs_diff(new)
+        };
+        (lower, Some(old + new))
+    }
+}
+impl<T: Ord> std::iter::FusedIterator for Diff<'_, T> {}
+
+/// Elements inserted into and removed from a sorted container, see
+/// `SortedVec::delta`
+///
+/// Both lists are sorted `SortedVec`s, so a deserialized delta with unsorted
+/// elements is rejected like any other `SortedVec`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SortedVecDelta<T: Ord> {
+    inserted: SortedVec<T>,
+    removed: SortedVec<T>,
+}
+
+impl<T: Ord> SortedVecDelta<T> {
+    #[inline]
+    pub fn new(inserted: SortedVec<T>, removed: SortedVec<T>) -> Self {
+        SortedVecDelta { inserted, removed }
+    }
+    /// The elements to insert
+    #[inline]
+    pub fn inserted(&self) -> &SortedVec<T> {
+        &self.inserted
+    }
+    /// The elements to remove, one occurrence each
+    #[inline]
+    pub fn removed(&self) -> &SortedVec<T> {
+        &self.removed
+    }
+    /// Whether applying the delta changes nothing
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inserted.is_empty() && self.removed.is_empty()
+    }
+    #[inline]
+    pub fn into_parts(self) -> (SortedVec<T>, SortedVec<T>) {
+        (self.inserted, self.removed)
+    }
+    /// Remove one occurrence of each removed element, then merge the inserted
+    /// elements. O(n + m).
+    pub fn apply(self, vec: &mut SortedVec<T>) {
+        vec.remove_items(&self.removed);
+        vec.merge_with(self.inserted)
+    }
+    /// Same as `apply`; inserted elements already in the set are dropped.
+    pub fn apply_to_set(self, set: &mut SortedSet<T>) {
+        set.remove_items(&self.removed);
+        set.merge_with(SortedSet::from_unsorted(self.inserted.into_vec()))
+    }
+}
+
+impl<T: Ord> Default for SortedVecDelta<T> {
+    fn default() -> Self {
+        SortedVecDelta {
+            inserted: SortedVec::new(),
+            removed: SortedVec::new(),
+        }
+    }
+}
+
+impl<T: Ord> SortedVec<T> {
+    /// The `SortedVecDelta` turning `self` into `other`, with clones of the
+    /// added and removed elements.
+    pub fn delta(&self, other: &SortedVec<T>) -> SortedVecDelta<T>
+    where
+        T: Clone,
+    {
+        let mut delta = SortedVecDelta::default();
+        for op in self.diff(other) {
+            match op {
+                DiffOp::Added(x) => delta.inserted.vec.push(x.clone()),
+                DiffOp::Removed(x) => delta.removed.vec.push(x.clone()),
+                DiffOp::Unchanged(_) => {}
+            }
+        }
+        delta
+    }
+    /// Changes turning `self` into `other`: elements only in `other` are
+    /// `Added` and elements only in `self` are `Removed`. Single merge pass,
+    /// no allocation.
+    pub fn diff<'a>(&'a self, other: &'a SortedVec<T>) -> Diff<'a, T> {
+        Diff {
+            old: self,
+            new: other,
+            unchanged: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let old = SortedVec::from_unsorted(vec![1, 2, 2, 5, 7]);
+        let new = SortedVec::from_unsorted(vec![0, 2, 5, 5, 8]);
+        let ops: Vec<_> = old.diff(&new).collect();
+        assert_eq!(
+            ops,
+            vec![
+                DiffOp::Added(&0),
+                DiffOp::Removed(&1),
+                DiffOp::Removed(&2),
+                DiffOp::Added(&5),
+                DiffOp::Removed(&7),
+                DiffOp::Added(&8),
+            ]
+        );
+        let all: Vec<_> = old.diff(&new).with_unchanged().collect();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[2], DiffOp::Unchanged(&2));
+        let rebuilt: Vec<_> = all
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Removed(_)))
+            .map(|op| **op.value())
+            .collect();
+        assert_eq!(rebuilt, *new);
+        assert_eq!(old.diff(&old).next(), None);
+        assert_eq!(old.diff(&new).size_hint(), (0, Some(10)));
+        assert_eq!(DiffOp::Added(3).into_value(), 3);
+    }
+
+    #[test]
+    fn test_delta() {
+        let old = SortedVec::from_unsorted(vec![1, 2, 2, 5, 7]);
+        let new = SortedVec::from_unsorted(vec![0, 2, 5, 5, 8]);
+        let delta = old.delta(&new);
+        assert_eq!(*delta.inserted().clone().into_vec(), [0, 5, 8]);
+        assert_eq!(*delta.removed().clone().into_vec(), [1, 2, 7]);
+        let mut replica = old.clone();
+        delta.apply(&mut replica);
+        assert_eq!(replica, new);
+        assert!(new.delta(&new).is_empty());
+        let mut set = SortedSet::from_unsorted(vec![1, 3]);
+        let delta = SortedVecDelta::new(
+            SortedVec::from_unsorted(vec![3, 4, 4]),
+            SortedVec::from_unsorted(vec![1, 2]),
+        );
+        delta.apply_to_set(&mut set);
+        assert_eq!(set[..], [3, 4]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_delta_serde() {
+        let old = SortedVec::from_unsorted(vec![1, 2, 3]);
+        let new = SortedVec::from_unsorted(vec![2, 3, 4]);
+        let json = serde_json::to_string(&old.delta(&new)).unwrap();
+        let delta: SortedVecDelta<i32> = serde_json::from_str(&json).unwrap();
+        let mut replica = old;
+        delta.apply(&mut replica);
+        assert_eq!(replica, new);
+        let unsorted = r#"{"inserted":[4,1],"removed":[]}"#;
+        assert!(serde_json::from_str::<SortedVecDelta<i32>>(unsorted).is_err());
+    }
+}
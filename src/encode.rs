@@ -0,0 +1,161 @@
+//! `bincode` 2 support (feature `bincode`).
+//!
+//! The containers are encoded like a `Vec`, as a length followed by the
+//! elements. Decoding checks the order of each element against the previous
+//! one as it is decoded, failing on the first element out of order (or
+//! duplicate for the sets).
+//!
+//! ```
+//! use sorted_vec2::SortedSet;
+//!
+//! let config = bincode::config::standard();
+//! let set = SortedSet::from_unsorted(vec![3u32, 1, 2]);
+//! let bytes = bincode::encode_to_vec(&set, config).unwrap();
+//! let (decoded, _): (SortedSet<u32>, usize) =
+//!     bincode::decode_from_slice(&bytes, config).unwrap();
+//! assert_eq!(decoded, set);
+//! let bytes = bincode::encode_to_vec(vec![1u32, 1], config).unwrap();
+//! assert!(bincode::decode_from_slice::<SortedSet<u32>, _>(&bytes, config).is_err());
+//! ```
+
+use std::marker::PhantomData;
+
+use ::bincode::de::{BorrowDecode, BorrowDecoder, Decode, Decoder};
+use ::bincode::enc::{Encode, Encoder};
+use ::bincode::error::{DecodeError, EncodeError};
+
+use crate::{SortOrder, SortedSet, SortedVec};
+
+/// Decode a length prefixed sequence with `decode`, calling `check` with the
+/// previous element (if any) and each new element as it is decoded
+fn decode_sorted<D, T, F, C>(
+    decoder: &mut D,
+    mut decode: F,
+    check: C,
+) -> Result<Vec<T>, DecodeError>
+where
+    D: Decoder,
+    F: FnMut(&mut D) -> Result<T, DecodeError>,
+    C: Fn(Option<&T>, &T) -> Result<(), &'static str>,
+{
+    let len = u64::decode(decoder)?;
+    let len = usize::try_from(len).map_err(|_| DecodeError::OutsideUsizeRange(len))?;
+    decoder.claim_container_read::<T>(len)?;
+    let mut vec: Vec<T> = Vec::with_capacity(len);
+    for _ in 0..len {
+        // the container read was claimed above, as in the `Vec` impl
+        decoder.unclaim_bytes_read(std::mem::size_of::<T>());
+        let element = decode(decoder)?;
+        check(vec.last(), &element).map_err(DecodeError::Other)?;
+        vec.push(element);
+    }
+    Ok(vec)
+}
+
+fn check_vec<T: Ord, O: SortOrder>(prev: Option<&T>, x: &T) -> Result<(), &'static str> {
+    match prev {
+        Some(prev) if O::compare(prev, x).is_gt() => Err("input sequence is not sorted"),
+        _ => Ok(()),
+    }
+}
+
+fn check_set<T: Ord, O: SortOrder>(prev: Option<&T>, x: &T) -> Result<(), &'static str> {
+    match prev.map(|prev| O::compare(prev, x)) {
+        Some(std::cmp::Ordering::Greater) => Err("input set is not sorted"),
+        Some(std::cmp::Ordering::Equal) => Err("input set contains duplicate values"),
+        _ => Ok(()),
+    }
+}
+
+impl<T: Ord + Encode, O: SortOrder> Encode for SortedVec<T, O> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.vec.encode(encoder)
+    }
+}
+
+impl<Context, T: Ord + Decode<Context>, O: SortOrder> Decode<Context> for SortedVec<T, O> {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let vec = decode_sorted(decoder, T::decode, check_vec::<T, O>)?;
+        Ok(SortedVec {
+            vec,
+            order: PhantomData,
+        })
+    }
+}
+
+impl<'de, Context, T, O> BorrowDecode<'de, Context> for SortedVec<T, O>
+where
+    T: Ord + BorrowDecode<'de, Context>,
+    O: SortOrder,
+{
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        let vec = decode_sorted(decoder, T::borrow_decode, check_vec::<T, O>)?;
+        Ok(SortedVec {
+            vec,
+            order: PhantomData,
+        })
+    }
+}
+
+impl<T: Ord + Encode, O: SortOrder> Encode for SortedSet<T, O> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.set.encode(encoder)
+    }
+}
+
+impl<Context, T: Ord + Decode<Context>, O: SortOrder> Decode<Context> for SortedSet<T, O> {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let vec = decode_sorted(decoder, T::decode, check_set::<T, O>)?;
+        Ok(SortedSet {
+            set: SortedVec {
+                vec,
+                order: PhantomData,
+            },
+        })
+    }
+}
+
+impl<'de, Context, T, O> BorrowDecode<'de, Context> for SortedSet<T, O>
+where
+    T: Ord + BorrowDecode<'de, Context>,
+    O: SortOrder,
+{
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        let vec = decode_sorted(decoder, T::borrow_decode, check_set::<T, O>)?;
+        Ok(SortedSet {
+            set: SortedVec {
+                vec,
+                order: PhantomData,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReverseSortedVec;
+    use ::bincode::config;
+
+    #[test]
+    fn test_bincode() {
+        let config = config::standard();
+        let v = SortedVec::from_unsorted(vec![5, -1, 3, 3]);
+        let bytes = ::bincode::encode_to_vec(&v, config).unwrap();
+        assert_eq!(bytes, ::bincode::encode_to_vec(v.to_vec(), config).unwrap());
+        let (decoded, len): (SortedVec<i32>, usize) =
+            ::bincode::decode_from_slice(&bytes, config).unwrap();
+        assert_eq!((decoded, len), (v, bytes.len()));
+        let bytes = ::bincode::encode_to_vec(vec![1, 3, 2], config).unwrap();
+        assert!(::bincode::decode_from_slice::<SortedVec<i32>, _>(&bytes, config).is_err());
+        assert!(::bincode::decode_from_slice::<ReverseSortedVec<i32>, _>(&bytes, config).is_err());
+        let bytes = ::bincode::encode_to_vec(vec!["b", "a"], config).unwrap();
+        let (decoded, _): (ReverseSortedVec<&str>, usize) =
+            ::bincode::borrow_decode_from_slice(&bytes, config).unwrap();
+        assert_eq!(*decoded, vec!["b", "a"]);
+    }
+}
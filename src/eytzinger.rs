@@ -0,0 +1,204 @@
+//! Immutable sorted vectors in Eytzinger layout.
+//!
+//! `SortedVec::into_eytzinger` rearranges the elements into the breadth-first
+//! order of an implicit binary search tree: the root first, then both nodes
+//! of the second level, and so on, with the children of the element at `k`
+//! at `2k + 1` and `2k + 2`. A search then reads the first levels of the tree
+//! from a few contiguous cache lines instead of jumping across the whole
+//! array, which makes lookups in large `FrozenEytzinger<T>`s considerably
+//! faster than binary search on sorted order.
+//!
+//! ```
+//! use sorted_vec2::SortedVec;
+//!
+//! let v = SortedVec::from_unsorted(vec![50, 10, 40, 20, 30]);
+//! let index = v.clone().into_eytzinger();
+//! assert_eq!(index.as_slice(), [40, 20, 50, 10, 30]);
+//! assert!(index.contains(&20));
+//! assert_eq!(index.rank(&35), 3);
+//! assert_eq!(index.into_sorted_vec(), v);
+//! ```
+
+use std::borrow::Borrow;
+
+use crate::SortedVec;
+
+/// Immutable vector in Eytzinger order, may contain duplicates
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FrozenEytzinger<T: Ord> {
+    data: Box<[T]>,
+}
+
+/// Indices of a `len` element Eytzinger array in ascending order of the
+/// elements, i.e. an in-order traversal of the tree
+fn in_order(len: usize) -> Vec<usize> {
+    fn visit(k: usize, len: usize, order: &mut Vec<usize>) {
+        if k < len {
+            visit(2 * k + 1, len, order);
+            order.push(k);
+            visit(2 * k + 2, len, order);
+        }
+    }
+    let mut order = Vec::with_capacity(len);
+    visit(0, len, &mut order);
+    order
+}
+
+/// Number of nodes in the subtree rooted at the one-based index `k`
+fn subtree_len(k: usize, len: usize) -> usize {
+    if k > len {
+        return 0;
+    }
+    // levels from `k` down to the (possibly partial) last level
+    let levels = (usize::BITS - len.leading_zeros()) - (usize::BITS - k.leading_zeros());
+    let last_level_start = k << levels;
+    let last_level = (len + 1).saturating_sub(last_level_start).min(1 << levels);
+    (1 << levels) - 1 + last_level
+}
+
+impl<T: Ord> SortedVec<T> {
+    /// Rearrange the elements into Eytzinger order for faster lookups. O(n).
+    pub fn into_eytzinger(self) -> FrozenEytzinger<T> {
+        let order = in_order(self.len());
+        let mut slots: Vec<Option<T>> = order.iter().map(|_| None).collect();
+        for (k, x) in order.into_iter().zip(self.vec) {
+            slots[k] = Some(x);
+        }
+        FrozenEytzinger {
+            data: slots.into_iter().map(Option::unwrap).collect(),
+        }
+    }
+}
+
+impl<T: Ord> FrozenEytzinger<T> {
+    /// One-based index of the first element that is not less than `x`, or 0
+    fn search<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut k = 1;
+        while k <= self.data.len() {
+            k = 2 * k + (self.data[k - 1].borrow() < x) as usize;
+        }
+        // undo the right turns taken after the last left turn
+        k >> (k.trailing_ones() + 1)
+    }
+    /// The first element equal to `x`. O(log n).
+    pub fn get<Q>(&self, x: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(x) {
+            0 => None,
+            k => Some(&self.data[k - 1]).filter(|y| (*y).borrow() == x),
+        }
+    }
+    /// Whether an element is equal to `x`. O(log n).
+    #[inline]
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(x).is_some()
+    }
+    /// Number of elements strictly less than `x`. O(log n).
+    pub fn rank<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let len = self.data.len();
+        let mut k = self.search(x);
+        if k == 0 {
+            return len;
+        }
+        let mut rank = subtree_len(2 * k, len);
+        while k > 1 {
+            if k % 2 == 1 {
+                // right child: the left sibling's subtree and the parent
+                rank += subtree_len(k - 1, len) + 1;
+            }
+            k /= 2;
+        }
+        rank
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// The elements in Eytzinger order
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+    /// Restore the elements to sorted order. O(n).
+    pub fn into_sorted_vec(self) -> SortedVec<T> {
+        let order = in_order(self.data.len());
+        let mut slots: Vec<Option<T>> = self.data.into_vec().into_iter().map(Some).collect();
+        let vec = order
+            .into_iter()
+            .map(|k| slots[k].take().unwrap())
+            .collect();
+        unsafe { SortedVec::from_unsorted_unchecked(vec) }
+    }
+    /// Copy the elements into a new `SortedVec`. O(n).
+    pub fn to_sorted_vec(&self) -> SortedVec<T>
+    where
+        T: Clone,
+    {
+        let vec = in_order(self.data.len())
+            .into_iter()
+            .map(|k| self.data[k].clone())
+            .collect();
+        unsafe { SortedVec::from_unsorted_unchecked(vec) }
+    }
+}
+
+impl<T: Ord> Default for FrozenEytzinger<T> {
+    fn default() -> Self {
+        SortedVec::new().into_eytzinger()
+    }
+}
+impl<T: Ord> From<SortedVec<T>> for FrozenEytzinger<T> {
+    fn from(sorted: SortedVec<T>) -> Self {
+        sorted.into_eytzinger()
+    }
+}
+impl<T: Ord> From<FrozenEytzinger<T>> for SortedVec<T> {
+    fn from(eytzinger: FrozenEytzinger<T>) -> Self {
+        eytzinger.into_sorted_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eytzinger() {
+        for len in 0..40 {
+            let v = SortedVec::from_unsorted((0..len).map(|x| x / 2 * 2).collect());
+            let e = v.clone().into_eytzinger();
+            assert_eq!(e.len(), v.len());
+            for x in -1..len + 1 {
+                assert_eq!(e.rank(&x), v.rank(&x));
+                assert_eq!(e.contains(&x), v.contains(&x));
+                assert_eq!(e.get(&x), v.find_by_key(&x, |y| *y));
+            }
+            assert_eq!(e.to_sorted_vec(), v);
+            assert_eq!(SortedVec::from(e), v);
+        }
+        let words = SortedVec::from_unsorted(vec!["b".to_string(), "a".to_string()]);
+        let e = FrozenEytzinger::from(words);
+        assert_eq!(e.get("b").map(String::as_str), Some("b"));
+        assert!(!e.contains("c"));
+        assert!(FrozenEytzinger::<i32>::default().is_empty());
+    }
+}
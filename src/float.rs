@@ -0,0 +1,291 @@
+//! Sorted vectors of floats with a configurable NaN policy.
+//!
+//! The `partial` containers panic as soon as a NaN is compared.
+//! `SortedFloatVec<F>` instead orders NaN according to a `NanPolicy` chosen
+//! at construction:
+//!
+//! - `NanLast` (default) -- NaNs are placed after every other value
+//! - `NanFirst` -- NaNs are placed before every other value
+//! - `Reject` -- inserting a NaN returns an `IncomparableError`
+//! - `TotalOrder` -- IEEE 754 totalOrder as implemented by `total_cmp`, which
+//!   also distinguishes `-0.0` from `0.0` and orders NaNs by sign
+//!
+//! ```
+//! use sorted_vec2::float::{NanPolicy, SortedFloatVec};
+//!
+//! let readings = vec![2.5, f64::NAN, -1.0];
+//! let v = SortedFloatVec::from_unsorted(readings.clone(), NanPolicy::NanFirst).unwrap();
+//! assert!(v[0].is_nan());
+//! assert_eq!(v[1..], [-1.0, 2.5]);
+//! let (_, index) = SortedFloatVec::from_unsorted(readings, NanPolicy::Reject).unwrap_err();
+//! assert_eq!(index, 1);
+//! ```
+//!
+//! `SortedF64Vec` and `SortedF32Vec` are `SortedVecBy` containers ordered by
+//! the `TotalCmp` comparator, i.e. always by `total_cmp`, for panic-free
+//! sorted floats without a wrapper type:
+//!
+//! ```
+//! use sorted_vec2::float::SortedF64Vec;
+//!
+//! let mut v: SortedF64Vec = vec![1.5, f64::NAN, -2.0].into_iter().collect();
+//! v.insert(0.0);
+//! assert_eq!(v[..3], [-2.0, 0.0, 1.5]);
+//! assert!(v.contains(&f64::NAN));
+//! ```
+
+use std::cmp::Ordering;
+use std::ops::{Add, Sub};
+
+use crate::compare::{Compare, SortedVecBy};
+use crate::partial::{search_approx, within_range, IncomparableError};
+
+/// Floating point types supported by `SortedFloatVec`
+pub trait Float: Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> {
+    fn is_nan(self) -> bool;
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+impl Float for f32 {
+    #[inline]
+    fn is_nan(self) -> bool {
+        f32::is_nan(self)
+    }
+    #[inline]
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f32::total_cmp(self, other)
+    }
+}
+
+impl Float for f64 {
+    #[inline]
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+    #[inline]
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+}
+
+/// How NaN values are ordered, see the module documentation
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum NanPolicy {
+    #[default]
+    NanLast,
+    NanFirst,
+    Reject,
+    TotalOrder,
+}
+
+impl NanPolicy {
+    /// Compare two floats according to the policy. `Reject` compares like
+    /// `NanLast`; it only differs in refusing to store NaNs.
+    pub fn compare<F: Float>(self, a: &F, b: &F) -> Ordering {
+        match self {
+            NanPolicy::TotalOrder => a.total_cmp(b),
+            NanPolicy::NanLast | NanPolicy::Reject => match (a.is_nan(), b.is_nan()) {
+                (false, false) => a.partial_cmp(b).unwrap(),
+                (a_nan, b_nan) => a_nan.cmp(&b_nan),
+            },
+            NanPolicy::NanFirst => match (a.is_nan(), b.is_nan()) {
+                (false, false) => a.partial_cmp(b).unwrap(),
+                (a_nan, b_nan) => b_nan.cmp(&a_nan),
+            },
+        }
+    }
+}
+
+/// Comparator ordering floats with `total_cmp`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct TotalCmp;
+
+impl<F: Float> Compare<F> for TotalCmp {
+    #[inline]
+    fn compare(&self, a: &F, b: &F) -> Ordering {
+        a.total_cmp(b)
+    }
+}
+
+/// Vector of `f64` sorted with `total_cmp`, may contain duplicates
+pub type SortedF64Vec = SortedVecBy<f64, TotalCmp>;
+/// Vector of `f32` sorted with `total_cmp`, may contain duplicates
+pub type SortedF32Vec = SortedVecBy<f32, TotalCmp>;
+
+/// Vector of floats sorted according to a `NanPolicy`, may contain duplicates
+#[derive(Clone, Debug, PartialEq)]
+pub struct SortedFloatVec<F: Float> {
+    vec: Vec<F>,
+    policy: NanPolicy,
+}
+
+impl<F: Float> SortedFloatVec<F> {
+    #[inline]
+    pub fn new(policy: NanPolicy) -> Self {
+        SortedFloatVec {
+            vec: Vec::new(),
+            policy,
+        }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize, policy: NanPolicy) -> Self {
+        SortedFloatVec {
+            vec: Vec::with_capacity(capacity),
+            policy,
+        }
+    }
+    /// Uses `sort_unstable_by()` to sort in place.
+    ///
+    /// With `NanPolicy::Reject`, if the input contains a NaN it is returned
+    /// unchanged together with the index of the first NaN.
+    pub fn from_unsorted(mut vec: Vec<F>, policy: NanPolicy) -> Result<Self, (Vec<F>, usize)> {
+        if policy == NanPolicy::Reject {
+            if let Some(index) = vec.iter().position(|x| x.is_nan()) {
+                return Err((vec, index));
+            }
+        }
+        vec.sort_unstable_by(|a, b| policy.compare(a, b));
+        Ok(SortedFloatVec { vec, policy })
+    }
+    #[inline]
+    pub fn policy(&self) -> NanPolicy {
+        self.policy
+    }
+    #[inline]
+    pub fn binary_search(&self, x: &F) -> Result<usize, usize> {
+        self.vec.binary_search_by(|y| self.policy.compare(y, x))
+    }
+    #[inline]
+    pub fn contains(&self, x: &F) -> bool {
+        self.binary_search(x).is_ok()
+    }
+    /// Index of the element closest to `x` among the elements within `epsilon`
+    /// of it with `Ok`, otherwise the index where `x` would be inserted with
+    /// `Err`. No element is within a NaN of `x` or within `epsilon` of NaN.
+    pub fn binary_search_approx(&self, x: F, epsilon: F) -> Result<usize, usize> {
+        if x.is_nan() || epsilon.is_nan() {
+            let at = self
+                .vec
+                .partition_point(|y| self.policy.compare(y, &x).is_lt());
+            return Err(at);
+        }
+        search_approx(&self.vec, x, epsilon, |a, b| self.policy.compare(a, b))
+    }
+    /// The elements within `epsilon` of `x`, empty if either is NaN.
+    pub fn find_within(&self, x: F, epsilon: F) -> &[F] {
+        if x.is_nan() || epsilon.is_nan() {
+            return &[];
+        }
+        &self.vec[within_range(&self.vec, x, epsilon, |a, b| self.policy.compare(a, b))]
+    }
+    /// Insert an element into sorted position, returning the order index at
+    /// which it was placed, or an error if the element is a NaN and the policy
+    /// is `Reject`.
+    pub fn insert(&mut self, element: F) -> Result<usize, IncomparableError> {
+        if self.policy == NanPolicy::Reject && element.is_nan() {
+            return Err(IncomparableError);
+        }
+        let insert_at = match self.binary_search(&element) {
+            Ok(insert_at) | Err(insert_at) => insert_at,
+        };
+        self.vec.insert(insert_at, element);
+        Ok(insert_at)
+    }
+    #[inline]
+    pub fn remove_item(&mut self, item: &F) -> Option<F> {
+        match self.binary_search(item) {
+            Ok(remove_at) => Some(self.vec.remove(remove_at)),
+            Err(_) => None,
+        }
+    }
+    /// Panics if index is out of bounds
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> F {
+        self.vec.remove(index)
+    }
+    #[inline]
+    pub fn pop(&mut self) -> Option<F> {
+        self.vec.pop()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    #[inline]
+    pub fn retain<P>(&mut self, f: P)
+    where
+        P: FnMut(&F) -> bool,
+    {
+        self.vec.retain(f)
+    }
+    /// NOTE: to_vec() is a slice method that is accessible through deref, use
+    /// this instead to avoid cloning
+    #[inline]
+    pub fn into_vec(self) -> Vec<F> {
+        self.vec
+    }
+}
+
+impl<F: Float> Default for SortedFloatVec<F> {
+    fn default() -> Self {
+        Self::new(NanPolicy::default())
+    }
+}
+
+impl<F: Float> std::ops::Deref for SortedFloatVec<F> {
+    type Target = Vec<F>;
+    fn deref(&self) -> &Vec<F> {
+        &self.vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nan_policy() {
+        let input = vec![1.0, f64::NAN, -0.0, 0.0, -f64::NAN];
+        let v = SortedFloatVec::from_unsorted(input.clone(), NanPolicy::NanLast).unwrap();
+        assert_eq!(v[..3], [-0.0, 0.0, 1.0]);
+        assert!(v[3..].iter().all(|x| x.is_nan()));
+        let v = SortedFloatVec::from_unsorted(input.clone(), NanPolicy::TotalOrder).unwrap();
+        assert!(v[0].is_nan() && v[0].is_sign_negative());
+        assert!(v[1].is_sign_negative() && v[2].is_sign_positive());
+        assert_eq!(v[3], 1.0);
+        assert!(v[4].is_nan());
+        assert!(SortedFloatVec::from_unsorted(input, NanPolicy::Reject).is_err());
+        let mut v = SortedFloatVec::new(NanPolicy::NanFirst);
+        assert_eq!(v.insert(2.0f32), Ok(0));
+        assert_eq!(v.insert(f32::NAN), Ok(0));
+        assert_eq!(v.insert(1.0), Ok(1));
+        assert!(v.contains(&f32::NAN));
+        assert!(v.remove_item(&f32::NAN).unwrap().is_nan());
+        assert_eq!(*v, vec![1.0, 2.0]);
+        let mut v = SortedFloatVec::new(NanPolicy::Reject);
+        assert_eq!(v.insert(f32::NAN), Err(IncomparableError));
+        assert_eq!(v.binary_search(&f32::NAN), Err(0));
+    }
+
+    #[test]
+    fn test_approx() {
+        let v =
+            SortedFloatVec::from_unsorted(vec![f64::NAN, 1.0, 2.0], NanPolicy::NanFirst).unwrap();
+        assert_eq!(v.binary_search_approx(1.9, 0.2), Ok(2));
+        assert_eq!(v.binary_search_approx(1.4, 0.2), Err(2));
+        assert_eq!(v.binary_search_approx(f64::NAN, 1.0), Err(0));
+        assert_eq!(v.find_within(1.5, 0.5), [1.0, 2.0]);
+        assert!(v.find_within(1.5, f64::NAN).is_empty());
+    }
+
+    #[test]
+    fn test_total_cmp() {
+        let mut v = SortedF32Vec::from_unsorted(vec![0.0, -0.0, f32::NAN, -1.0], TotalCmp);
+        assert_eq!(v.insert(f32::INFINITY), 3);
+        assert!(v[1].is_sign_negative() && v[2].is_sign_positive());
+        assert_eq!(v.binary_search(&0.0), Ok(2));
+        assert!(v.remove_item(&f32::NAN).unwrap().is_nan());
+        assert_eq!(*v, vec![-1.0, -0.0, 0.0, f32::INFINITY]);
+        assert!(SortedF64Vec::default().is_empty());
+    }
+}
@@ -0,0 +1,62 @@
+//! `defmt` support (feature `defmt`).
+//!
+//! The containers implement `defmt::Format` by formatting their elements as a
+//! slice, so they can be logged directly:
+//!
+//! ```ignore
+//! let v = sorted_vec2::SortedVec::from_unsorted(vec![3u8, 1, 2]);
+//! defmt::info!("readings: {}", v);
+//! ```
+
+use ::defmt::{Format, Formatter};
+
+use crate::{partial, PriorityQueue, SortOrder, SortedSet, SortedVec};
+
+impl<T: Ord + Format, O: SortOrder> Format for SortedVec<T, O> {
+    fn format(&self, f: Formatter) {
+        ::defmt::write!(f, "{=[?]}", self.as_slice())
+    }
+}
+
+impl<T: Ord + Format, O: SortOrder> Format for SortedSet<T, O> {
+    fn format(&self, f: Formatter) {
+        ::defmt::write!(f, "{=[?]}", self.as_slice())
+    }
+}
+
+impl<T: Ord + Format> Format for PriorityQueue<T> {
+    fn format(&self, f: Formatter) {
+        ::defmt::write!(f, "{=[?]}", self.as_slice())
+    }
+}
+
+macro_rules! impl_partial {
+    ($($container:ident),*) => {
+        $(
+            impl<T: PartialOrd + Format> Format for partial::$container<T> {
+                fn format(&self, f: Formatter) {
+                    ::defmt::write!(f, "{=[?]}", self.as_slice())
+                }
+            }
+        )*
+    };
+}
+
+impl_partial!(SortedVec, SortedSet, ReverseSortedVec, ReverseSortedSet);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReverseSortedSet;
+
+    fn assert_format<T: Format>() {}
+
+    #[test]
+    fn test_format() {
+        // formatting needs a `defmt` logger, only check the implementations
+        assert_format::<SortedVec<u8>>();
+        assert_format::<ReverseSortedSet<u16>>();
+        assert_format::<PriorityQueue<u32>>();
+        assert_format::<partial::ReverseSortedSet<f32>>();
+    }
+}
@@ -0,0 +1,256 @@
+//! Immutable shared snapshots of sorted vectors.
+//!
+//! `SortedVec::freeze` moves the elements into an `Arc<[T]>`, producing a
+//! `FrozenSortedVec<T>` that is cloned in O(1) and is `Send + Sync` when `T`
+//! is, so an index built once can be shared between threads. All of the
+//! read-only queries of `SortedVec` are available on the snapshot.
+//!
+//! ```
+//! use sorted_vec2::SortedVec;
+//!
+//! let index = SortedVec::from_unsorted(vec![30, 10, 20]).freeze();
+//! let shared = index.clone();
+//! let handle = std::thread::spawn(move || shared.binary_search(&20));
+//! assert_eq!(handle.join().unwrap(), Ok(1));
+//! assert_eq!(index.floor(&25), Some(&20));
+//! ```
+
+use std::borrow::Borrow;
+use std::sync::Arc;
+
+use crate::{search, Ascending, Iter, SortedVec};
+
+/// Immutable vector sorted in ascending order, may contain duplicates, that
+/// shares its elements between clones
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct FrozenSortedVec<T: Ord> {
+    slice: Arc<[T]>,
+}
+
+impl<T: Ord> SortedVec<T> {
+    /// Move the elements into an immutable snapshot that is cheap to clone.
+    #[inline]
+    pub fn freeze(self) -> FrozenSortedVec<T> {
+        FrozenSortedVec {
+            slice: self.vec.into(),
+        }
+    }
+}
+
+impl<T: Ord> FrozenSortedVec<T> {
+    /// Binary search for `x`, which may be any borrowed form of the element
+    /// type. See `slice::binary_search`.
+    #[inline]
+    pub fn binary_search<Q>(&self, x: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.slice.binary_search_by(|y| y.borrow().cmp(x))
+    }
+    /// Binary search for `x`, shadowing the linear `slice::contains` reached
+    /// through `Deref`. O(log n).
+    #[inline]
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(x).is_ok()
+    }
+    /// Iterator over the elements whose values fall within `range`. See
+    /// `SortedVec::range`.
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: std::ops::RangeBounds<Q>,
+    {
+        Iter {
+            iter: self.slice[search::range_indices::<Ascending, T, Q, R>(&self.slice, range)]
+                .iter(),
+            order: std::marker::PhantomData,
+        }
+    }
+    /// Binary search by a key extracted from each element. See
+    /// `SortedVec::binary_search_by_key`.
+    #[inline]
+    pub fn binary_search_by_key<B, F>(&self, b: &B, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> B,
+        B: Ord,
+    {
+        self.slice.binary_search_by_key(b, f)
+    }
+    /// An element whose key is `b`. See `binary_search_by_key`.
+    #[inline]
+    pub fn find_by_key<B, F>(&self, b: &B, f: F) -> Option<&T>
+    where
+        F: FnMut(&T) -> B,
+        B: Ord,
+    {
+        self.binary_search_by_key(b, f).ok().map(|i| &self.slice[i])
+    }
+    /// Index of the first element that is not less than `x`.
+    #[inline]
+    pub fn lower_bound<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::lower_bound::<Ascending, T, Q>(&self.slice, x)
+    }
+    /// Index of the first element that is greater than `x`.
+    #[inline]
+    pub fn upper_bound<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::upper_bound::<Ascending, T, Q>(&self.slice, x)
+    }
+    /// Index of the first element for which `pred` is false. See
+    /// `slice::partition_point`.
+    #[inline]
+    pub fn partition_point<P>(&self, pred: P) -> usize
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.slice.partition_point(pred)
+    }
+    /// Number of elements strictly less than `x`. O(log n).
+    #[inline]
+    pub fn rank<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.lower_bound(x)
+    }
+    /// Number of elements less than or equal to `x`. O(log n).
+    #[inline]
+    pub fn rank_inclusive<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.upper_bound(x)
+    }
+    /// The greatest element less than or equal to `x`. O(log n).
+    #[inline]
+    pub fn floor<Q>(&self, x: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::floor::<Ascending, T, Q>(&self.slice, x)
+    }
+    /// The least element greater than or equal to `x`. O(log n).
+    #[inline]
+    pub fn ceiling<Q>(&self, x: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::ceiling::<Ascending, T, Q>(&self.slice, x)
+    }
+    /// Number of elements equal to `x`. O(log n).
+    #[inline]
+    pub fn count<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.equal_range_indices(x).len()
+    }
+    /// The slice of all elements equal to `x`, empty if there are none.
+    #[inline]
+    pub fn equal_range<Q>(&self, x: &Q) -> &[T]
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        &self.slice[self.equal_range_indices(x)]
+    }
+    /// Index range of the elements equal to `x`. See
+    /// `SortedVec::equal_range_indices`.
+    pub fn equal_range_indices<Q>(&self, x: &Q) -> std::ops::Range<usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::equal_range_indices::<Ascending, T, Q>(&self.slice, x)
+    }
+    /// The elements in sorted order
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.slice
+    }
+    /// The shared elements in sorted order
+    #[inline]
+    pub fn as_arc(&self) -> &Arc<[T]> {
+        &self.slice
+    }
+    /// Copy the elements into a new mutable `SortedVec`.
+    pub fn to_sorted_vec(&self) -> SortedVec<T>
+    where
+        T: Clone,
+    {
+        unsafe { SortedVec::from_unsorted_unchecked(self.slice.to_vec()) }
+    }
+}
+
+impl<T: Ord> Clone for FrozenSortedVec<T> {
+    /// O(1), the elements are shared
+    fn clone(&self) -> Self {
+        FrozenSortedVec {
+            slice: Arc::clone(&self.slice),
+        }
+    }
+}
+impl<T: Ord> Default for FrozenSortedVec<T> {
+    fn default() -> Self {
+        SortedVec::new().freeze()
+    }
+}
+impl<T: Ord> std::ops::Deref for FrozenSortedVec<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        &self.slice
+    }
+}
+impl<T: Ord> From<SortedVec<T>> for FrozenSortedVec<T> {
+    fn from(sorted: SortedVec<T>) -> Self {
+        sorted.freeze()
+    }
+}
+impl<'a, T: Ord> IntoIterator for &'a FrozenSortedVec<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+    fn into_iter(self) -> std::slice::Iter<'a, T> {
+        self.slice.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freeze() {
+        let v = SortedVec::from_unsorted(vec![5, 1, 3, 3, 9]);
+        let frozen = v.clone().freeze();
+        let clone = frozen.clone();
+        assert!(Arc::ptr_eq(frozen.as_arc(), clone.as_arc()));
+        assert_eq!(*frozen, *v);
+        assert_eq!(frozen.binary_search(&9), v.binary_search(&9));
+        assert_eq!(frozen.range(2..=5).collect::<Vec<_>>(), vec![&3, &3, &5]);
+        assert_eq!(frozen.range(6..).collect::<Vec<_>>(), vec![&9]);
+        assert_eq!(frozen.equal_range(&3), [3, 3]);
+        assert_eq!(frozen.count(&4), 0);
+        assert_eq!((frozen.floor(&4), frozen.ceiling(&4)), (Some(&3), Some(&5)));
+        assert_eq!(frozen.find_by_key(&10, |x| x * 2), Some(&5));
+        assert_eq!(frozen.to_sorted_vec(), v);
+        assert!(FrozenSortedVec::<i32>::default().is_empty());
+    }
+}
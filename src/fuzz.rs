@@ -0,0 +1,90 @@
+//! `arbitrary` support for fuzzing (feature `arbitrary`).
+//!
+//! The containers are generated as an arbitrary `Vec` that is then sorted (and
+//! deduplicated for the sets), so every generated value upholds the container
+//! invariants. The `partial` containers reject input with incomparable
+//! elements (e.g. NaN) with `Error::IncorrectFormat` rather than panicking.
+//!
+//! ```
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use sorted_vec2::SortedSet;
+//!
+//! let mut u = Unstructured::new(&[4, 0, 3, 0, 3, 0, 1, 0]);
+//! let set = SortedSet::<u16>::arbitrary(&mut u).unwrap();
+//! assert!(set.windows(2).all(|w| w[0] < w[1]));
+//! ```
+
+use ::arbitrary::{Arbitrary, Error, Result, Unstructured};
+
+use crate::{partial, SortOrder, SortedSet, SortedVec};
+
+impl<'a, T: Ord + Arbitrary<'a>, O: SortOrder> Arbitrary<'a> for SortedVec<T, O> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::sort_in_order(Vec::arbitrary(u)?))
+    }
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        Ok(Self::sort_in_order(Vec::arbitrary_take_rest(u)?))
+    }
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        Vec::<T>::size_hint(depth)
+    }
+}
+
+impl<'a, T: Ord + Arbitrary<'a>, O: SortOrder> Arbitrary<'a> for SortedSet<T, O> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut set = SortedVec::arbitrary(u)?;
+        set.dedup();
+        Ok(SortedSet { set })
+    }
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        let mut set = SortedVec::arbitrary_take_rest(u)?;
+        set.dedup();
+        Ok(SortedSet { set })
+    }
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        Vec::<T>::size_hint(depth)
+    }
+}
+
+macro_rules! impl_partial {
+    ($($container:ident),*) => {
+        $(
+            impl<'a, T: PartialOrd + Arbitrary<'a>> Arbitrary<'a> for partial::$container<T> {
+                fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                    partial::$container::try_from_unsorted(Vec::arbitrary(u)?)
+                        .map_err(|_| Error::IncorrectFormat)
+                }
+                fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+                    partial::$container::try_from_unsorted(Vec::arbitrary_take_rest(u)?)
+                        .map_err(|_| Error::IncorrectFormat)
+                }
+                fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                    Vec::<T>::size_hint(depth)
+                }
+            }
+        )*
+    };
+}
+
+impl_partial!(SortedVec, SortedSet, ReverseSortedVec, ReverseSortedSet);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReverseSortedVec;
+
+    #[test]
+    fn test_arbitrary() {
+        let bytes: Vec<u8> = (0..=255).rev().chain(0..=255).collect();
+        let v = ReverseSortedVec::<u8>::arbitrary_take_rest(Unstructured::new(&bytes)).unwrap();
+        assert!(v.windows(2).all(|w| w[0] >= w[1]));
+        let s = SortedSet::<u8>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert!(s.windows(2).all(|w| w[0] < w[1]));
+        // every float generated from these bytes is a NaN
+        let bytes = [0xff; 64];
+        match partial::SortedVec::<f64>::arbitrary_take_rest(Unstructured::new(&bytes)) {
+            Ok(v) => assert!(v.is_empty()),
+            Err(err) => assert!(matches!(err, Error::IncorrectFormat)),
+        }
+    }
+}
@@ -0,0 +1,352 @@
+//! Sorted containers over `heapless::Vec` (feature `heapless`).
+//!
+//! `SortedVec<T, N>` and `SortedSet<T, N>` keep at most `N` elements in
+//! ascending order in a `heapless::Vec`, so firmware can maintain small sorted
+//! tables, e.g. of scheduled timers, without an allocator. Insertion is
+//! fallible and returns the element with `Err` when the container is full.
+//!
+//! ```
+//! use sorted_vec2::heapless::SortedSet;
+//!
+//! let mut timers = SortedSet::<u32, 4>::new();
+//! assert_eq!(timers.try_insert(300), Ok(true));
+//! assert_eq!(timers.try_insert(100), Ok(true));
+//! assert_eq!(timers.try_insert(100), Ok(false));
+//! assert_eq!(timers.first(), Some(&100));
+//! assert_eq!(timers.pop_first(), Some(100));
+//! ```
+
+use core::borrow::Borrow;
+
+use crate::storage::Storage;
+use crate::FindOrInsert;
+
+/// Vector of at most `N` elements sorted in ascending order, may contain
+/// duplicates
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SortedVec<T: Ord, const N: usize> {
+    vec: ::heapless::Vec<T, N>,
+}
+
+/// Set of at most `N` elements sorted in ascending order
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SortedSet<T: Ord, const N: usize> {
+    set: SortedVec<T, N>,
+}
+
+//
+//  impl SortedVec
+//
+
+impl<T: Ord, const N: usize> SortedVec<T, N> {
+    pub const fn new() -> Self {
+        SortedVec {
+            vec: ::heapless::Vec::new(),
+        }
+    }
+    /// Uses `sort_unstable()` to sort in place.
+    #[inline]
+    pub fn from_unsorted(mut vec: ::heapless::Vec<T, N>) -> Self {
+        vec.sort_unstable();
+        SortedVec { vec }
+    }
+    /// # Safety
+    ///
+    /// The caller must ensure that the provided vector is already sorted.
+    #[inline]
+    pub unsafe fn from_unsorted_unchecked(vec: ::heapless::Vec<T, N>) -> Self {
+        SortedVec { vec }
+    }
+    /// Insert an element into sorted position, returning the order index at which
+    /// it was placed, or returning the element with `Err` if the vector is full.
+    #[inline]
+    pub fn try_insert(&mut self, element: T) -> Result<usize, T> {
+        self.vec.insert_sorted(element)
+    }
+    /// Find the element and return the index with `Ok`, otherwise insert the
+    /// element and return the new element index with `Err`. If the element
+    /// is not found and the vector is full it is returned with `Err`.
+    #[inline]
+    pub fn try_find_or_insert(&mut self, element: T) -> Result<FindOrInsert, T> {
+        self.vec.find_or_insert_sorted(element)
+    }
+    /// Same as `try_insert`, except performance is O(1) when the element
+    /// belongs at the back of the container.
+    #[inline]
+    pub fn try_push(&mut self, element: T) -> Result<usize, T> {
+        self.vec.push_sorted(element)
+    }
+    /// Binary search for `x`, which may be any borrowed form of the element
+    /// type. See `slice::binary_search`.
+    #[inline]
+    pub fn binary_search<Q>(&self, x: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.search(x)
+    }
+    /// Binary search for `x`, shadowing the linear `slice::contains` reached
+    /// through `Deref`. O(log n).
+    #[inline]
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(x).is_ok()
+    }
+    #[inline]
+    pub fn remove_item<Q>(&mut self, item: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.remove_sorted(item)
+    }
+    /// Panics if index is out of bounds
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> T {
+        self.vec.remove(index)
+    }
+    /// Remove and return the least element
+    #[inline]
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.vec.is_empty() {
+            None
+        } else {
+            Some(self.vec.remove(0))
+        }
+    }
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    pub fn dedup(&mut self) {
+        if self.vec.is_empty() {
+            return;
+        }
+        // move the first of each run of equal elements to the front, leaving
+        // the duplicates at the back to be truncated
+        let mut write = 0;
+        for read in 1..self.vec.len() {
+            if self.vec[read] != self.vec[write] {
+                write += 1;
+                self.vec.swap(write, read);
+            }
+        }
+        self.vec.truncate(write + 1);
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.vec.retain(f)
+    }
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.vec.is_full()
+    }
+    /// NOTE: to_vec() is a slice method that is accessible through deref, use
+    /// this instead to avoid cloning
+    #[inline]
+    pub fn into_vec(self) -> ::heapless::Vec<T, N> {
+        self.vec
+    }
+}
+
+impl<T: Ord, const N: usize> Default for SortedVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Ord, const N: usize> core::ops::Deref for SortedVec<T, N> {
+    type Target = ::heapless::Vec<T, N>;
+    fn deref(&self) -> &::heapless::Vec<T, N> {
+        &self.vec
+    }
+}
+impl<T: Ord, const N: usize> From<::heapless::Vec<T, N>> for SortedVec<T, N> {
+    fn from(unsorted: ::heapless::Vec<T, N>) -> Self {
+        Self::from_unsorted(unsorted)
+    }
+}
+impl<T: Ord, const N: usize> From<SortedVec<T, N>> for ::heapless::Vec<T, N> {
+    fn from(sorted: SortedVec<T, N>) -> Self {
+        sorted.into_vec()
+    }
+}
+impl<T: Ord, const N: usize> IntoIterator for SortedVec<T, N> {
+    type Item = T;
+    type IntoIter = <::heapless::Vec<T, N> as IntoIterator>::IntoIter;
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+impl<'a, T: Ord, const N: usize> IntoIterator for &'a SortedVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+    fn into_iter(self) -> core::slice::Iter<'a, T> {
+        self.vec.iter()
+    }
+}
+
+//
+//  impl SortedSet
+//
+
+impl<T: Ord, const N: usize> SortedSet<T, N> {
+    pub const fn new() -> Self {
+        SortedSet {
+            set: SortedVec::new(),
+        }
+    }
+    /// Uses `sort_unstable()` to sort in place and `dedup()` to remove
+    /// duplicates.
+    #[inline]
+    pub fn from_unsorted(vec: ::heapless::Vec<T, N>) -> Self {
+        let mut set = SortedVec::from_unsorted(vec);
+        set.dedup();
+        SortedSet { set }
+    }
+    /// # Safety
+    ///
+    /// The caller must ensure that the provided vector is already sorted and
+    /// deduplicated.
+    #[inline]
+    pub unsafe fn from_unsorted_unchecked(vec: ::heapless::Vec<T, N>) -> Self {
+        SortedSet {
+            set: SortedVec::from_unsorted_unchecked(vec),
+        }
+    }
+    /// Insert an element, returning `Ok(false)` if an equal element was already
+    /// present, or returning the element with `Err` if the set is full.
+    pub fn try_insert(&mut self, element: T) -> Result<bool, T> {
+        self.set
+            .try_find_or_insert(element)
+            .map(|result| result.is_inserted())
+    }
+    /// Insert an element into sorted position, returning the order index at which
+    /// it was placed and the existing item if one was found, or returning the
+    /// element with `Err` if it is not present and the set is full.
+    pub fn try_replace(&mut self, element: T) -> Result<(usize, Option<T>), T> {
+        self.set.vec.replace_sorted(element)
+    }
+    /// Find the element and return the index with `Ok`, otherwise insert the
+    /// element and return the new element index with `Err`. If the element
+    /// is not found and the set is full it is returned with `Err`.
+    #[inline]
+    pub fn try_find_or_insert(&mut self, element: T) -> Result<FindOrInsert, T> {
+        self.set.try_find_or_insert(element)
+    }
+    #[inline]
+    pub fn remove_item<Q>(&mut self, item: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.set.remove_item(item)
+    }
+    /// Panics if index is out of bounds
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> T {
+        self.set.remove_index(index)
+    }
+    /// Remove and return the least element
+    #[inline]
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.set.pop_first()
+    }
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.set.pop()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.set.clear()
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.set.retain(f)
+    }
+    /// NOTE: to_vec() is a slice method that is accessible through deref, use
+    /// this instead to avoid cloning
+    #[inline]
+    pub fn into_vec(self) -> ::heapless::Vec<T, N> {
+        self.set.into_vec()
+    }
+}
+
+impl<T: Ord, const N: usize> Default for SortedSet<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Ord, const N: usize> core::ops::Deref for SortedSet<T, N> {
+    type Target = SortedVec<T, N>;
+    fn deref(&self) -> &SortedVec<T, N> {
+        &self.set
+    }
+}
+impl<T: Ord, const N: usize> From<::heapless::Vec<T, N>> for SortedSet<T, N> {
+    fn from(unsorted: ::heapless::Vec<T, N>) -> Self {
+        Self::from_unsorted(unsorted)
+    }
+}
+impl<T: Ord, const N: usize> From<SortedSet<T, N>> for ::heapless::Vec<T, N> {
+    fn from(sorted: SortedSet<T, N>) -> Self {
+        sorted.into_vec()
+    }
+}
+impl<T: Ord, const N: usize> IntoIterator for SortedSet<T, N> {
+    type Item = T;
+    type IntoIter = <::heapless::Vec<T, N> as IntoIterator>::IntoIter;
+    fn into_iter(self) -> Self::IntoIter {
+        self.set.into_iter()
+    }
+}
+impl<'a, T: Ord, const N: usize> IntoIterator for &'a SortedSet<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+    fn into_iter(self) -> core::slice::Iter<'a, T> {
+        self.set.vec.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heapless() {
+        let mut v = SortedVec::<i32, 4>::new();
+        assert_eq!(v.try_push(5), Ok(0));
+        assert_eq!(v.try_insert(1), Ok(0));
+        assert_eq!(v.try_insert(5), Ok(1));
+        assert_eq!(v.try_find_or_insert(1), Ok(FindOrInsert::Found(0)));
+        assert_eq!(v.try_push(7), Ok(3));
+        assert!(v.is_full());
+        assert_eq!(v.try_insert(3), Err(3));
+        assert_eq!(v.try_find_or_insert(5).map(|r| r.is_found()), Ok(true));
+        v.dedup();
+        assert_eq!(v[..], [1, 5, 7]);
+        let vec: ::heapless::Vec<i32, 4> = [3, 1, 3, 2].into_iter().collect();
+        let mut s = SortedSet::from(vec);
+        assert_eq!(s[..], [1, 2, 3]);
+        assert_eq!(s.try_replace(2), Ok((1, Some(2))));
+        assert_eq!(s.try_insert(0), Ok(true));
+        assert_eq!(s.try_insert(9), Err(9));
+        assert_eq!(s.try_replace(9), Err(9));
+        assert_eq!(s.pop_first(), Some(0));
+        assert_eq!(s.remove_item(&2), Some(2));
+        assert_eq!(s.into_iter().collect::<Vec<_>>(), vec![1, 3]);
+    }
+}
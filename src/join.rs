@@ -0,0 +1,193 @@
+//! Sort-merge join of two sorted containers.
+//!
+//! `SortedVec::join` walks two vectors whose elements are sorted by a join
+//! key, such as a leading id field, and yields for each distinct key the run
+//! of elements having that key on either side. The walk is a single O(n + m)
+//! merge pass and the runs are borrowed slices, so nothing is allocated.
+//!
+//! ```
+//! use sorted_vec2::join::Joined;
+//! use sorted_vec2::SortedVec;
+//!
+//! let users = SortedVec::from_unsorted(vec![(1, "ann"), (2, "bob"), (4, "eve")]);
+//! let orders = SortedVec::from_unsorted(vec![(1, 30), (1, 10), (3, 20)]);
+//! let mut joined = users.join(&orders, |u| u.0, |o| o.0);
+//! assert_eq!(joined.next(), Some(Joined::Both(&users[..1], &orders[..2])));
+//! assert_eq!(joined.next(), Some(Joined::Left(&users[1..2])));
+//! assert_eq!(joined.next(), Some(Joined::Right(&orders[2..])));
+//! assert_eq!(joined.next(), Some(Joined::Left(&users[2..])));
+//! assert_eq!(joined.next(), None);
+//! ```
+
+use crate::SortedVec;
+
+/// The elements having one join key
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Joined<'a, A, B> {
+    /// The key only occurs on the left side
+    Left(&'a [A]),
+    /// The key only occurs on the right side
+    Right(&'a [B]),
+    /// The key occurs on both sides
+    Both(&'a [A], &'a [B]),
+}
+
+impl<'a, A, B> Joined<'a, A, B> {
+    /// The elements from the left side, empty for `Right`
+    pub fn left(&self) -> &'a [A] {
+        match *self {
+            Joined::Left(a) | Joined::Both(a, _) => a,
+            Joined::Right(_) => &[],
+        }
+    }
+    /// The elements from the right side, empty for `Left`
+    pub fn right(&self) -> &'a [B] {
+        match *self {
+            Joined::Right(b) | Joined::Both(_, b) => b,
+            Joined::Left(_) => &[],
+        }
+    }
+    /// Every pair of a left and a right element, as produced by an inner join
+    pub fn pairs(&self) -> impl Iterator<Item = (&'a A, &'a B)> + 'a {
+        let b = self.right();
+        self.left()
+            .iter()
+            .flat_map(move |a| b.iter().map(move |b| (a, b)))
+    }
+}
+
+/// Iterator over the runs of each join key, see `SortedVec::join`
+#[derive(Clone, Debug)]
+pub struct MergeJoin<'a, A, B, FA, FB> {
+    a: &'a [A],
+    b: &'a [B],
+    key_a: FA,
+    key_b: FB,
+}
+
+/// Sort-merge join of two slices that are sorted by the keys `key_a` and
+/// `key_b` respectively. See `SortedVec::join`.
+pub fn merge_join_by<'a, A, B, K, FA, FB>(
+    a: &'a [A],
+    b: &'a [B],
+    key_a: FA,
+    key_b: FB,
+) -> MergeJoin<'a, A, B, FA, FB>
+where
+    K: Ord,
+    FA: FnMut(&A) -> K,
+    FB: FnMut(&B) -> K,
+{
+    MergeJoin { a, b, key_a, key_b }
+}
+
+/// Length of the run at the front of `slice` whose key is `k`
+fn run_len<T, K: Ord>(slice: &[T], k: &K, mut key: impl FnMut(&T) -> K) -> usize {
+    1 + slice[1..].iter().take_while(|x| key(x) == *k).count()
+}
+
+impl<'a, A, B, K, FA, FB> Iterator for MergeJoin<'a, A, B, FA, FB>
+where
+    K: Ord,
+    FA: FnMut(&A) -> K,
+    FB: FnMut(&B) -> K,
+{
+    type Item = Joined<'a, A, B>;
+    fn next(&mut self) -> Option<Joined<'a, A, B>> {
+        let ka = self.a.first().map(&mut self.key_a);
+        let kb = self.b.first().map(&mut self.key_b);
+        let (take_a, take_b) = match (&ka, &kb) {
+            (Some(ka), Some(kb)) => (ka <= kb, kb <= ka),
+            (Some(_), None) => (true, false),
+            (None, Some(_)) => (false, true),
+            (None, None) => return None,
+        };
+        let a = if take_a {
+            let n = run_len(self.a, ka.as_ref().unwrap(), &mut self.key_a);
+            let (run, rest) = self.a.split_at(n);
+            self.a = rest;
+            run
+        } else {
+            &[]
+        };
+        let b = if take_b {
+            let n = run_len(self.b, kb.as_ref().unwrap(), &mut self.key_b);
+            let (run, rest) = self.b.split_at(n);
+            self.b = rest;
+            run
+        } else {
+            &[]
+        };
+        Some(match (take_a, take_b) {
+            (true, true) => Joined::Both(a, b),
+            (true, false) => Joined::Left(a),
+            _ => Joined::Right(b),
+        })
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = (self.a.len(), self.b.len());
+        (a.max(b).min(1), Some(a + b))
+    }
+}
+impl<A, B, K, FA, FB> std::iter::FusedIterator for MergeJoin<'_, A, B, FA, FB>
+where
+    K: Ord,
+    FA: FnMut(&A) -> K,
+    FB: FnMut(&B) -> K,
+{
+}
+
+impl<T: Ord> SortedVec<T> {
+    /// Sort-merge join with `other`, yielding for each distinct key, in
+    /// ascending order, the runs of elements of `self` and `other` with that
+    /// key. O(n + m).
+    ///
+    /// The keys must be non-decreasing along each vector, which holds when
+    /// the key is a leading field of the element or otherwise preserves the
+    /// order of the elements; keys out of order give unspecified, but safe,
+    /// groupings.
+    pub fn join<'a, U, K, FA, FB>(
+        &'a self,
+        other: &'a SortedVec<U>,
+        key_a: FA,
+        key_b: FB,
+    ) -> MergeJoin<'a, T, U, FA, FB>
+    where
+        U: Ord,
+        K: Ord,
+        FA: FnMut(&T) -> K,
+        FB: FnMut(&U) -> K,
+    {
+        merge_join_by(self, other, key_a, key_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join() {
+        let a = SortedVec::from_unsorted(vec![(1, 'a'), (1, 'b'), (3, 'c'), (5, 'd')]);
+        let b = SortedVec::from_unsorted(vec![(0, 10), (1, 20), (1, 30), (5, 40), (6, 50)]);
+        let joined: Vec<_> = a.join(&b, |x| x.0, |y| y.0).collect();
+        assert_eq!(joined.len(), 5);
+        assert_eq!(joined[0], Joined::Right(&b[..1]));
+        assert_eq!(joined[1], Joined::Both(&a[..2], &b[1..3]));
+        assert_eq!(joined[2].left(), &a[2..3]);
+        assert!(joined[2].right().is_empty());
+        let inner: Vec<_> = joined.iter().flat_map(|j| j.pairs()).collect();
+        assert_eq!(inner.len(), 5);
+        assert_eq!(inner[1], (&(1, 'a'), &(1, 30)));
+        assert_eq!(joined[4], Joined::Right(&b[4..]));
+        let e = SortedVec::<(i32, char)>::new();
+        assert_eq!(e.join(&b, |x| x.0, |y| y.0).count(), 4);
+        assert_eq!(e.join(&e, |x| x.0, |y| y.0).next(), None);
+        let words = ["apple", "avocado", "banana"];
+        let letters = ['a', 'c'];
+        let by_letter: Vec<_> = merge_join_by(&words, &letters, |w| w.as_bytes()[0], |c| *c as u8)
+            .map(|j| (j.left().len(), j.right().len()))
+            .collect();
+        assert_eq!(by_letter, vec![(2, 1), (1, 0), (0, 1)]);
+    }
+}
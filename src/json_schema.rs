@@ -0,0 +1,98 @@
+//! `schemars` JSON Schema support (feature `schemars`).
+//!
+//! The containers are described as arrays of their elements, with
+//! `uniqueItems` set for the sets. JSON Schema has no keyword for ordering, so
+//! the ordering is stated in the description, and descending containers get a
+//! `Reverse` prefix on their schema name. The deprecated `ReverseWrapped*`
+//! aliases are ascending containers of `std::cmp::Reverse<T>` and are described
+//! as such: their order is over `Reverse<T>`, i.e. descending in `T`.
+//!
+//! ```
+//! use schemars::schema_for;
+//! use sorted_vec2::SortedSet;
+//!
+//! let schema = schema_for!(SortedSet<u32>);
+//! let array = schema.schema.array.unwrap();
+//! assert_eq!(array.unique_items, Some(true));
+//! ```
+
+use ::schemars::gen::SchemaGenerator;
+use ::schemars::schema::{ArrayValidation, InstanceType, Metadata, Schema, SchemaObject};
+use ::schemars::JsonSchema;
+
+use crate::{SortOrder, SortedSet, SortedVec};
+
+fn array_schema<T: JsonSchema, O: SortOrder>(gen: &mut SchemaGenerator, unique: bool) -> Schema {
+    let description = if unique {
+        format!("Array of unique items sorted in {} order", O::NAME)
+    } else {
+        format!("Array sorted in {} order", O::NAME)
+    };
+    SchemaObject {
+        metadata: Some(Box::new(Metadata {
+            description: Some(description),
+            ..Default::default()
+        })),
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(gen.subschema_for::<T>().into()),
+            unique_items: if unique { Some(true) } else { None },
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+impl<T: Ord + JsonSchema, O: SortOrder> JsonSchema for SortedVec<T, O> {
+    fn is_referenceable() -> bool {
+        false
+    }
+    fn schema_name() -> String {
+        let prefix = if O::IS_DESCENDING { "Reverse" } else { "" };
+        format!("{}SortedVec_of_{}", prefix, T::schema_name())
+    }
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        array_schema::<T, O>(gen, false)
+    }
+}
+
+impl<T: Ord + JsonSchema, O: SortOrder> JsonSchema for SortedSet<T, O> {
+    fn is_referenceable() -> bool {
+        false
+    }
+    fn schema_name() -> String {
+        let prefix = if O::IS_DESCENDING { "Reverse" } else { "" };
+        format!("{}SortedSet_of_{}", prefix, T::schema_name())
+    }
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        array_schema::<T, O>(gen, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReverseSortedSet, ReverseSortedVec};
+    use ::schemars::schema_for;
+
+    #[test]
+    fn test_json_schema() {
+        let schema = schema_for!(SortedVec<i32>).schema;
+        let array = schema.array.unwrap();
+        assert_eq!(array.unique_items, None);
+        assert!(array.items.is_some());
+        let schema = schema_for!(ReverseSortedSet<String>).schema;
+        assert_eq!(schema.array.unwrap().unique_items, Some(true));
+        assert_eq!(
+            schema.metadata.unwrap().description.unwrap(),
+            "Array of unique items sorted in descending order"
+        );
+        assert_eq!(
+            <ReverseSortedSet<String>>::schema_name(),
+            "ReverseSortedSet_of_String"
+        );
+        let schema = schema_for!(ReverseSortedVec<u8>).schema;
+        assert_eq!(schema.instance_type, Some(InstanceType::Array.into()));
+    }
+}
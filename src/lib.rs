@@ -7,56 +7,339 @@
 //! - `ReverseSortedVec` -- sorted from greatest to least, may contain
 //!   duplicates
 //! - `ReverseSortedSet` -- sorted from greatest to least, unique elements
+//! - `PriorityQueue` -- `BinaryHeap` compatible queue backed by a `SortedVec`
 //!
 //! The `partial` module provides sorted vectors of types that only implement
 //! `PartialOrd` where comparison of incomparable elements results in runtime
 //! panic.
+//!
+//! The `float` module provides `SortedFloatVec`, a vector of floats ordering
+//! NaN according to a configurable `NanPolicy` instead of panicking, and the
+//! `SortedF64Vec`/`SortedF32Vec` vectors ordered by `total_cmp`.
+//!
+//! The `branded` module provides views of the containers returning indices
+//! that can not be mixed up between containers.
+//!
+//! The `by_key` module provides `SortedByKeyVec`, sorted by a key projected
+//! from each element, for element types that do not implement `Ord`.
+//!
+//! The `compare` module provides `SortedVecBy`, sorted by a stored comparator
+//! instead of `Ord`.
+//!
+//! The `diff` module provides `SortedVec::diff`, the elements added and
+//! removed between two versions of a container.
+//!
+//! The `join` module provides `SortedVec::join`, a sort-merge join of two
+//! vectors sorted by a common key.
+//!
+//! The `map` module provides `SortedVecMap` and `SortedVecMultiMap`, maps
+//! stored as a vector of key-value pairs sorted by key.
+//!
+//! The `order` module provides the `Ascending` (default) and `Descending`
+//! markers selecting the order of `SortedVec<T, O>` and `SortedSet<T, O>`.
+//!
+//! The `multiset` module provides `SortedMultiSet`, storing each distinct
+//! element once with its count.
+//!
+//! The `set_ops` module provides the lazy `union`, `intersection`,
+//! `difference` and `symmetric_difference` iterators of `SortedSet`.
+//!
+//! The `concurrent` module provides `ConcurrentSortedSet` and
+//! `ConcurrentSortedMap`, split into key-range shards behind `RwLock`s so that
+//! threads can insert and look up keys concurrently.
+//!
+//! The `cow` module provides `CowSortedVec`, which borrows a sorted slice
+//! until it is mutated.
+//!
+//! The `frozen` module provides `FrozenSortedVec`, an immutable snapshot
+//! returned by `SortedVec::freeze` that is shared between clones, and the
+//! `eytzinger` module provides `FrozenEytzinger`, which stores the elements in
+//! breadth-first search tree order for cache friendly lookups.
+//!
+//! The `deque` module provides `SortedDeque`, backed by a `VecDeque` so that
+//! both the least and the greatest elements can be popped in O(1).
+//!
+//! The `array` module provides `SortedArrayVec`, a fixed capacity sorted
+//! vector stored inline that never allocates.
+//!
+//! The `search` module provides batched lookups of many sorted needles and
+//! searches restricted to a subrange or starting from a hint.
+//!
+//! Iterators known to yield sorted elements implement the `SortedIterator`
+//! marker trait (and `UniqueSortedIterator` if elements are also unique).
+//!
+//! The `unstable` feature requires a nightly compiler and uses specialization
+//! to skip sorting in `FromIterator`/`Extend` for `SortedIterator`s.
+//!
+//! With the `allocator_api` feature (also nightly only) `SortedVec` and
+//! `SortedSet` take the allocator of their storage as a last type parameter,
+//! `Global` by default, see `SortedVec::new_in`.
+//!
+//! The `branchless` module (feature `branchless`) provides binary searches of
+//! integer and float keys that select with conditional moves instead of
+//! branches, including interleaved batched lookups.
+//!
+//! The `arrow` module (feature `arrow`) converts numeric containers to and from
+//! Apache Arrow primitive arrays.
+//!
+//! The `critical` module (feature `critical-section`) provides a fixed capacity
+//! sorted set that can be shared with interrupt handlers.
+//!
+//! The `shared` module (feature `arc-swap`) provides `SharedSortedVec`, whose
+//! readers load immutable snapshots without locking while writers publish
+//! batches of changes by swapping the snapshot.
+//!
+//! The `heapless` module (feature `heapless`) provides sorted containers over
+//! `heapless::Vec` with fallible insertion, for targets without an allocator.
+//!
+//! The `small` module (feature `smallvec`) provides sorted containers that
+//! store small element counts inline in a `SmallVec`. The `tiny` module
+//! (feature `tinyvec`) provides the same for `TinyVec`, which uses no unsafe
+//! code.
+//!
+//! The `ordered_float` module (feature `ordered-float`) provides aliases and
+//! conversions for containers of `OrderedFloat` and `NotNan` floats.
+//!
+//! With the `bincode` feature the containers implement `bincode` 2 `Encode` and
+//! `Decode`, checking the order of the elements as they are decoded.
+//!
+//! With the `arbitrary` feature the containers implement `Arbitrary` for
+//! fuzzing, sorting the generated elements.
+//!
+//! The `strategy` module (feature `proptest`) provides `proptest` strategies
+//! generating sorted containers, which also implement `Arbitrary`.
+//!
+//! With the `defmt` feature the containers implement `defmt::Format`.
+//!
+//! With the `rayon` feature the containers implement the `rayon` parallel
+//! iterator traits; collecting from a parallel iterator sorts in parallel, as
+//! does `par_from_unsorted`.
+//!
+//! With the `rkyv` feature `SortedVec` and `SortedSet` can be archived, and
+//! `ArchivedSortedVec` and `ArchivedSortedSet` support binary search directly
+//! on the archived form.
+//!
+//! With the `schemars` feature the containers implement `JsonSchema`, described
+//! as arrays (of unique items for the sets).
+//!
+//! With the `utoipa` feature the containers implement `ToSchema`, so they can
+//! appear in OpenAPI documents.
+//!
+//! The `unsorted` module (feature `serde`) can be used with
+//! `#[serde(with = "sorted_vec2::unsorted")]` to sort incoming sequences on
+//! deserialization instead of rejecting them. The `serde_helpers` module
+//! (feature `serde`) validates or sorts plain `Vec<T>` fields the same way.
+//!
+//! The `test_util` module (feature `test-util`) provides a model-based testing
+//! harness checking the containers against `BTreeMap`/`BTreeSet`.
+
+#![cfg_attr(feature = "unstable", feature(specialization))]
+#![cfg_attr(feature = "unstable", allow(incomplete_features))]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
 
 #[cfg(feature = "serde")]
 #[macro_use]
 extern crate serde;
 
-#[cfg(feature = "serde")]
-extern crate is_sorted;
+#[cfg(feature = "allocator_api")]
+use std::alloc::{Allocator, Global};
+use std::borrow::Borrow;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 
-// At the time of writing, is_sorted() is not available on stable Rust
-#[cfg(feature = "serde")]
-use is_sorted::IsSorted;
+#[cfg(all(feature = "allocator_api", feature = "rkyv"))]
+compile_error!("the `allocator_api` and `rkyv` features can not be enabled together");
 
-use std::hash::{Hash, Hasher};
+/// The type `$ty` with the allocator `$alloc` as its last parameter when the
+/// `allocator_api` feature is enabled, e.g. `with_alloc!(Vec<T>, A)`
+#[cfg(feature = "allocator_api")]
+macro_rules! with_alloc {
+    ($($path:ident)::+ <$($arg:tt),*>, $alloc:ty) => {
+        $($path)::+ <$($arg),*, $alloc>
+    };
+}
+#[cfg(not(feature = "allocator_api"))]
+macro_rules! with_alloc {
+    ($($path:ident)::+ <$($arg:tt),*>, $alloc:ty) => {
+        $($path)::+ <$($arg),*>
+    };
+}
 
+#[cfg(feature = "rkyv")]
+mod archived;
+pub mod array;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "branchless")]
+pub mod branchless;
+pub mod branded;
+pub mod by_key;
+pub mod compare;
+pub mod concurrent;
+pub mod cow;
+#[cfg(feature = "critical-section")]
+pub mod critical;
+#[cfg(feature = "serde")]
+mod de;
+pub mod deque;
+pub mod diff;
+#[cfg(feature = "bincode")]
+mod encode;
+pub mod eytzinger;
+pub mod float;
+#[cfg(feature = "defmt")]
+mod format;
+pub mod frozen;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+pub mod join;
+#[cfg(feature = "schemars")]
+mod json_schema;
+pub mod map;
+mod merge;
+pub mod multiset;
+pub mod order;
+#[cfg(feature = "ordered-float")]
+pub mod ordered_float;
+#[cfg(feature = "rayon")]
+mod par;
 pub mod partial;
+pub mod priority_queue;
+pub mod search;
+#[cfg(feature = "serde")]
+pub mod serde_helpers;
+pub mod set_ops;
+#[cfg(feature = "arc-swap")]
+pub mod shared;
+#[cfg(feature = "smallvec")]
+pub mod small;
+#[cfg(feature = "unstable")]
+mod spec;
+mod storage;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+#[cfg(feature = "tinyvec")]
+pub mod tiny;
+#[cfg(feature = "utoipa")]
+mod to_schema;
+#[cfg(feature = "serde")]
+pub mod unsorted;
+
+pub use order::{Ascending, Descending, SortOrder};
+pub use priority_queue::PriorityQueue;
 
 /// Forward sorted vector
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[cfg_attr(
     all(feature = "serde", not(feature = "serde-nontransparent")),
     serde(transparent)
 )]
-#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
-pub struct SortedVec<T: Ord> {
-    #[cfg_attr(feature = "serde", serde(deserialize_with = "SortedVec::parse_vec"))]
+#[cfg_attr(not(feature = "allocator_api"), derive(Eq, Ord, PartialEq, PartialOrd))]
+#[derive(Clone)]
+pub struct SortedVec<
+    T: Ord,
+    O: SortOrder = Ascending,
+    #[cfg(feature = "allocator_api")] A: Allocator = Global,
+> {
+    #[cfg(not(feature = "allocator_api"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "SortedVec::<T, O>::parse_vec")
+    )]
     #[cfg_attr(
         feature = "serde",
         serde(bound(deserialize = "T : serde::Deserialize <'de>"))
     )]
     vec: Vec<T>,
+    #[cfg(feature = "allocator_api")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "SortedVec::<T, O, A>::serialize_vec_in",
+            deserialize_with = "SortedVec::<T, O, A>::parse_vec_in"
+        )
+    )]
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(
+            serialize = "T : serde::Serialize",
+            deserialize = "T : serde::Deserialize <'de>, A : Default"
+        ))
+    )]
+    vec: Vec<T, A>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    order: PhantomData<O>,
 }
 
 /// Forward sorted set
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 #[cfg_attr(
     all(feature = "serde", not(feature = "serde-nontransparent")),
     serde(transparent)
 )]
-#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
-pub struct SortedSet<T: Ord> {
-    #[cfg_attr(feature = "serde", serde(deserialize_with = "SortedSet::parse_vec"))]
+#[cfg_attr(not(feature = "allocator_api"), derive(Eq, Ord, PartialEq, PartialOrd))]
+#[derive(Clone, Debug)]
+pub struct SortedSet<
+    T: Ord,
+    O: SortOrder = Ascending,
+    #[cfg(feature = "allocator_api")] A: Allocator = Global,
+> {
+    #[cfg(not(feature = "allocator_api"))]
     #[cfg_attr(
         feature = "serde",
-        serde(bound(deserialize = "T : serde::Deserialize <'de>"))
+        serde(
+            rename = "vec",
+            serialize_with = "SortedSet::<T, O>::serialize_vec",
+            deserialize_with = "SortedSet::<T, O>::parse_vec"
+        )
+    )]
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(
+            serialize = "T : serde::Serialize",
+            deserialize = "T : serde::Deserialize <'de>"
+        ))
     )]
-    set: SortedVec<T>,
+    set: SortedVec<T, O>,
+    #[cfg(feature = "allocator_api")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "vec",
+            serialize_with = "SortedSet::<T, O, A>::serialize_vec",
+            deserialize_with = "SortedSet::<T, O, A>::parse_vec_in"
+        )
+    )]
+    #[cfg_attr(
+        feature = "serde",
+        serde(bound(
+            serialize = "T : serde::Serialize",
+            deserialize = "T : serde::Deserialize <'de>, A : Default"
+        ))
+    )]
+    set: SortedVec<T, O, A>,
+}
+
+/// Which element of a run of equal elements is kept when deduplicating
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum DedupPolicy {
+    /// Keep the first element of each run
+    #[default]
+    KeepFirst,
+    /// Keep the last element of each run
+    KeepLast,
 }
 
 /// Value returned when find_or_insert is used.
@@ -116,33 +399,150 @@ impl FindOrInsert {
     }
 }
 
+/// Merge two sorted sequences into a new vector with a single allocation.
+/// Each pair of equal elements is passed to `equal`, which pushes the result
+/// onto the output.
+pub(crate) fn merge_vec<O, T, I, F>(left: Vec<T>, right: I, mut equal: F) -> Vec<T>
+where
+    O: SortOrder,
+    T: Ord,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&mut Vec<T>, T, T),
+{
+    use std::cmp::Ordering;
+    let right = right.into_iter();
+    let mut vec = Vec::with_capacity(left.len() + right.size_hint().0);
+    let mut left = left.into_iter().peekable();
+    let mut right = right.peekable();
+    loop {
+        match (left.peek(), right.peek()) {
+            (Some(l), Some(r)) => match O::compare(l, r) {
+                Ordering::Less => vec.push(left.next().unwrap()),
+                Ordering::Greater => vec.push(right.next().unwrap()),
+                Ordering::Equal => equal(&mut vec, left.next().unwrap(), right.next().unwrap()),
+            },
+            (Some(_), None) => {
+                vec.extend(left);
+                break;
+            }
+            (None, _) => {
+                vec.extend(right);
+                break;
+            }
+        }
+    }
+    vec
+}
+
+/// Merge two sorted vectors, combining elements that compare equal with
+/// `resolve`. Re-sorts the result if `resolve` returned an element out of order.
+fn merge_resolve_vec<O, T, F>(left: Vec<T>, right: Vec<T>, mut resolve: F) -> Vec<T>
+where
+    O: SortOrder,
+    T: Ord,
+    F: FnMut(T, T) -> T,
+{
+    let mut vec = merge_vec::<O, _, _, _>(left, right, |vec, l, r| vec.push(resolve(l, r)));
+    if !vec.windows(2).all(|w| O::compare(&w[0], &w[1]).is_le()) {
+        vec.sort_by(O::compare);
+    }
+    vec
+}
+
 //
 //  impl SortedVec
 //
 
-impl<T: Ord> SortedVec<T> {
+impl<T: Ord, O: SortOrder> SortedVec<T, O> {
+    /// Empty container sorted in the given order, e.g.
+    /// `SortedVec::with_order(Descending)`.
     #[inline]
-    pub fn new() -> Self {
-        SortedVec { vec: Vec::new() }
+    pub fn with_order(_order: O) -> Self {
+        SortedVec {
+            vec: Vec::new(),
+            order: PhantomData,
+        }
     }
     #[inline]
-    pub fn with_capacity(capacity: usize) -> Self {
+    pub fn with_capacity_and_order(capacity: usize, _order: O) -> Self {
         SortedVec {
             vec: Vec::with_capacity(capacity),
+            order: PhantomData,
         }
     }
-    /// Uses `sort_unstable()` to sort in place.
-    #[inline]
-    pub fn from_unsorted(mut vec: Vec<T>) -> Self {
-        vec.sort_unstable();
-        SortedVec { vec }
+    /// Perform sorting on the input sequence when deserializing with `serde`.
+    ///
+    /// Use with `#[serde(deserialize_with = "SortedVec::deserialize_unsorted")]`:
+    /// ```text
+    /// #[derive(Debug, Eq, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+    /// pub struct Foo {
+    ///   #[serde(deserialize_with = "SortedVec::deserialize_unsorted")]
+    ///   pub v : SortedVec <u64>
+    /// }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn deserialize_unsorted<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        use serde::Deserialize;
+        let v = Vec::deserialize(deserializer)?;
+        Ok(Self::sort_in_order(v))
     }
-
-    /// The caller must ensure that the provided vector is already sorted.
-    pub unsafe fn from_unsorted_unchecked(vec: Vec<T>) -> Self {
-        SortedVec { vec }
+    /// Like the `Deserialize` implementation but failing as soon as the input
+    /// has more than `max_len` elements, so that untrusted input can not
+    /// cause an unbounded allocation.
+    ///
+    /// Use with `deserialize_with` through a function fixing `max_len`:
+    /// ```text
+    /// fn bounded <'de, D : Deserializer <'de>> (d : D)
+    ///   -> Result <SortedVec <u64>, D::Error>
+    /// {
+    ///   SortedVec::deserialize_bounded (d, 1024)
+    /// }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn deserialize_bounded<'de, D>(deserializer: D, max_len: usize) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        let vec = de::deserialize_sorted_vec(deserializer, max_len, Self::check_next)?;
+        Ok(SortedVec {
+            vec,
+            order: PhantomData,
+        })
+    }
+    #[cfg(all(feature = "serde", not(feature = "allocator_api")))]
+    fn parse_vec<'de, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        de::deserialize_sorted_vec(deserializer, usize::MAX, Self::check_next)
     }
+}
 
+impl<T: Ord, O: SortOrder, #[cfg(feature = "allocator_api")] A: Allocator> with_alloc!(SortedVec<T, O>, A) {
+    /// Same as `from_unsorted`, sorting in the given order.
+    #[inline]
+    pub fn from_unsorted_with_order(vec: with_alloc!(Vec<T>, A), _order: O) -> Self {
+        Self::sort_in_order(vec)
+    }
+    fn sort_in_order(mut vec: with_alloc!(Vec<T>, A)) -> Self {
+        if !vec.windows(2).all(|w| O::compare(&w[0], &w[1]).is_le()) {
+            if vec.windows(2).all(|w| O::compare(&w[0], &w[1]).is_ge()) {
+                vec.reverse();
+            } else {
+                vec.sort_unstable_by(O::compare);
+            }
+        }
+        SortedVec {
+            vec,
+            order: PhantomData,
+        }
+    }
     /// Insert an element into sorted position, returning the order index at which
     /// it was placed.
     pub fn insert(&mut self, element: T) -> usize {
@@ -168,7 +568,7 @@ impl<T: Ord> SortedVec<T> {
     #[inline]
     pub fn push(&mut self, element: T) -> usize {
         if let Some(last) = self.vec.last() {
-            let cmp = element.cmp(last);
+            let cmp = O::compare(&element, last);
             if cmp == std::cmp::Ordering::Greater || cmp == std::cmp::Ordering::Equal {
                 // The new element is greater than or equal to the current last element,
                 // so we can simply push it onto the vec.
@@ -186,6 +586,35 @@ impl<T: Ord> SortedVec<T> {
             return 0;
         }
     }
+    /// Append the element in O(1) if it belongs at the back of the container,
+    /// returning its index, otherwise give the element back with `Err`
+    /// without searching. Suited to input that is almost always in order,
+    /// where the caller handles the rare out of order element separately.
+    #[inline]
+    pub fn push_max(&mut self, element: T) -> Result<usize, T> {
+        match self.vec.last() {
+            Some(last) if O::compare(&element, last) == std::cmp::Ordering::Less => Err(element),
+            _ => {
+                self.vec.push(element);
+                Ok(self.vec.len() - 1)
+            }
+        }
+    }
+    /// Insert the element at the front if it belongs there, returning `Ok(0)`,
+    /// otherwise give the element back with `Err`. Skips the search, but the
+    /// insertion still shifts the existing elements.
+    #[inline]
+    pub fn push_min(&mut self, element: T) -> Result<usize, T> {
+        match self.vec.first() {
+            Some(first) if O::compare(&element, first) == std::cmp::Ordering::Greater => {
+                Err(element)
+            }
+            _ => {
+                self.vec.insert(0, element);
+                Ok(0)
+            }
+        }
+    }
     /// Reserves additional capacity in the underlying vector.
     /// See std::vec::Vec::reserve.
     #[inline]
@@ -196,7 +625,7 @@ impl<T: Ord> SortedVec<T> {
     /// belongs at the back of the container.
     pub fn find_or_push(&mut self, element: T) -> FindOrInsert {
         if let Some(last) = self.vec.last() {
-            let cmp = element.cmp(last);
+            let cmp = O::compare(&element, last);
             if cmp == std::cmp::Ordering::Equal {
                 return FindOrInsert::Found(self.vec.len() - 1);
             } else if cmp == std::cmp::Ordering::Greater {
@@ -214,13 +643,76 @@ impl<T: Ord> SortedVec<T> {
             return FindOrInsert::Inserted(0);
         }
     }
+    /// Binary search for `x`, which may be any borrowed form of the element
+    /// type. See `slice::binary_search`.
+    #[inline]
+    pub fn binary_search<Q>(&self, x: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.binary_search_by(|y| O::compare(y.borrow(), x))
+    }
+    /// Binary search for `x`, shadowing the linear `slice::contains` reached
+    /// through `Deref`. O(log n).
+    #[inline]
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(x).is_ok()
+    }
     #[inline]
-    pub fn remove_item(&mut self, item: &T) -> Option<T> {
-        match self.vec.binary_search(item) {
+    pub fn remove_item<Q>(&mut self, item: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.binary_search(item) {
             Ok(remove_at) => Some(self.vec.remove(remove_at)),
             Err(_) => None,
         }
     }
+    /// Remove one occurrence of each of `sorted_items`, which must be sorted
+    /// in the same order as the container, returning the number of elements
+    /// removed. The removal is a single O(n + m) compaction pass instead of
+    /// one shift of the tail per item.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let mut v = SortedVec::from_unsorted(vec![1, 2, 2, 3, 5, 8]);
+    /// assert_eq!(v.remove_items(&[2, 4, 5, 8]), 3);
+    /// assert_eq!(*v, vec![1, 2, 3]);
+    /// ```
+    pub fn remove_items<Q>(&mut self, sorted_items: &[Q]) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord,
+    {
+        debug_assert!(sorted_items
+            .windows(2)
+            .all(|w| O::compare(&w[0], &w[1]).is_le()));
+        let len = self.vec.len();
+        let mut items = sorted_items;
+        self.vec.retain(|x| {
+            while items
+                .first()
+                .is_some_and(|y| O::compare(y, x.borrow()).is_lt())
+            {
+                items = &items[1..];
+            }
+            match items.split_first() {
+                Some((y, rest)) if y == x.borrow() => {
+                    items = rest;
+                    false
+                }
+                _ => true,
+            }
+        });
+        len - self.vec.len()
+    }
     /// Panics if index is out of bounds
     #[inline]
     pub fn remove_index(&mut self, index: usize) -> T {
@@ -246,8 +738,38 @@ impl<T: Ord> SortedVec<T> {
     {
         self.vec.dedup_by_key(key);
     }
+    /// Same as `dedup`, except the last element of each run of equal elements
+    /// is kept, e.g. the most recently inserted of several structs ordered by
+    /// a key.
+    pub fn dedup_keep_last(&mut self) {
+        self.vec.dedup_by(|next, kept| {
+            if next == kept {
+                std::mem::swap(next, kept);
+                true
+            } else {
+                false
+            }
+        });
+    }
+    /// Deduplicate according to `policy`, see `dedup` and `dedup_keep_last`.
+    #[inline]
+    pub fn dedup_with_policy(&mut self, policy: DedupPolicy) {
+        match policy {
+            DedupPolicy::KeepFirst => self.dedup(),
+            DedupPolicy::KeepLast => self.dedup_keep_last(),
+        }
+    }
+    /// Remove consecutive elements related by `same_bucket`, see
+    /// `Vec::dedup_by`. Only removing elements, this can not break the order.
+    #[inline]
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        self.vec.dedup_by(same_bucket);
+    }
     #[inline]
-    pub fn drain<R>(&mut self, range: R) -> std::vec::Drain<T>
+    pub fn drain<R>(&mut self, range: R) -> with_alloc!(std::vec::Drain<'_, T>, A)
     where
         R: std::ops::RangeBounds<usize>,
     {
@@ -260,126 +782,637 @@ impl<T: Ord> SortedVec<T> {
     {
         self.vec.retain(f)
     }
+    /// Same as `retain`, except that `f` may mutate the elements. Uses
+    /// `sort_unstable_by()` to re-sort the retained elements.
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        self.mutate_vec(|vec| vec.retain_mut(f))
+    }
     /// NOTE: to_vec() is a slice method that is accessible through deref, use
     /// this instead to avoid cloning
     #[inline]
-    pub fn into_vec(self) -> Vec<T> {
+    pub fn into_vec(self) -> with_alloc!(Vec<T>, A) {
         self.vec
     }
-    /// Apply a closure mutating the sorted vector and use `sort_unstable()`
+    /// Apply a closure mutating the sorted vector and use `sort_unstable_by()`
     /// to re-sort the mutated vector
-    pub fn mutate_vec<F, O>(&mut self, f: F) -> O
+    pub fn mutate_vec<F, R>(&mut self, f: F) -> R
     where
-        F: FnOnce(&mut Vec<T>) -> O,
+        F: FnOnce(&mut with_alloc!(Vec<T>, A)) -> R,
     {
         let res = f(&mut self.vec);
-        self.vec.sort_unstable();
+        self.vec.sort_unstable_by(O::compare);
         res
     }
     /// Unsafe access to the underlying vector. The caller must ensure that any
     /// changes to the values in the vector do not impact the ordering of the
     /// elements inside, or else this container will misbehave.
-    pub unsafe fn get_unchecked_mut_vec(&mut self) -> &mut Vec<T> {
+    pub unsafe fn get_unchecked_mut_vec(&mut self) -> &mut with_alloc!(Vec<T>, A) {
         return &mut self.vec;
     }
-
-    /// Perform sorting on the input sequence when deserializing with `serde`.
+    /// Mutable access to the underlying vector without re-sorting, analogous to
+    /// `String::as_mut_vec`.
+    ///
+    /// # Safety
+    ///
+    /// The vector must be sorted when the borrow ends. Elements may be added,
+    /// removed or modified in place, but leaving the vector unsorted breaks the
+    /// invariant that searching and inserting rely on.
     ///
-    /// Use with `#[serde(deserialize_with = "SortedVec::deserialize_unsorted")]`:
-    /// ```text
-    /// #[derive(Debug, Eq, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
-    /// pub struct Foo {
-    ///   #[serde(deserialize_with = "SortedVec::deserialize_unsorted")]
-    ///   pub v : SortedVec <u64>
-    /// }
     /// ```
-    #[cfg(feature = "serde")]
-    pub fn deserialize_unsorted<'de, D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-        T: serde::Deserialize<'de>,
-    {
-        use serde::Deserialize;
-        let v = Vec::deserialize(deserializer)?;
-        Ok(SortedVec::from_unsorted(v))
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let mut v = SortedVec::from_unsorted(vec![3, 1, 2]);
+    /// // doubling every element keeps the order
+    /// unsafe { v.as_mut_vec().iter_mut().for_each(|x| *x *= 2) };
+    /// assert_eq!(v.binary_search(&4), Ok(1));
+    /// ```
+    #[inline]
+    pub unsafe fn as_mut_vec(&mut self) -> &mut with_alloc!(Vec<T>, A) {
+        self.get_unchecked_mut_vec()
     }
-
+    /// Checks each element against the previous one as it is deserialized
     #[cfg(feature = "serde")]
-    fn parse_vec<'de, D>(deserializer: D) -> Result<Vec<T>, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-        T: serde::Deserialize<'de>,
-    {
-        use serde::de::Error;
-        use serde::Deserialize;
-        let v = Vec::deserialize(deserializer)?;
-        let is_sorted = {
-            let mut iter = v.iter();
-            IsSorted::is_sorted(&mut iter)
-        };
-        if !is_sorted {
-            Err(D::Error::custom("input sequence is not sorted"))
-        } else {
-            Ok(v)
+    fn check_next(prev: Option<&T>, x: &T) -> Result<(), &'static str> {
+        match prev {
+            Some(prev) if O::compare(prev, x).is_gt() => Err("input sequence is not sorted"),
+            _ => Ok(()),
         }
     }
 }
-impl<T: Ord> Default for SortedVec<T> {
-    fn default() -> Self {
-        Self::new()
+
+#[cfg(feature = "allocator_api")]
+impl<T: Ord, A: Allocator> SortedVec<T, Ascending, A> {
+    /// Empty container storing its elements with the allocator `alloc`. Use
+    /// `from_unsorted_with_order(Vec::new_in(alloc), order)` for another
+    /// order.
+    ///
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::Global;
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let mut v = SortedVec::with_capacity_in(4, Global);
+    /// v.insert(3);
+    /// v.insert(1);
+    /// assert_eq!(*v, [1, 3]);
+    /// let vec: Vec<i32, Global> = v.into_vec();
+    /// assert_eq!(vec, [1, 3]);
+    /// ```
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        SortedVec {
+            vec: Vec::new_in(alloc),
+            order: PhantomData,
+        }
     }
-}
-impl<T: Ord> From<Vec<T>> for SortedVec<T> {
-    fn from(unsorted: Vec<T>) -> Self {
-        Self::from_unsorted(unsorted)
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        SortedVec {
+            vec: Vec::with_capacity_in(capacity, alloc),
+            order: PhantomData,
+        }
     }
 }
-impl<T: Ord> std::ops::Deref for SortedVec<T> {
-    type Target = Vec<T>;
-    fn deref(&self) -> &Vec<T> {
-        &self.vec
+
+#[cfg(feature = "allocator_api")]
+impl<T: Ord, O: SortOrder, A: Allocator> SortedVec<T, O, A> {
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.vec.allocator()
     }
-}
-impl<T: Ord> Extend<T> for SortedVec<T> {
-    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        for t in iter {
-            let _ = self.insert(t);
-        }
+    #[cfg(feature = "serde")]
+    fn serialize_vec_in<S>(vec: &Vec<T, A>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: serde::Serialize,
+    {
+        serializer.collect_seq(vec)
     }
-}
-impl<T: Ord + Hash> Hash for SortedVec<T> {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        let v: &Vec<T> = self.as_ref();
-        v.hash(state);
+    #[cfg(feature = "serde")]
+    fn parse_vec_in<'de, D>(deserializer: D) -> Result<Vec<T, A>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+        A: Default,
+    {
+        let parsed = de::deserialize_sorted_vec(deserializer, usize::MAX, Self::check_next)?;
+        let mut vec = Vec::with_capacity_in(parsed.len(), A::default());
+        vec.extend(parsed);
+        Ok(vec)
     }
 }
 
-//
-//  impl SortedSet
-//
-
-impl<T: Ord> SortedSet<T> {
+impl<T: Ord> SortedVec<T> {
     #[inline]
     pub fn new() -> Self {
-        SortedSet {
-            set: SortedVec::new(),
-        }
+        Self::with_order(Ascending)
     }
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
-        SortedSet {
-            set: SortedVec::with_capacity(capacity),
-        }
+        Self::with_capacity_and_order(capacity, Ascending)
     }
-    /// Uses `sort_unstable()` to sort in place and `dedup()` to remove
-    /// duplicates.
+    /// Convert into a container sorted from greatest to least by reversing the
+    /// vector in place, without re-sorting.
     #[inline]
-    pub fn from_unsorted(vec: Vec<T>) -> Self {
-        let mut set = SortedVec::from_unsorted(vec);
-        set.dedup();
-        SortedSet { set }
+    pub fn into_reverse(mut self) -> ReverseSortedVec<T> {
+        self.vec.reverse();
+        SortedVec {
+            vec: self.vec,
+            order: PhantomData,
+        }
     }
-    /// Insert an element into sorted position, returning the order index at which
+    /// Collect an iterator that is known to be sorted without sorting.
+    #[inline]
+    pub fn from_sorted_iter<I>(iter: I) -> Self
+    where
+        I: SortedIterator<Item = T>,
+    {
+        SortedVec {
+            vec: iter.collect(),
+            order: PhantomData,
+        }
+    }
+}
+
+impl<T: Ord, #[cfg(feature = "allocator_api")] A: Allocator> with_alloc!(SortedVec<T, Ascending>, A) {
+    /// Uses `sort_unstable()` to sort in place.
+    ///
+    /// Input that is already sorted, or sorted in reverse, is detected in O(n)
+    /// and only reversed if needed.
+    #[inline]
+    pub fn from_unsorted(vec: with_alloc!(Vec<T>, A)) -> Self {
+        Self::from_unsorted_with_order(vec, Ascending)
+    }
+    /// The caller must ensure that the provided vector is already sorted.
+    pub unsafe fn from_unsorted_unchecked(vec: with_alloc!(Vec<T>, A)) -> Self {
+        SortedVec {
+            vec,
+            order: PhantomData,
+        }
+    }
+}
+
+impl<T: Ord, O: SortOrder, #[cfg(feature = "allocator_api")] A: Allocator> with_alloc!(SortedVec<T, O>, A) {
+    /// Iterator over the elements whose values fall within `range`, located
+    /// with two binary searches. An empty iterator is returned if the start of
+    /// the range is after its end in the order of the container.
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, T, O>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: std::ops::RangeBounds<Q>,
+    {
+        Iter {
+            iter: self.vec[self.range_indices(range)].iter(),
+            order: PhantomData,
+        }
+    }
+    /// Binary search by a key extracted from each element. The order of the
+    /// keys must agree with the order of the elements, e.g. when `T` is sorted
+    /// by a field and `f` returns that field.
+    #[inline]
+    pub fn binary_search_by_key<B, F>(&self, b: &B, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> B,
+        B: Ord,
+    {
+        self.vec.binary_search_by(|y| O::compare(&f(y), b))
+    }
+    /// An element whose key is `b`. See `binary_search_by_key`.
+    #[inline]
+    pub fn find_by_key<B, F>(&self, b: &B, f: F) -> Option<&T>
+    where
+        F: FnMut(&T) -> B,
+        B: Ord,
+    {
+        self.binary_search_by_key(b, f).ok().map(|i| &self.vec[i])
+    }
+    /// Remove an element whose key is `b`. See `binary_search_by_key`.
+    #[inline]
+    pub fn remove_by_key<B, F>(&mut self, b: &B, f: F) -> Option<T>
+    where
+        F: FnMut(&T) -> B,
+        B: Ord,
+    {
+        let index = self.binary_search_by_key(b, f).ok()?;
+        Some(self.vec.remove(index))
+    }
+    /// Index of the first element that is not less than `x`, i.e. where `x`
+    /// would be inserted before any equal elements.
+    ///
+    /// Here and in the other searches "less" and "greater" refer to the order
+    /// of the container: in a `ReverseSortedVec` this is the first element not
+    /// greater than `x`.
+    #[inline]
+    pub fn lower_bound<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::lower_bound::<O, T, Q>(&self.vec, x)
+    }
+    /// Index of the first element that is greater than `x`, i.e. where `x`
+    /// would be inserted after any equal elements.
+    #[inline]
+    pub fn upper_bound<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::upper_bound::<O, T, Q>(&self.vec, x)
+    }
+    /// Index of the first element for which `pred` is false. `pred` must hold
+    /// for a (possibly empty) prefix of the elements in sorted order and fail
+    /// for the rest. See `slice::partition_point`.
+    #[inline]
+    pub fn partition_point<P>(&self, pred: P) -> usize
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.vec.partition_point(pred)
+    }
+    /// Number of elements strictly less than `x`. O(log n).
+    #[inline]
+    pub fn rank<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.lower_bound(x)
+    }
+    /// Number of elements less than or equal to `x`. O(log n).
+    #[inline]
+    pub fn rank_inclusive<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.upper_bound(x)
+    }
+    /// The greatest element less than or equal to `x`. O(log n).
+    #[inline]
+    pub fn floor<Q>(&self, x: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::floor::<O, T, Q>(&self.vec, x)
+    }
+    /// The least element greater than or equal to `x`. O(log n).
+    #[inline]
+    pub fn ceiling<Q>(&self, x: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::ceiling::<O, T, Q>(&self.vec, x)
+    }
+    /// The element closest to `x` according to `distance`, chosen between the
+    /// `floor` and the `ceiling` of `x`. Ties are resolved in favor of the
+    /// floor.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let v = SortedVec::from_unsorted(vec![10, 20, 40]);
+    /// let distance = |a: &i32, b: &i32| a.abs_diff(*b);
+    /// assert_eq!(v.nearest(&33, distance), Some(&40));
+    /// assert_eq!(v.nearest(&30, distance), Some(&20));
+    /// ```
+    pub fn nearest<F, D>(&self, x: &T, mut distance: F) -> Option<&T>
+    where
+        F: FnMut(&T, &T) -> D,
+        D: PartialOrd,
+    {
+        match (self.floor(x), self.ceiling(x)) {
+            (Some(floor), Some(ceiling)) => {
+                if distance(ceiling, x) < distance(floor, x) {
+                    Some(ceiling)
+                } else {
+                    Some(floor)
+                }
+            }
+            (floor, ceiling) => floor.or(ceiling),
+        }
+    }
+    /// Number of elements equal to `x`, from a leftmost and a rightmost binary
+    /// search. O(log n).
+    #[inline]
+    pub fn count<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.equal_range_indices(x).len()
+    }
+    /// Index of the leftmost element equal to `x`. Unlike `binary_search` the
+    /// result does not depend on the number of duplicates.
+    #[inline]
+    pub fn first_index_of<Q>(&self, x: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.lower_bound(x);
+        match self.vec.get(index) {
+            Some(y) if y.borrow() == x => Some(index),
+            _ => None,
+        }
+    }
+    /// Index of the rightmost element equal to `x`.
+    #[inline]
+    pub fn last_index_of<Q>(&self, x: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.upper_bound(x).checked_sub(1)?;
+        if self.vec[index].borrow() == x {
+            Some(index)
+        } else {
+            None
+        }
+    }
+    /// The slice of all elements equal to `x`, empty if there are none.
+    #[inline]
+    pub fn equal_range<Q>(&self, x: &Q) -> &[T]
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        &self.vec[self.equal_range_indices(x)]
+    }
+    /// Index range of the elements equal to `x`, found with a leftmost and a
+    /// rightmost binary search. If there are none, the empty range is located
+    /// at the position where `x` would be inserted.
+    pub fn equal_range_indices<Q>(&self, x: &Q) -> std::ops::Range<usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        search::equal_range_indices::<O, T, Q>(&self.vec, x)
+    }
+    fn range_indices<Q, R>(&self, range: R) -> std::ops::Range<usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: std::ops::RangeBounds<Q>,
+    {
+        search::range_indices::<O, T, Q, R>(&self.vec, range)
+    }
+    /// Iterator over the distinct elements and the length of their runs
+    #[inline]
+    pub fn counts(&self) -> Counts<'_, T, O> {
+        Counts {
+            rest: &self.vec,
+            order: PhantomData,
+        }
+    }
+    /// Same as `dedup`, additionally returning the length of each run of equal
+    /// elements, so that the `i`th count belongs to the `i`th remaining
+    /// element. See `counts` for an iterator that leaves the vector unchanged.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let mut v = SortedVec::from_unsorted(vec!['b', 'a', 'b', 'c', 'b']);
+    /// assert_eq!(v.dedup_with_count(), vec![1, 3, 1]);
+    /// assert_eq!(*v, vec!['a', 'b', 'c']);
+    /// ```
+    pub fn dedup_with_count(&mut self) -> Vec<usize> {
+        let counts = self.counts().map(|(_, count)| count).collect();
+        self.vec.dedup();
+        counts
+    }
+}
+
+impl<T: Ord, O: SortOrder> SortedVec<T, O> {
+    /// Collapse each run of equal elements into a single element by folding
+    /// the run from the left with `f`. `f` should return an element equal to
+    /// its arguments, e.g. when summing the quantities of order book levels
+    /// ordered by price; otherwise the vector is re-sorted.
+    pub fn coalesce_duplicates<F>(&mut self, mut f: F)
+    where
+        F: FnMut(T, T) -> T,
+    {
+        // find the runs before folding, as `f` may return an element that
+        // equals the start of the next run
+        let runs: Vec<usize> = self.counts().map(|(_, count)| count).collect();
+        let mut elements = std::mem::take(&mut self.vec).into_iter();
+        let mut coalesced: Vec<T> = Vec::with_capacity(runs.len());
+        for run in runs {
+            let first = elements.next().expect("runs cover every element");
+            coalesced.push(elements.by_ref().take(run - 1).fold(first, &mut f));
+        }
+        if !coalesced
+            .windows(2)
+            .all(|w| O::compare(&w[0], &w[1]).is_le())
+        {
+            coalesced.sort_by(O::compare);
+        }
+        self.vec = coalesced;
+    }
+    /// Merge with another sorted vector in O(n + m). When an element of `self`
+    /// compares equal to an element of `other`, the pair is combined with
+    /// `resolve(self_element, other_element)` instead of keeping both.
+    ///
+    /// `resolve` should return an element comparing equal to its arguments;
+    /// otherwise the merged vector is re-sorted.
+    pub fn merge_resolve<F>(self, other: Self, resolve: F) -> Self
+    where
+        F: FnMut(T, T) -> T,
+    {
+        SortedVec {
+            vec: merge_resolve_vec::<O, _, _>(self.vec, other.vec, resolve),
+            order: PhantomData,
+        }
+    }
+}
+impl<T: Ord, O: SortOrder> Default for SortedVec<T, O> {
+    fn default() -> Self {
+        SortedVec {
+            vec: Vec::new(),
+            order: PhantomData,
+        }
+    }
+}
+impl<T: Ord, O: SortOrder, #[cfg(feature = "allocator_api")] A: Allocator>
+    From<with_alloc!(Vec<T>, A)> for with_alloc!(SortedVec<T, O>, A)
+{
+    fn from(unsorted: with_alloc!(Vec<T>, A)) -> Self {
+        Self::sort_in_order(unsorted)
+    }
+}
+impl<T: Ord + std::fmt::Debug, O: SortOrder, #[cfg(feature = "allocator_api")] A: Allocator>
+    std::fmt::Debug for with_alloc!(SortedVec<T, O>, A)
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SortedVec").field("vec", &self.vec).finish()
+    }
+}
+impl<T: Ord, O: SortOrder, #[cfg(feature = "allocator_api")] A: Allocator> std::ops::Deref for with_alloc!(SortedVec<T, O>, A) {
+    type Target = with_alloc!(Vec<T>, A);
+    fn deref(&self) -> &with_alloc!(Vec<T>, A) {
+        &self.vec
+    }
+}
+impl<T: Ord, O: SortOrder> FromIterator<T> for SortedVec<T, O> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        #[cfg(feature = "unstable")]
+        {
+            spec::SpecFromIter::spec_from_iter(iter.into_iter())
+        }
+        #[cfg(not(feature = "unstable"))]
+        {
+            Self::sort_in_order(iter.into_iter().collect())
+        }
+    }
+}
+impl<T: Ord, O: SortOrder> IntoIterator for SortedVec<T, O> {
+    type Item = T;
+    type IntoIter = IntoIter<T, O>;
+    fn into_iter(self) -> IntoIter<T, O> {
+        IntoIter {
+            iter: self.vec.into_iter(),
+            order: PhantomData,
+        }
+    }
+}
+impl<'a, T: Ord, O: SortOrder> IntoIterator for &'a SortedVec<T, O> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, O>;
+    fn into_iter(self) -> Iter<'a, T, O> {
+        Iter {
+            iter: self.vec.iter(),
+            order: PhantomData,
+        }
+    }
+}
+impl<T: Ord, O: SortOrder> Extend<T> for SortedVec<T, O> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        #[cfg(feature = "unstable")]
+        {
+            spec::SpecExtend::spec_extend(self, iter.into_iter())
+        }
+        #[cfg(not(feature = "unstable"))]
+        self.merge_unsorted_batch(iter.into_iter().collect())
+    }
+}
+impl<T: Ord + Hash, O: SortOrder> Hash for SortedVec<T, O> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let v: &Vec<T> = self.as_ref();
+        v.hash(state);
+    }
+}
+
+//
+//  impl SortedSet
+//
+
+impl<T: Ord, O: SortOrder> SortedSet<T, O> {
+    /// Empty set sorted in the given order, e.g.
+    /// `SortedSet::with_order(Descending)`.
+    #[inline]
+    pub fn with_order(order: O) -> Self {
+        SortedSet {
+            set: SortedVec::with_order(order),
+        }
+    }
+    #[inline]
+    pub fn with_capacity_and_order(capacity: usize, order: O) -> Self {
+        SortedSet {
+            set: SortedVec::with_capacity_and_order(capacity, order),
+        }
+    }
+    /// Perform deduplication and sorting on the input sequence when deserializing
+    /// with `serde`.
+    ///
+    /// Use with
+    /// `#[serde(deserialize_with = "SortedSet::deserialize_dedup_unsorted")]`:
+    /// ```text
+    /// #[derive(Debug, Eq, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+    /// pub struct Foo {
+    ///   #[serde(deserialize_with = "SortedSet::deserialize_dedup_unsorted")]
+    ///   pub s : SortedSet <u64>
+    /// }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn deserialize_dedup_unsorted<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        use serde::Deserialize;
+        let v = Vec::deserialize(deserializer)?;
+        let mut set = SortedVec::sort_in_order(v);
+        set.dedup();
+        Ok(SortedSet { set })
+    }
+    /// Like the `Deserialize` implementation but failing as soon as the input
+    /// has more than `max_len` elements, see `SortedVec::deserialize_bounded`.
+    #[cfg(feature = "serde")]
+    pub fn deserialize_bounded<'de, D>(deserializer: D, max_len: usize) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        let vec = de::deserialize_sorted_vec(deserializer, max_len, Self::check_next)?;
+        Ok(SortedSet {
+            set: SortedVec {
+                vec,
+                order: PhantomData,
+            },
+        })
+    }
+    /// Remove duplicates from the input sequence when deserializing with
+    /// `serde`, still failing if it is not sorted.
+    ///
+    /// Use with `#[serde(deserialize_with = "SortedSet::deserialize_dedup")]`.
+    #[cfg(feature = "serde")]
+    pub fn deserialize_dedup<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        let mut vec =
+            de::deserialize_sorted_vec(deserializer, usize::MAX, SortedVec::<T, O>::check_next)?;
+        vec.dedup();
+        Ok(SortedSet {
+            set: SortedVec {
+                vec,
+                order: PhantomData,
+            },
+        })
+    }
+    #[cfg(all(feature = "serde", not(feature = "allocator_api")))]
+    fn parse_vec<'de, D>(deserializer: D) -> Result<SortedVec<T, O>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        let vec = de::deserialize_sorted_vec(deserializer, usize::MAX, Self::check_next)?;
+        Ok(SortedVec {
+            vec,
+            order: PhantomData,
+        })
+    }
+}
+
+impl<T: Ord, O: SortOrder, #[cfg(feature = "allocator_api")] A: Allocator> with_alloc!(SortedSet<T, O>, A) {
+    /// Same as `from_unsorted`, sorting in the given order.
+    #[inline]
+    pub fn from_unsorted_with_order(vec: with_alloc!(Vec<T>, A), _order: O) -> Self {
+        Self::sort_in_order(vec)
+    }
+    fn sort_in_order(vec: with_alloc!(Vec<T>, A)) -> Self {
+        let mut set = SortedVec::sort_in_order(vec);
+        set.dedup();
+        SortedSet { set }
+    }
+    /// Insert an element into sorted position, returning the order index at which
     /// it was placed. If an existing item was found it will be returned.
     #[inline]
     pub fn replace(&mut self, mut element: T) -> (usize, Option<T>) {
@@ -410,7 +1443,7 @@ impl<T: Ord> SortedSet<T> {
     #[inline]
     pub fn push(&mut self, element: T) -> (usize, Option<T>) {
         if let Some(last) = self.vec.last() {
-            let cmp = element.cmp(last);
+            let cmp = O::compare(&element, last);
             if cmp == std::cmp::Ordering::Greater {
                 // The new element is greater than the current last element, so we can
                 // simply push it onto the vec.
@@ -434,6 +1467,24 @@ impl<T: Ord> SortedSet<T> {
             return (0, None);
         }
     }
+    /// Same as `SortedVec::push_max`, except that an element equal to the
+    /// last element is also given back.
+    #[inline]
+    pub fn push_max(&mut self, element: T) -> Result<usize, T> {
+        match self.vec.last() {
+            Some(last) if O::compare(&element, last) != std::cmp::Ordering::Greater => Err(element),
+            _ => self.set.push_max(element),
+        }
+    }
+    /// Same as `SortedVec::push_min`, except that an element equal to the
+    /// first element is also given back.
+    #[inline]
+    pub fn push_min(&mut self, element: T) -> Result<usize, T> {
+        match self.vec.first() {
+            Some(first) if O::compare(&element, first) != std::cmp::Ordering::Less => Err(element),
+            _ => self.set.push_min(element),
+        }
+    }
     /// Reserves additional capacity in the underlying vector.
     /// See std::vec::Vec::reserve.
     #[inline]
@@ -446,9 +1497,22 @@ impl<T: Ord> SortedSet<T> {
         self.set.find_or_insert(element)
     }
     #[inline]
-    pub fn remove_item(&mut self, item: &T) -> Option<T> {
+    pub fn remove_item<Q>(&mut self, item: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         self.set.remove_item(item)
     }
+    /// See `SortedVec::remove_items`
+    #[inline]
+    pub fn remove_items<Q>(&mut self, sorted_items: &[Q]) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord,
+    {
+        self.set.remove_items(sorted_items)
+    }
     /// Panics if index is out of bounds
     #[inline]
     pub fn remove_index(&mut self, index: usize) -> T {
@@ -463,7 +1527,7 @@ impl<T: Ord> SortedSet<T> {
         self.set.clear()
     }
     #[inline]
-    pub fn drain<R>(&mut self, range: R) -> std::vec::Drain<T>
+    pub fn drain<R>(&mut self, range: R) -> with_alloc!(std::vec::Drain<'_, T>, A)
     where
         R: std::ops::RangeBounds<usize>,
     {
@@ -476,129 +1540,594 @@ impl<T: Ord> SortedSet<T> {
     {
         self.set.retain(f)
     }
+    /// Same as `retain`, except that `f` may mutate the elements. Uses
+    /// `sort_unstable_by()` to re-sort and `dedup()` to remove any duplicates
+    /// among the retained elements.
+    pub fn retain_mut<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        self.mutate_vec(|vec| vec.retain_mut(f))
+    }
     /// NOTE: to_vec() is a slice method that is accessible through deref, use
     /// this instead to avoid cloning
     #[inline]
-    pub fn into_vec(self) -> Vec<T> {
+    pub fn into_vec(self) -> with_alloc!(Vec<T>, A) {
         self.set.into_vec()
     }
-    /// Apply a closure mutating the sorted vector and use `sort_unstable()`
+    /// Apply a closure mutating the sorted vector and use `sort_unstable_by()`
     /// to re-sort the mutated vector and `dedup()` to remove any duplicate
     /// values
-    pub fn mutate_vec<F, O>(&mut self, f: F) -> O
+    pub fn mutate_vec<F, R>(&mut self, f: F) -> R
     where
-        F: FnOnce(&mut Vec<T>) -> O,
+        F: FnOnce(&mut with_alloc!(Vec<T>, A)) -> R,
     {
         let res = self.set.mutate_vec(f);
         self.set.dedup();
         res
     }
+    /// Same as `mutate_vec`, except that after re-sorting, consecutive elements
+    /// that are equal or related by `same_bucket` are removed, e.g. to
+    /// deduplicate case-insensitively.
+    pub fn mutate_vec_dedup_by<F, R, D>(&mut self, f: F, mut same_bucket: D) -> R
+    where
+        F: FnOnce(&mut with_alloc!(Vec<T>, A)) -> R,
+        D: FnMut(&mut T, &mut T) -> bool,
+    {
+        let res = self.set.mutate_vec(f);
+        self.set.dedup_by(|a, b| a == b || same_bucket(a, b));
+        res
+    }
+    /// Remove consecutive elements related by `same_bucket`, see
+    /// `Vec::dedup_by`.
+    #[inline]
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        self.set.dedup_by(same_bucket);
+    }
     /// Unsafe access to the underlying vector. The caller must ensure that any
     /// changes to the values in the vector do not impact the ordering of the
     /// elements inside, or else this container will misbehave.
-    pub unsafe fn get_unchecked_mut_vec(&mut self) -> &mut Vec<T> {
+    pub unsafe fn get_unchecked_mut_vec(&mut self) -> &mut with_alloc!(Vec<T>, A) {
         return self.set.get_unchecked_mut_vec();
     }
+    /// Mutable access to the underlying vector without re-sorting, analogous to
+    /// `String::as_mut_vec`.
+    ///
+    /// # Safety
+    ///
+    /// The vector must be sorted and must not contain duplicates when the borrow
+    /// ends.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedSet;
+    ///
+    /// let mut s = SortedSet::from_unsorted(vec![1, 5, 3]);
+    /// // appending a greater element keeps the set sorted and unique
+    /// unsafe { s.as_mut_vec().push(7) };
+    /// assert_eq!(s.binary_search(&7), Ok(3));
+    /// ```
+    #[inline]
+    pub unsafe fn as_mut_vec(&mut self) -> &mut with_alloc!(Vec<T>, A) {
+        self.get_unchecked_mut_vec()
+    }
+    #[cfg(feature = "serde")]
+    fn serialize_vec<S>(
+        set: &with_alloc!(SortedVec<T, O>, A),
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        T: serde::Serialize,
+    {
+        serializer.collect_seq(set.iter())
+    }
+    /// Checks each element against the previous one as it is deserialized
+    #[cfg(feature = "serde")]
+    fn check_next(prev: Option<&T>, x: &T) -> Result<(), &'static str> {
+        match prev.map(|prev| O::compare(prev, x)) {
+            Some(std::cmp::Ordering::Greater) => Err("input set is not sorted"),
+            Some(std::cmp::Ordering::Equal) => Err("input set contains duplicate values"),
+            _ => Ok(()),
+        }
+    }
+}
 
-    /// Perform deduplication and sorting on the input sequence when deserializing
-    /// with `serde`.
+#[cfg(feature = "allocator_api")]
+impl<T: Ord, A: Allocator> SortedSet<T, Ascending, A> {
+    /// Empty set storing its elements with the allocator `alloc`.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        SortedSet {
+            set: SortedVec::new_in(alloc),
+        }
+    }
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        SortedSet {
+            set: SortedVec::with_capacity_in(capacity, alloc),
+        }
+    }
+}
+
+#[cfg(all(feature = "allocator_api", feature = "serde"))]
+impl<T: Ord, O: SortOrder, A: Allocator> SortedSet<T, O, A> {
+    fn parse_vec_in<'de, D>(deserializer: D) -> Result<SortedVec<T, O, A>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+        A: Default,
+    {
+        let parsed = de::deserialize_sorted_vec(deserializer, usize::MAX, Self::check_next)?;
+        let mut vec = Vec::with_capacity_in(parsed.len(), A::default());
+        vec.extend(parsed);
+        Ok(SortedVec {
+            vec,
+            order: PhantomData,
+        })
+    }
+}
+
+impl<T: Ord> SortedSet<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_order(Ascending)
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_order(capacity, Ascending)
+    }
+    /// Uses the stable `sort()` so that equal elements keep their input order,
+    /// then keeps the first or last occurrence of each according to `policy`.
     ///
-    /// Use with
-    /// `#[serde(deserialize_with = "SortedSet::deserialize_dedup_unsorted")]`:
-    /// ```text
-    /// #[derive(Debug, Eq, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
-    /// pub struct Foo {
-    ///   #[serde(deserialize_with = "SortedSet::deserialize_dedup_unsorted")]
-    ///   pub s : SortedSet <u64>
+    /// ```
+    /// use sorted_vec2::{DedupPolicy, SortedSet};
+    ///
+    /// #[derive(Debug, Eq)]
+    /// struct Quote { symbol: &'static str, price: u32 }
+    /// impl PartialEq for Quote {
+    ///     fn eq(&self, other: &Self) -> bool { self.symbol == other.symbol }
+    /// }
+    /// impl Ord for Quote {
+    ///     fn cmp(&self, other: &Self) -> std::cmp::Ordering { self.symbol.cmp(other.symbol) }
     /// }
+    /// impl PartialOrd for Quote {
+    ///     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+    /// }
+    ///
+    /// let quotes = vec![
+    ///     Quote { symbol: "B", price: 1 },
+    ///     Quote { symbol: "A", price: 2 },
+    ///     Quote { symbol: "B", price: 3 },
+    /// ];
+    /// let latest = SortedSet::from_unsorted_with(quotes, DedupPolicy::KeepLast);
+    /// assert_eq!(latest.iter().map(|q| q.price).collect::<Vec<_>>(), vec![2, 3]);
     /// ```
-    #[cfg(feature = "serde")]
-    pub fn deserialize_dedup_unsorted<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    pub fn from_unsorted_with(mut vec: Vec<T>, policy: DedupPolicy) -> Self {
+        vec.sort();
+        let mut set = SortedVec {
+            vec,
+            order: PhantomData,
+        };
+        set.dedup_with_policy(policy);
+        SortedSet { set }
+    }
+    /// Convert into a set sorted from greatest to least by reversing the vector
+    /// in place, without re-sorting.
+    #[inline]
+    pub fn into_reverse(self) -> ReverseSortedSet<T> {
+        SortedSet {
+            set: self.set.into_reverse(),
+        }
+    }
+    /// Collect an iterator that is known to be sorted and unique without sorting
+    /// or removing duplicates.
+    #[inline]
+    pub fn from_sorted_iter<I>(iter: I) -> Self
+    where
+        I: UniqueSortedIterator<Item = T>,
+    {
+        SortedSet {
+            set: SortedVec::from_sorted_iter(iter),
+        }
+    }
+}
+
+impl<T: Ord, #[cfg(feature = "allocator_api")] A: Allocator> with_alloc!(SortedSet<T, Ascending>, A) {
+    /// Uses `sort_unstable()` to sort in place and `dedup()` to remove
+    /// duplicates.
+    #[inline]
+    pub fn from_unsorted(vec: with_alloc!(Vec<T>, A)) -> Self {
+        Self::from_unsorted_with_order(vec, Ascending)
+    }
+}
+
+impl<T: Ord, O: SortOrder, #[cfg(feature = "allocator_api")] A: Allocator> with_alloc!(SortedSet<T, O>, A) {
+    /// Iterator over the elements whose values fall within `range`, see
+    /// `SortedVec::range`.
+    pub fn range<Q, R>(&self, range: R) -> SetIter<'_, T, O>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: std::ops::RangeBounds<Q>,
+    {
+        SetIter {
+            iter: self.set.vec[self.set.range_indices(range)].iter(),
+            order: PhantomData,
+        }
+    }
+    /// See `SortedVec::remove_by_key`
+    #[inline]
+    pub fn remove_by_key<B, F>(&mut self, b: &B, f: F) -> Option<T>
     where
-        D: serde::Deserializer<'de>,
-        T: serde::Deserialize<'de>,
+        F: FnMut(&T) -> B,
+        B: Ord,
     {
-        use serde::Deserialize;
-        let v = Vec::deserialize(deserializer)?;
-        Ok(SortedSet::from_unsorted(v))
+        self.set.remove_by_key(b, f)
     }
+}
 
-    #[cfg(feature = "serde")]
-    fn parse_vec<'de, D>(deserializer: D) -> Result<SortedVec<T>, D::Error>
+impl<T: Ord, O: SortOrder> SortedSet<T, O> {
+    /// Merge with another sorted set in O(n + m). Elements contained in both sets
+    /// are combined with `resolve(self_element, other_element)`.
+    ///
+    /// `resolve` should return an element comparing equal to its arguments;
+    /// otherwise the merged set is re-sorted and deduplicated.
+    pub fn merge_resolve<F>(self, other: Self, resolve: F) -> Self
     where
-        D: serde::Deserializer<'de>,
-        T: serde::Deserialize<'de>,
+        F: FnMut(T, T) -> T,
     {
-        use serde::de::Error;
-        use serde::Deserialize;
-        let mut vec = Vec::deserialize(deserializer)?;
-        let input_len = vec.len();
-        vec.dedup();
-        if vec.len() != input_len {
-            return Err(D::Error::custom("input set contains duplicate values"));
+        let mut set = SortedVec {
+            vec: merge_resolve_vec::<O, _, _>(self.set.vec, other.set.vec, resolve),
+            order: PhantomData,
         };
-        let is_sorted = {
-            let mut iter = vec.iter();
-            IsSorted::is_sorted(&mut iter)
-        };
-        if !is_sorted {
-            Err(D::Error::custom("input set is not sorted"))
-        } else {
-            Ok(SortedVec { vec })
-        }
+        set.dedup();
+        SortedSet { set }
+    }
+    /// In-place version of `merge_resolve`.
+    pub fn merge_resolve_in_place<F>(&mut self, other: Self, resolve: F)
+    where
+        F: FnMut(T, T) -> T,
+    {
+        *self = std::mem::take(self).merge_resolve(other, resolve);
     }
 }
-impl<T: Ord> Default for SortedSet<T> {
+impl<T: Ord, O: SortOrder> Default for SortedSet<T, O> {
     fn default() -> Self {
-        Self::new()
+        SortedSet {
+            set: SortedVec::default(),
+        }
     }
 }
-impl<T: Ord> From<Vec<T>> for SortedSet<T> {
-    fn from(unsorted: Vec<T>) -> Self {
-        Self::from_unsorted(unsorted)
+impl<T: Ord, O: SortOrder, #[cfg(feature = "allocator_api")] A: Allocator>
+    From<with_alloc!(Vec<T>, A)> for with_alloc!(SortedSet<T, O>, A)
+{
+    fn from(unsorted: with_alloc!(Vec<T>, A)) -> Self {
+        Self::sort_in_order(unsorted)
     }
 }
-impl<T: Ord> std::ops::Deref for SortedSet<T> {
-    type Target = SortedVec<T>;
-    fn deref(&self) -> &SortedVec<T> {
+impl<T: Ord, O: SortOrder, #[cfg(feature = "allocator_api")] A: Allocator> std::ops::Deref for with_alloc!(SortedSet<T, O>, A) {
+    type Target = with_alloc!(SortedVec<T, O>, A);
+    fn deref(&self) -> &with_alloc!(SortedVec<T, O>, A) {
         &self.set
     }
 }
-impl<T: Ord> Extend<T> for SortedSet<T> {
+impl<T: Ord, O: SortOrder> FromIterator<T> for SortedSet<T, O> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        #[cfg(feature = "unstable")]
+        {
+            spec::SpecFromIter::spec_from_iter(iter.into_iter())
+        }
+        #[cfg(not(feature = "unstable"))]
+        {
+            Self::sort_in_order(iter.into_iter().collect())
+        }
+    }
+}
+impl<T: Ord, O: SortOrder> IntoIterator for SortedSet<T, O> {
+    type Item = T;
+    type IntoIter = SetIntoIter<T, O>;
+    fn into_iter(self) -> SetIntoIter<T, O> {
+        SetIntoIter {
+            iter: self.set.vec.into_iter(),
+            order: PhantomData,
+        }
+    }
+}
+impl<'a, T: Ord, O: SortOrder> IntoIterator for &'a SortedSet<T, O> {
+    type Item = &'a T;
+    type IntoIter = SetIter<'a, T, O>;
+    fn into_iter(self) -> SetIter<'a, T, O> {
+        SetIter {
+            iter: self.set.vec.iter(),
+            order: PhantomData,
+        }
+    }
+}
+impl<T: Ord, O: SortOrder> Extend<T> for SortedSet<T, O> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        for t in iter {
-            let _ = self.find_or_insert(t);
+        #[cfg(feature = "unstable")]
+        {
+            spec::SpecExtend::spec_extend(self, iter.into_iter())
         }
+        #[cfg(not(feature = "unstable"))]
+        self.merge_unsorted_batch(iter.into_iter().collect())
     }
 }
-impl<T: Ord + Hash> Hash for SortedSet<T> {
+impl<T: Ord + Hash, O: SortOrder> Hash for SortedSet<T, O> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         let v: &Vec<T> = self.as_ref();
         v.hash(state);
     }
 }
 
+/// Comparisons of the containers by their elements, which unlike the derived
+/// ones do not require the allocator to be comparable
+#[cfg(feature = "allocator_api")]
+macro_rules! impl_alloc_cmp {
+    ($name:ident, $field:ident) => {
+        impl<T: Ord, O: SortOrder, A: Allocator> PartialEq for $name<T, O, A> {
+            fn eq(&self, other: &Self) -> bool {
+                self.$field == other.$field
+            }
+        }
+        impl<T: Ord, O: SortOrder, A: Allocator> Eq for $name<T, O, A> {}
+        impl<T: Ord, O: SortOrder, A: Allocator> PartialOrd for $name<T, O, A> {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl<T: Ord, O: SortOrder, A: Allocator> Ord for $name<T, O, A> {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.$field.cmp(&other.$field)
+            }
+        }
+    };
+}
+#[cfg(feature = "allocator_api")]
+impl_alloc_cmp!(SortedVec, vec);
+#[cfg(feature = "allocator_api")]
+impl_alloc_cmp!(SortedSet, set);
+
+//
+//  iterators
+//
+
+/// Marker for iterators yielding elements from least to greatest.
+///
+/// Containers can be built from a `SortedIterator` without sorting, see
+/// `SortedVec::from_sorted_iter`.
+///
+/// # Safety
+///
+/// Implementors must guarantee that each element yielded by `next()` is
+/// greater than or equal to the previous one, the same contract as
+/// `SortedVec::from_unsorted_unchecked`. Iterators of the crate's containers
+/// and of `BTreeSet`/`BTreeMap` keys implement this trait, as do the std
+/// adaptors that can only drop elements.
+pub unsafe trait SortedIterator: Iterator {}
+
+/// Marker for iterators yielding elements from least to greatest without
+/// duplicates.
+///
+/// # Safety
+///
+/// Implementors must guarantee that each element yielded by `next()` is
+/// strictly greater than the previous one.
+pub unsafe trait UniqueSortedIterator: SortedIterator {}
+
+macro_rules! impl_iter {
+    ($name:ident <$($lt:lifetime,)? $t:ident $(, $o:ident)?> -> $item:ty) => {
+        impl<$($lt,)? $t $(, $o)?> Iterator for $name<$($lt,)? $t $(, $o)?> {
+            type Item = $item;
+            #[inline]
+            fn next(&mut self) -> Option<$item> {
+                self.iter.next()
+            }
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
+        }
+        impl<$($lt,)? $t $(, $o)?> DoubleEndedIterator for $name<$($lt,)? $t $(, $o)?> {
+            #[inline]
+            fn next_back(&mut self) -> Option<$item> {
+                self.iter.next_back()
+            }
+        }
+        impl<$($lt,)? $t $(, $o)?> ExactSizeIterator for $name<$($lt,)? $t $(, $o)?> {}
+        impl<$($lt,)? $t $(, $o)?> std::iter::FusedIterator for $name<$($lt,)? $t $(, $o)?> {}
+        unsafe impl<$($lt,)? $t> SortedIterator
+            for $name<$($lt,)? $t $(, impl_iter!(@ascending $o))?>
+        {
+        }
+    };
+    // only ascending iterators are `SortedIterator`s
+    (@ascending $o:ident) => {
+        Ascending
+    };
+}
+
+/// Consuming iterator over the elements of a `SortedVec` in sorted order
+#[derive(Clone, Debug)]
+pub struct IntoIter<T, O = Ascending> {
+    iter: std::vec::IntoIter<T>,
+    order: PhantomData<O>,
+}
+impl_iter!(IntoIter<T, O> -> T);
+
+/// Consuming iterator over the elements of a `SortedSet` in sorted order
+#[derive(Clone, Debug)]
+pub struct SetIntoIter<T, O = Ascending> {
+    iter: std::vec::IntoIter<T>,
+    order: PhantomData<O>,
+}
+impl_iter!(SetIntoIter<T, O> -> T);
+unsafe impl<T> UniqueSortedIterator for SetIntoIter<T> {}
+
+/// Borrowing iterator over the elements of a `SortedVec` in sorted order
+#[derive(Clone, Debug)]
+pub struct Iter<'a, T, O = Ascending> {
+    iter: std::slice::Iter<'a, T>,
+    order: PhantomData<O>,
+}
+impl_iter!(Iter<'a, T, O> -> &'a T);
+
+/// Iterator over the distinct elements of a `SortedVec` with their number of
+/// occurrences, see `SortedVec::counts`
+#[derive(Clone, Debug)]
+pub struct Counts<'a, T, O = Ascending> {
+    rest: &'a [T],
+    order: PhantomData<O>,
+}
+impl<'a, T: Ord, O> Iterator for Counts<'a, T, O> {
+    type Item = (&'a T, usize);
+    fn next(&mut self) -> Option<(&'a T, usize)> {
+        let first = self.rest.first()?;
+        let count = self
+            .rest
+            .iter()
+            .position(|x| x != first)
+            .unwrap_or(self.rest.len());
+        self.rest = &self.rest[count..];
+        Some((first, count))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.rest.len().min(1), Some(self.rest.len()))
+    }
+}
+impl<T: Ord, O> std::iter::FusedIterator for Counts<'_, T, O> {}
+unsafe impl<T: Ord> SortedIterator for Counts<'_, T> {}
+unsafe impl<T: Ord> UniqueSortedIterator for Counts<'_, T> {}
+
+/// Borrowing iterator over the elements of a `SortedSet` in sorted order
+#[derive(Clone, Debug)]
+pub struct SetIter<'a, T, O = Ascending> {
+    iter: std::slice::Iter<'a, T>,
+    order: PhantomData<O>,
+}
+impl_iter!(SetIter<'a, T, O> -> &'a T);
+unsafe impl<T> UniqueSortedIterator for SetIter<'_, T> {}
+
+// std adaptors yielding a subsequence of a sorted sequence
+macro_rules! impl_sorted_adaptor {
+    ($($adaptor:ident $(<$p:ident>)?),*) => {
+        $(
+            unsafe impl<I: SortedIterator $(, $p)?> SortedIterator
+                for std::iter::$adaptor<I $(, $p)?>
+            where
+                Self: Iterator
+            {
+            }
+            unsafe impl<I: UniqueSortedIterator $(, $p)?> UniqueSortedIterator
+                for std::iter::$adaptor<I $(, $p)?>
+            where
+                Self: Iterator
+            {
+            }
+        )*
+    };
+}
+impl_sorted_adaptor!(
+    Filter<P>,
+    Fuse,
+    Peekable,
+    Skip,
+    SkipWhile<P>,
+    StepBy,
+    Take,
+    TakeWhile<P>
+);
+unsafe impl<'a, T: 'a + Clone, I> SortedIterator for std::iter::Cloned<I> where
+    I: SortedIterator<Item = &'a T>
+{
+}
+unsafe impl<'a, T: 'a + Clone, I> UniqueSortedIterator for std::iter::Cloned<I> where
+    I: UniqueSortedIterator<Item = &'a T>
+{
+}
+unsafe impl<'a, T: 'a + Copy, I> SortedIterator for std::iter::Copied<I> where
+    I: SortedIterator<Item = &'a T>
+{
+}
+unsafe impl<'a, T: 'a + Copy, I> UniqueSortedIterator for std::iter::Copied<I> where
+    I: UniqueSortedIterator<Item = &'a T>
+{
+}
+
+// std ordered collections
+macro_rules! impl_sorted_std {
+    ($($path:ident :: $iter:ident <$($lt:lifetime,)? $($t:ident),*>),*) => {
+        $(
+            unsafe impl<$($lt,)? $($t),*> SortedIterator
+                for std::collections::$path::$iter<$($lt,)? $($t),*> {}
+            unsafe impl<$($lt,)? $($t),*> UniqueSortedIterator
+                for std::collections::$path::$iter<$($lt,)? $($t),*> {}
+        )*
+    };
+}
+impl_sorted_std!(
+    btree_set::Iter<'a, T>,
+    btree_set::IntoIter<T>,
+    btree_set::Range<'a, T>,
+    btree_map::Keys<'a, K, V>,
+    btree_map::IntoKeys<K, V>
+);
+
 /// Reverse-sorted Containers.
 ///
 /// Use these containers to have the vector sorted in the reverse order of its
-/// usual comparison.
-///
-/// Note that objects going into the reverse container needs to be wrapped in
-/// std::cmp::Reverse.
+/// usual comparison. Elements are plain `T`, the order is inverted by the
+/// `Descending` marker, and searches, ranges and set operations follow the
+/// descending order.
 ///
 /// # Examples
 ///
 /// ```
-/// use std::cmp::Reverse;
-/// use sorted_vec::ReverseSortedVec;
+/// use sorted_vec2::ReverseSortedVec;
 ///
-/// let mut vec = ReverseSortedVec::<u64>::new();
-/// vec.insert(Reverse(10));
-/// vec.insert(Reverse(15));
-/// assert_eq!(vec.last().unwrap().0, 10);
+/// let mut vec: ReverseSortedVec<u64> = [10, 15].into_iter().collect();
+/// vec.insert(12);
+/// assert_eq!(vec.last(), Some(&10));
+/// assert_eq!(vec.range(14..=10).collect::<Vec<_>>(), vec![&12, &10]);
+/// assert_eq!(vec.into_iter().collect::<Vec<_>>(), vec![15, 12, 10]);
 /// ```
-pub type ReverseSortedVec<T> = SortedVec<std::cmp::Reverse<T>>;
-pub type ReverseSortedSet<T> = SortedSet<std::cmp::Reverse<T>>;
+pub type ReverseSortedVec<T> = SortedVec<T, Descending>;
+pub type ReverseSortedSet<T> = SortedSet<T, Descending>;
+
+/// The former `ReverseSortedVec`, an ascending container of elements wrapped
+/// in `std::cmp::Reverse`.
+#[deprecated(note = "use `ReverseSortedVec`, which stores plain `T`")]
+pub type ReverseWrappedSortedVec<T> = SortedVec<std::cmp::Reverse<T>>;
+/// The former `ReverseSortedSet`, an ascending set of elements wrapped in
+/// `std::cmp::Reverse`.
+#[deprecated(note = "use `ReverseSortedSet`, which stores plain `T`")]
+pub type ReverseWrappedSortedSet<T> = SortedSet<std::cmp::Reverse<T>>;
+
+impl<T: Ord> ReverseSortedVec<T> {
+    /// Convert into a container sorted from least to greatest by reversing the
+    /// vector in place, without re-sorting.
+    #[inline]
+    pub fn into_forward(mut self) -> SortedVec<T> {
+        self.vec.reverse();
+        SortedVec {
+            vec: self.vec,
+            order: PhantomData,
+        }
+    }
+}
+impl<T: Ord> ReverseSortedSet<T> {
+    /// Convert into a set sorted from least to greatest by reversing the vector
+    /// in place, without re-sorting.
+    #[inline]
+    pub fn into_forward(self) -> SortedSet<T> {
+        SortedSet {
+            set: self.set.into_forward(),
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -737,78 +2266,599 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_iter() {
+        let v: SortedVec<i32> = [5, -10, 99, -11, 2, 17, 10].into_iter().collect();
+        assert_eq!(*v, vec![-11, -10, 2, 5, 10, 17, 99]);
+        let mut w: SortedVec<i32> = v.clone().into_iter().rev().skip(2).collect();
+        assert_eq!(*w, vec![-11, -10, 2, 5, 10]);
+        w.extend(v.clone());
+        assert_eq!(*w, vec![-11, -11, -10, -10, 2, 2, 5, 5, 10, 10, 17, 99]);
+        w.extend([3, 4, 100].iter().copied());
+        assert_eq!(w.len(), 15);
+        assert!(w.windows(2).all(|w| w[0] <= w[1]));
+        let s: SortedSet<i32> = w.into_iter().collect();
+        assert_eq!(**s, vec![-11, -10, 2, 3, 4, 5, 10, 17, 99, 100]);
+        let mut t: SortedSet<i32> = s.clone().into_iter().collect();
+        assert_eq!(s, t);
+        t.extend(s.into_iter().map(|x| x + 1));
+        assert_eq!(
+            t.into_iter().collect::<Vec<i32>>(),
+            vec![-11, -10, -9, 2, 3, 4, 5, 6, 10, 11, 17, 18, 99, 100, 101]
+        );
+    }
+
+    #[test]
+    fn test_sorted_iter() {
+        fn is_sorted<I: SortedIterator>(_: &I) {}
+        fn is_unique<I: UniqueSortedIterator>(_: &I) {}
+        let v = SortedVec::from_unsorted(vec![3, 1, 2, 2, 5, 4]);
+        is_sorted(&v.clone().into_iter());
+        is_sorted(&(&v).into_iter().copied().filter(|x| x % 2 == 0));
+        let w = SortedVec::from_sorted_iter((&v).into_iter().skip(1).cloned());
+        assert_eq!(*w, vec![2, 2, 3, 4, 5]);
+        let s = SortedSet::from_unsorted(vec![3, 1, 2, 2]);
+        is_unique(&(&s).into_iter());
+        is_unique(&s.clone().into_iter().take(2));
+        let t = SortedSet::from_sorted_iter((&s).into_iter().step_by(2).cloned());
+        assert_eq!(**t, vec![1, 3]);
+        let b: std::collections::BTreeSet<i32> = [9, 7, 8].into_iter().collect();
+        let t = SortedSet::from_sorted_iter(b.into_iter());
+        assert_eq!(**t, vec![7, 8, 9]);
+    }
+
+    #[test]
+    fn test_merge_resolve() {
+        #[derive(Debug, Eq, PartialEq)]
+        struct Counter(char, u32);
+        impl PartialOrd for Counter {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Counter {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+        let sum = |a: Counter, b: Counter| Counter(a.0, a.1 + b.1);
+        let v = SortedVec::from_unsorted(vec![Counter('a', 1), Counter('c', 2)]);
+        let w = SortedVec::from_unsorted(vec![Counter('b', 3), Counter('c', 4)]);
+        assert_eq!(
+            v.merge_resolve(w, sum).into_vec(),
+            vec![Counter('a', 1), Counter('b', 3), Counter('c', 6)]
+        );
+        let v = SortedVec::from_unsorted(vec![1, 1, 2, 5]);
+        let w = SortedVec::from_unsorted(vec![1, 3, 5, 5]);
+        assert_eq!(*v.merge_resolve(w, |a, _| a), vec![1, 1, 2, 3, 5, 5]);
+        let mut s = SortedSet::from_unsorted(vec![1, 4, 7]);
+        s.merge_resolve_in_place(SortedSet::from_unsorted(vec![2, 4, 8]), |a, b| a * b);
+        // resolved 16 is out of order and collides with nothing
+        assert_eq!(**s, vec![1, 2, 7, 8, 16]);
+        let s = s.merge_resolve(SortedSet::from_unsorted(vec![8, 9]), |_, b| b - 1);
+        assert_eq!(**s, vec![1, 2, 7, 9, 16]);
+    }
+
+    #[test]
+    fn test_as_mut_vec() {
+        let mut v = SortedVec::from_unsorted(vec![1, 2, 3, 4]);
+        unsafe {
+            let vec = v.as_mut_vec();
+            vec.iter_mut().for_each(|x| *x *= 2);
+            vec.truncate(3);
+        }
+        assert_eq!(*v, vec![2, 4, 6]);
+        let mut s = SortedSet::from_unsorted(vec![3, 1]);
+        unsafe { s.as_mut_vec().push(5) };
+        assert_eq!(s.binary_search(&5), Ok(2));
+    }
+
+    #[test]
+    fn test_range() {
+        let v = SortedVec::from_unsorted(vec![5, 1, 3, 3, 7, 9]);
+        assert_eq!(v.range(3..7).copied().collect::<Vec<_>>(), vec![3, 3, 5]);
+        assert_eq!(v.range(3..=7).count(), 4);
+        assert_eq!(v.range(4..).copied().collect::<Vec<_>>(), vec![5, 7, 9]);
+        assert_eq!(v.range(..).count(), 6);
+        assert_eq!(v.range(..=1).copied().collect::<Vec<_>>(), vec![1]);
+        let (start, end) = (8, 2);
+        assert_eq!(v.range(start..end).next(), None);
+        use std::ops::Bound;
+        assert_eq!(
+            v.range((Bound::Excluded(3), Bound::Excluded(9)))
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![5, 7]
+        );
+        let s = SortedSet::from_unsorted(vec![5, 1, 3, 3, 7, 9]);
+        assert_eq!(s.range(2..8).copied().collect::<Vec<_>>(), vec![3, 5, 7]);
+    }
+
+    #[test]
+    fn test_equal_range() {
+        let v = SortedVec::from_unsorted(vec![5, 1, 3, 3, 3, 7]);
+        assert_eq!(v.equal_range(&3), [3, 3, 3]);
+        assert_eq!(v.equal_range_indices(&3), 1..4);
+        assert_eq!(v.equal_range(&7), [7]);
+        assert!(v.equal_range(&4).is_empty());
+        assert_eq!(v.equal_range_indices(&4), 4..4);
+        assert_eq!(v.equal_range_indices(&0), 0..0);
+        assert_eq!(v.equal_range_indices(&8), 6..6);
+        assert_eq!(v.count(&3), 3);
+        assert_eq!(
+            (v.first_index_of(&3), v.last_index_of(&3)),
+            (Some(1), Some(3))
+        );
+        assert_eq!((v.first_index_of(&4), v.last_index_of(&4)), (None, None));
+        assert_eq!((v.first_index_of(&0), v.last_index_of(&0)), (None, None));
+        assert_eq!(v.count(&4), 0);
+        assert_eq!(SortedSet::from_unsorted(vec![1, 1]).count(&1), 1);
+    }
+
+    #[test]
+    fn test_from_unsorted_presorted() {
+        assert_eq!(
+            *SortedVec::from_unsorted(vec![1, 2, 2, 3]),
+            vec![1, 2, 2, 3]
+        );
+        assert_eq!(
+            *SortedVec::from_unsorted(vec![3, 2, 2, 1]),
+            vec![1, 2, 2, 3]
+        );
+        assert_eq!(
+            *SortedVec::from_unsorted(vec![2, 3, 2, 1]),
+            vec![1, 2, 2, 3]
+        );
+        assert_eq!(*SortedVec::<i32>::from_unsorted(vec![]), Vec::<i32>::new());
+        assert_eq!(**SortedSet::from_unsorted(vec![3, 3, 1]), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_rank() {
+        let v = SortedVec::from_unsorted(vec![10, 20, 20, 30]);
+        assert_eq!(v.rank(&5), 0);
+        assert_eq!(v.rank(&20), 1);
+        assert_eq!(v.rank_inclusive(&20), 3);
+        assert_eq!(v.rank(&25), 3);
+        assert_eq!(v.rank_inclusive(&30), 4);
+        let s = SortedSet::from_unsorted(vec![10, 20, 30]);
+        assert_eq!((s.rank(&20), s.rank_inclusive(&20)), (1, 2));
+    }
+
+    #[test]
+    fn test_contains() {
+        let v = SortedVec::from_unsorted(vec![3, 1, 2, 2]);
+        assert!(v.contains(&2));
+        assert!(!v.contains(&4));
+        let s = SortedSet::from_unsorted(vec![3, 1, 2, 2]);
+        assert!(s.contains(&1));
+        assert!(!s.contains(&0));
+    }
+
+    #[test]
+    fn test_borrowed_lookup() {
+        let mut s = SortedSet::from_unsorted(vec!["b".to_string(), "a".to_string()]);
+        assert!(s.contains("a"));
+        assert_eq!(s.binary_search("b"), Ok(1));
+        use std::ops::Bound::{Excluded, Included};
+        assert_eq!(s.range::<str, _>((Included("a"), Excluded("b"))).count(), 1);
+        assert_eq!(s.remove_item("a"), Some("a".to_string()));
+        let v = SortedVec::from_unsorted(vec![vec![2u8], vec![1u8, 2], vec![1u8, 2]]);
+        assert_eq!(v.count(&[1u8, 2][..]), 2);
+        assert_eq!(v.ceiling(&[1u8, 3][..]), Some(&vec![2u8]));
+    }
+
+    #[test]
+    fn test_by_key() {
+        #[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
+        struct Event {
+            time: u32,
+            name: &'static str,
+        }
+        let mut s = SortedSet::from_unsorted(vec![
+            Event { time: 5, name: "b" },
+            Event { time: 1, name: "a" },
+        ]);
+        assert_eq!(s.binary_search_by_key(&5, |e| e.time), Ok(1));
+        assert_eq!(s.find_by_key(&1, |e| e.time).map(|e| e.name), Some("a"));
+        assert_eq!(s.find_by_key(&2, |e| e.time), None);
+        assert_eq!(s.remove_by_key(&5, |e| e.time).map(|e| e.name), Some("b"));
+        assert_eq!(s.remove_by_key(&5, |e| e.time), None);
+        assert_eq!(s.len(), 1);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let v = SortedVec::from_unsorted(vec![1, 3, 3, 5]);
+        assert_eq!((v.lower_bound(&3), v.upper_bound(&3)), (1, 3));
+        assert_eq!((v.lower_bound(&4), v.upper_bound(&4)), (3, 3));
+        assert_eq!(v.partition_point(|x| *x < 5), 3);
+        let r: ReverseSortedVec<i32> = vec![1, 3, 3, 5].into();
+        assert_eq!((r.lower_bound(&3), r.upper_bound(&3)), (1, 3));
+        assert_eq!((r.lower_bound(&4), r.upper_bound(&4)), (1, 1));
+        assert_eq!(r.partition_point(|x| *x > 1), 3);
+        assert_eq!(
+            (r.rank(&3), r.floor(&4), r.ceiling(&4)),
+            (1, Some(&5), Some(&3))
+        );
+        assert_eq!(r.range(4..).collect::<Vec<_>>(), vec![&3, &3, &1]);
+        assert_eq!(r.equal_range(&3), [3, 3]);
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut v = SortedVec::from_unsorted(vec![3, 1, 3, 2, 3]);
+        assert_eq!(
+            v.counts().collect::<Vec<_>>(),
+            vec![(&1, 1), (&2, 1), (&3, 3)]
+        );
+        assert_eq!(v.dedup_with_count(), vec![1, 1, 3]);
+        assert_eq!(*v, vec![1, 2, 3]);
+        assert_eq!(
+            SortedVec::<i32>::new().dedup_with_count(),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn test_retain_mut() {
+        let mut v = SortedVec::from_unsorted(vec![1, 2, 3, 4]);
+        v.retain_mut(|x| {
+            *x = -*x;
+            *x < -1
+        });
+        assert_eq!(*v, vec![-4, -3, -2]);
+        let mut s = SortedSet::from_unsorted(vec![10, 11, 20, 35]);
+        s.retain_mut(|x| {
+            *x /= 10;
+            *x < 3
+        });
+        assert_eq!(**s, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_dedup_by() {
+        let mut v = SortedVec::from_unsorted(vec!["b", "B", "a", "A", "c"]);
+        v.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        assert_eq!(*v, vec!["A", "B", "a", "b", "c"]);
+        let mut v = SortedVec::from_unsorted(vec![1, 2, 4, 5, 9]);
+        v.dedup_by(|a, b| *a - *b == 1);
+        assert_eq!(*v, vec![1, 4, 9]);
+        let mut s = SortedSet::from_unsorted(vec!["b".to_string(), "c".to_string()]);
+        s.mutate_vec_dedup_by(
+            |v| v.push("B".to_string()),
+            |a, b| a.to_lowercase() == b.to_lowercase(),
+        );
+        assert_eq!(**s, vec!["B".to_string(), "c".to_string()]);
+        s.dedup_by(|_, _| true);
+        assert_eq!(s.len(), 1);
+    }
+
+    #[test]
+    fn test_dedup_keep_last() {
+        #[derive(Debug, Eq)]
+        struct Keyed(u32, char);
+        impl PartialEq for Keyed {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Ord for Keyed {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+        impl PartialOrd for Keyed {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        let mut v = SortedVec::new();
+        for (key, payload) in [(0, 'a'), (1, 'b'), (1, 'c'), (1, 'd'), (2, 'e')] {
+            v.push(Keyed(key, payload));
+        }
+        v.dedup_keep_last();
+        let payloads: Vec<_> = v.iter().map(|k| (k.0, k.1)).collect();
+        assert_eq!(payloads, vec![(0, 'a'), (1, 'd'), (2, 'e')]);
+        let s = SortedSet::from_unsorted_with(vec![3, 1, 3, 2], DedupPolicy::KeepLast);
+        assert_eq!(**s, vec![1, 2, 3]);
+        let s = SortedSet::from_unsorted_with(vec![3, 1, 3], DedupPolicy::default());
+        assert_eq!(**s, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_coalesce_duplicates() {
+        #[derive(Debug, Eq)]
+        struct Level {
+            price: u32,
+            quantity: u32,
+        }
+        impl PartialEq for Level {
+            fn eq(&self, other: &Self) -> bool {
+                self.price == other.price
+            }
+        }
+        impl Ord for Level {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.price.cmp(&other.price)
+            }
+        }
+        impl PartialOrd for Level {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        let level = |price, quantity| Level { price, quantity };
+        let mut v =
+            SortedVec::from_unsorted(vec![level(2, 1), level(1, 4), level(2, 2), level(2, 3)]);
+        v.coalesce_duplicates(|a, b| level(a.price, a.quantity + b.quantity));
+        let levels: Vec<_> = v.iter().map(|l| (l.price, l.quantity)).collect();
+        assert_eq!(levels, vec![(1, 4), (2, 6)]);
+        let mut v = SortedVec::from_unsorted(vec![1, 1, 2]);
+        v.coalesce_duplicates(|a, b| a + b + 1);
+        assert_eq!(*v, vec![2, 3]);
+        let mut v = SortedVec::from_unsorted(vec![1, 1, 3]);
+        v.coalesce_duplicates(|a, b| a + b + 1);
+        assert_eq!(*v, vec![3, 3]);
+    }
+
+    #[test]
+    fn test_floor_ceiling_nearest() {
+        let v = SortedVec::from_unsorted(vec![40, 10, 20, 20]);
+        assert_eq!(v.floor(&5), None);
+        assert_eq!(v.floor(&20), Some(&20));
+        assert_eq!(v.floor(&39), Some(&20));
+        assert_eq!(v.ceiling(&21), Some(&40));
+        assert_eq!(v.ceiling(&41), None);
+        let distance = |a: &i32, b: &i32| a.abs_diff(*b);
+        assert_eq!(v.nearest(&0, distance), Some(&10));
+        assert_eq!(v.nearest(&16, distance), Some(&20));
+        assert_eq!(v.nearest(&100, distance), Some(&40));
+        assert_eq!(SortedVec::<i32>::new().nearest(&1, distance), None);
+    }
+
+    #[test]
+    fn test_sort_order() {
+        let mut v = SortedVec::from_unsorted_with_order(vec![2, 5, 1, 5], Descending);
+        assert_eq!(*v, vec![5, 5, 2, 1]);
+        assert_eq!(v.insert(3), 2);
+        assert_eq!(v.push(0), 5);
+        assert_eq!(v.find_or_push(0), FindOrInsert::Found(5));
+        assert_eq!(v.binary_search(&2), Ok(3));
+        assert_eq!(v.remove_item(&5), Some(5));
+        v.mutate_vec(|v| v.push(4));
+        assert_eq!(*v, vec![5, 4, 3, 2, 1, 0]);
+        let mut s = SortedSet::with_order(Descending);
+        for x in [1, 3, 2, 3] {
+            let _ = s.find_or_insert(x);
+        }
+        assert_eq!(**s, vec![3, 2, 1]);
+        assert_eq!(s.push(0), (3, None));
+        assert_eq!(s.replace(2), (1, Some(2)));
+        assert_eq!(
+            SortedSet::from_unsorted_with_order(vec![1, 2, 2, 3], Descending),
+            SortedSet::from_unsorted_with_order(vec![3, 2, 1], Descending)
+        );
+        assert_eq!(
+            format!("{:?}", SortedVec::from_unsorted(vec![1])),
+            "SortedVec { vec: [1] }"
+        );
+    }
+
+    #[test]
+    fn test_custom_order_iter() {
+        #[derive(Clone, Debug)]
+        struct Backwards;
+        impl SortOrder for Backwards {
+            const NAME: &'static str = "backwards";
+            const IS_DESCENDING: bool = true;
+            fn compare<T: Ord + ?Sized>(a: &T, b: &T) -> std::cmp::Ordering {
+                b.cmp(a)
+            }
+        }
+        let mut v: SortedVec<i32, Backwards> = vec![2, 5, 1].into_iter().collect();
+        v.extend(vec![4, 2]);
+        assert_eq!(*v, vec![5, 4, 2, 2, 1]);
+        assert_eq!(
+            (&v).into_iter().collect::<Vec<_>>(),
+            vec![&5, &4, &2, &2, &1]
+        );
+        assert_eq!(v.into_iter().collect::<Vec<_>>(), vec![5, 4, 2, 2, 1]);
+        let mut s = SortedSet::<_, Backwards>::from(vec![3, 1, 3]);
+        s.extend(vec![2, 1]);
+        assert_eq!(**s, vec![3, 2, 1]);
+        assert_eq!((&s).into_iter().collect::<Vec<_>>(), vec![&3, &2, &1]);
+        assert_eq!(s.into_iter().rev().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_push_max() {
+        let mut v = SortedVec::new();
+        assert_eq!(v.push_max(2), Ok(0));
+        assert_eq!(v.push_max(2), Ok(1));
+        assert_eq!(v.push_max(5), Ok(2));
+        assert_eq!(v.push_max(3), Err(3));
+        assert_eq!(v.push_min(2), Ok(0));
+        assert_eq!(v.push_min(3), Err(3));
+        assert_eq!(*v, vec![2, 2, 2, 5]);
+        let mut s = SortedSet::new();
+        assert_eq!(s.push_min(4), Ok(0));
+        assert_eq!(s.push_max(4), Err(4));
+        assert_eq!(s.push_min(4), Err(4));
+        assert_eq!(s.push_max(6), Ok(1));
+        assert_eq!(s.push_min(1), Ok(0));
+        assert_eq!(s[..], [1, 4, 6]);
+        let mut d = SortedVec::from_unsorted_with_order(vec![1, 3], Descending);
+        assert_eq!(d.push_max(0), Ok(2));
+        assert_eq!(d.push_max(2), Err(2));
+        assert_eq!(d.push_min(4), Ok(0));
+        assert_eq!(*d, vec![4, 3, 1, 0]);
+    }
+
+    #[test]
+    fn test_remove_items() {
+        let mut v = SortedVec::from_unsorted(vec![4, 1, 4, 4, 9, 7]);
+        assert_eq!(v.remove_items(&[0, 4, 4, 7, 10]), 3);
+        assert_eq!(*v, vec![1, 4, 9]);
+        assert_eq!(v.remove_items::<i32>(&[]), 0);
+        let mut s = SortedSet::from_unsorted(vec![Reverse(1), Reverse(3)]);
+        assert_eq!(s.remove_items(&[Reverse(3), Reverse(2)]), 1);
+        assert_eq!(s.len(), 1);
+        let mut d = ReverseSortedVec::from_unsorted_with_order(vec![1, 2, 3], Descending);
+        assert_eq!(d.remove_items(&[3, 1]), 2);
+        assert_eq!(*d, vec![2]);
+    }
+
+    #[test]
+    fn test_reverse_sorted_vec_plain() {
+        let mut v: ReverseSortedVec<i32> = vec![5, -10, 99, 2].into();
+        assert_eq!(v.insert(17), 1);
+        v.extend([0, 99]);
+        assert_eq!(
+            v.iter().copied().collect::<Vec<_>>(),
+            vec![99, 99, 17, 5, 2, 0, -10]
+        );
+        assert_eq!(v.pop(), Some(-10));
+        let s: ReverseSortedSet<i32> = v.into_iter().collect();
+        assert_eq!((&s).into_iter().next(), Some(&99));
+        assert_eq!(s.into_iter().collect::<Vec<_>>(), vec![99, 17, 5, 2, 0]);
+    }
+
+    #[test]
+    fn test_into_reverse() {
+        let v = SortedVec::from_unsorted(vec![3, 1, 2, 2]).into_reverse();
+        assert_eq!(*v, vec![3, 2, 2, 1]);
+        let mut v = v.into_forward();
+        assert_eq!(v.insert(0), 0);
+        assert_eq!(*v, vec![0, 1, 2, 2, 3]);
+        let s = SortedSet::from_unsorted(vec![1, 3, 2]).into_reverse();
+        assert_eq!(s.binary_search(&1), Ok(2));
+        assert_eq!(**s.into_forward(), vec![1, 2, 3]);
+    }
+
     #[test]
     fn test_reverse_sorted_vec() {
-        let mut v = ReverseSortedVec::new();
-        assert_eq!(v.insert(Reverse(5)), 0);
-        assert_eq!(v.insert(Reverse(3)), 1);
-        assert_eq!(v.insert(Reverse(4)), 1);
-        assert_eq!(v.find_or_insert(Reverse(6)), FindOrInsert::Inserted(0));
-        assert_eq!(v.insert(Reverse(4)), 2);
-        assert_eq!(v.find_or_insert(Reverse(4)), FindOrInsert::Found(2));
+        let mut v = ReverseSortedVec::default();
+        assert_eq!(v.insert(5), 0);
+        assert_eq!(v.insert(3), 1);
+        assert_eq!(v.insert(4), 1);
+        assert_eq!(v.find_or_insert(6), FindOrInsert::Inserted(0));
+        assert_eq!(v.insert(4), 2);
+        assert_eq!(v.find_or_insert(4), FindOrInsert::Found(2));
         assert_eq!(v.len(), 5);
         v.dedup();
         assert_eq!(v.len(), 4);
         assert_eq!(
-            *ReverseSortedVec::from_unsorted(Vec::from_iter(
-                [5, -10, 99, -11, 2, 17, 10].map(Reverse)
-            )),
-            Vec::from_iter([99, 17, 10, 5, 2, -10, -11].map(Reverse))
+            *ReverseSortedVec::from(Vec::from([5, -10, 99, -11, 2, 17, 10])),
+            Vec::from([99, 17, 10, 5, 2, -10, -11])
         );
         assert_eq!(
-            ReverseSortedVec::from_unsorted(Vec::from_iter(
-                [5, -10, 99, -11, 2, 17, 10].map(Reverse)
-            )),
-            Vec::from_iter([5, -10, 99, -11, 2, 17, 10].map(Reverse)).into()
+            ReverseSortedVec::from_unsorted_with_order(
+                Vec::from([5, -10, 99, -11, 2, 17, 10]),
+                Descending
+            ),
+            Vec::from([5, -10, 99, -11, 2, 17, 10]).into()
         );
-        let mut v = ReverseSortedVec::new();
-        v.extend([5, -10, 99, -11, 2, 17, 10].map(Reverse));
-        assert_eq!(v.as_slice(), [99, 17, 10, 5, 2, -10, -11].map(Reverse));
+        let mut v = ReverseSortedVec::default();
+        v.extend([5, -10, 99, -11, 2, 17, 10]);
+        assert_eq!(v.as_slice(), [99, 17, 10, 5, 2, -10, -11]);
         let _ = v.mutate_vec(|v| {
-            v[6] = Reverse(11);
-            v[3] = Reverse(1);
+            v[6] = 11;
+            v[3] = 1;
         });
         assert_eq!(
-            v.drain(..).collect::<Vec<Reverse<i32>>>(),
-            Vec::from_iter([99, 17, 11, 10, 2, 1, -10].map(Reverse))
+            v.drain(..).collect::<Vec<i32>>(),
+            Vec::from([99, 17, 11, 10, 2, 1, -10])
         );
     }
 
     #[test]
     fn test_reverse_sorted_set() {
-        let mut s = ReverseSortedSet::new();
-        assert_eq!(s.replace(Reverse(5)), (0, None));
-        assert_eq!(s.replace(Reverse(3)), (1, None));
-        assert_eq!(s.replace(Reverse(4)), (1, None));
-        assert_eq!(s.find_or_insert(Reverse(6)), FindOrInsert::Inserted(0));
-        assert_eq!(s.replace(Reverse(4)), (2, Some(Reverse(4))));
-        assert_eq!(s.find_or_insert(Reverse(4)), FindOrInsert::Found(2));
+        let mut s = ReverseSortedSet::default();
+        assert_eq!(s.replace(5), (0, None));
+        assert_eq!(s.replace(3), (1, None));
+        assert_eq!(s.replace(4), (1, None));
+        assert_eq!(s.find_or_insert(6), FindOrInsert::Inserted(0));
+        assert_eq!(s.replace(4), (2, Some(4)));
+        assert_eq!(s.find_or_insert(4), FindOrInsert::Found(2));
         assert_eq!(s.len(), 4);
-        assert_eq!(s.binary_search(&Reverse(3)), Ok(3));
+        assert_eq!(s.binary_search(&3), Ok(3));
         assert_eq!(
-            **ReverseSortedSet::from_unsorted(Vec::from_iter(
-                [5, -10, 99, -11, 2, 99, 17, 10, -10].map(Reverse)
-            )),
-            Vec::from_iter([99, 17, 10, 5, 2, -10, -11].map(Reverse))
+            **ReverseSortedSet::from(Vec::from([5, -10, 99, -11, 2, 99, 17, 10, -10])),
+            Vec::from([99, 17, 10, 5, 2, -10, -11])
         );
         assert_eq!(
-            ReverseSortedSet::from_unsorted(Vec::from_iter(
-                [5, -10, 99, -11, 2, 99, 17, 10, -10].map(Reverse)
-            )),
-            Vec::from_iter([5, -10, 99, -11, 2, 99, 17, 10, -10].map(Reverse)).into()
+            ReverseSortedSet::from_unsorted_with_order(
+                Vec::from([5, -10, 99, -11, 2, 99, 17, 10, -10]),
+                Descending
+            ),
+            Vec::from([5, -10, 99, -11, 2, 99, 17, 10, -10]).into()
         );
-        let mut s = ReverseSortedSet::new();
-        s.extend([5, -10, 2, 99, -11, -11, 2, 17, 10].map(Reverse));
-        assert_eq!(s.as_slice(), [99, 17, 10, 5, 2, -10, -11].map(Reverse));
+        let mut s = ReverseSortedSet::default();
+        s.extend([5, -10, 2, 99, -11, -11, 2, 17, 10]);
+        assert_eq!(s.as_slice(), [99, 17, 10, 5, 2, -10, -11]);
         let _ = s.mutate_vec(|s| {
-            s[6] = Reverse(17);
-            s[3] = Reverse(1);
+            s[6] = 17;
+            s[3] = 1;
         });
         assert_eq!(
-            s.drain(..).collect::<Vec<Reverse<i32>>>(),
-            Vec::from_iter([99, 17, 10, 2, 1, -10].map(Reverse))
+            s.drain(..).collect::<Vec<i32>>(),
+            Vec::from([99, 17, 10, 2, 1, -10])
         );
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_reverse_wrapped_sorted_vec() {
+        let mut v = ReverseWrappedSortedVec::new();
+        v.insert(Reverse(3));
+        v.insert(Reverse(5));
+        assert_eq!(*v, vec![Reverse(5), Reverse(3)]);
+        assert_eq!(v.lower_bound(&Reverse(4)), 1);
+    }
+    #[cfg(feature = "allocator_api")]
+    #[test]
+    fn test_allocator() {
+        use std::alloc::{AllocError, Layout};
+        use std::cell::Cell;
+        use std::ptr::NonNull;
+
+        /// Counts the allocations made through it
+        #[derive(Clone, Copy)]
+        struct Counting<'a>(&'a Cell<usize>);
+
+        unsafe impl Allocator for Counting<'_> {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.0.set(self.0.get() + 1);
+                Global.allocate(layout)
+            }
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                Global.deallocate(ptr, layout)
+            }
+        }
+
+        let count = Cell::new(0);
+        let mut v = SortedVec::with_capacity_in(8, Counting(&count));
+        assert_eq!(count.get(), 1);
+        for x in [5, 1, 3, 1] {
+            v.insert(x);
+        }
+        assert_eq!(v.push(7), 4);
+        assert_eq!(v.find_or_insert(3), FindOrInsert::Found(2));
+        assert_eq!(**v, [1, 1, 3, 5, 7]);
+        assert_eq!(v.remove_item(&1), Some(1));
+        assert_eq!(v.range(2..6).count(), 2);
+        let vec = v.into_vec();
+        assert_eq!(vec, [1, 3, 5, 7]);
+        assert_eq!(count.get(), 1);
+        let mut s = SortedSet::from_unsorted(vec);
+        assert_eq!(s.replace(3), (1, Some(3)));
+        assert_eq!(s.replace(4), (2, None));
+        assert_eq!(***s, [1, 3, 4, 5, 7]);
+        let r = SortedVec::from_unsorted_with_order(s.into_vec(), Descending);
+        assert_eq!(**r, [7, 5, 4, 3, 1]);
+        assert_eq!(count.get(), 1);
+    }
     #[cfg(feature = "serde-nontransparent")]
     #[test]
     fn test_deserialize() {
@@ -861,4 +2911,51 @@ mod tests {
         let s = "[99,-11,-10,2,5,10,17]";
         let _ = serde_json::from_str::<ReverseSortedVec<i32>>(s).unwrap();
     }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_bounded() {
+        let de = |s| serde_json::Deserializer::from_str(s);
+        let v = SortedVec::<i32>::deserialize_bounded(&mut de("[1,2,3]"), 3).unwrap();
+        assert_eq!(v.as_slice(), [1, 2, 3]);
+        assert!(SortedVec::<i32>::deserialize_bounded(&mut de("[1,2,3]"), 2).is_err());
+        assert!(SortedVec::<i32>::deserialize_bounded(&mut de("[2,1]"), 2).is_err());
+        assert!(SortedSet::<i32>::deserialize_bounded(&mut de("[1,1]"), 2).is_err());
+        assert!(SortedSet::<i32>::deserialize_bounded(&mut de("[]"), 0).is_ok());
+    }
+    #[cfg(all(feature = "serde", not(feature = "serde-nontransparent")))]
+    #[test]
+    fn test_deserialize_fail_fast() {
+        // the order check fails before the invalid third element is parsed
+        let err = serde_json::from_str::<SortedVec<i32>>(r#"[2,1,"x"]"#).unwrap_err();
+        assert!(err.to_string().starts_with("input sequence is not sorted"));
+        let err = serde_json::from_str::<SortedSet<i32>>(r#"[1,1,"x"]"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("input set contains duplicate values"));
+    }
+    #[cfg(feature = "serde-nontransparent")]
+    #[test]
+    fn test_deserialize_set() {
+        let s = r#"{"vec":[1,2,3]}"#;
+        let set = serde_json::from_str::<SortedSet<i32>>(s).unwrap();
+        assert_eq!(serde_json::to_string(&set).unwrap(), s);
+        assert!(serde_json::from_str::<SortedSet<i32>>(r#"{"vec":[1,1]}"#).is_err());
+        assert!(serde_json::from_str::<SortedSet<i32>>(r#"{"set":[1]}"#).is_err());
+    }
+    #[cfg(all(feature = "serde", not(feature = "serde-nontransparent")))]
+    #[test]
+    fn test_deserialize_set() {
+        #[derive(Deserialize)]
+        struct Foo {
+            #[serde(deserialize_with = "SortedSet::deserialize_dedup")]
+            s: SortedSet<i32>,
+        }
+        assert!(serde_json::from_str::<SortedSet<i32>>("[1,2,3]").is_ok());
+        assert!(serde_json::from_str::<SortedSet<i32>>("[1,2,2,3]").is_err());
+        assert!(serde_json::from_str::<SortedSet<i32>>("[1,3,2]").is_err());
+        assert!(serde_json::from_str::<ReverseSortedSet<i32>>("[3,3,1]").is_err());
+        let foo = serde_json::from_str::<Foo>(r#"{"s":[1,2,2,3]}"#).unwrap();
+        assert_eq!(foo.s.as_slice(), [1, 2, 3]);
+        assert!(serde_json::from_str::<Foo>(r#"{"s":[2,1,1]}"#).is_err());
+    }
 }
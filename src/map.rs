@@ -0,0 +1,924 @@
+//! Flat sorted map.
+//!
+//! `SortedVecMap<K, V>` stores `(K, V)` pairs in a single vector sorted by
+//! key, with unique keys. Lookups are binary searches and iteration is a
+//! linear scan of contiguous memory, which makes it a good fit for maps that
+//! are built once or rarely modified. Inserting or removing an arbitrary key
+//! is O(n).
+//!
+//! ```
+//! use sorted_vec2::map::SortedVecMap;
+//!
+//! let mut map = SortedVecMap::new();
+//! map.insert("b", 2);
+//! map.insert("a", 1);
+//! assert_eq!(map.insert("b", 3), Some(2));
+//! assert_eq!(map.get("b"), Some(&3));
+//! assert_eq!(map.as_slice(), [("a", 1), ("b", 3)]);
+//! ```
+//!
+//! `SortedVecMultiMap<K, V>` allows several values per key. Values with equal
+//! keys are kept in insertion order and are contiguous, so all values of a key
+//! are returned as a single slice.
+//!
+//! With the `serde` feature `SortedVecMap` is serialized as a map. On
+//! deserialization the keys must be sorted and unique, otherwise an error is
+//! returned. `SortedVecMap::serialize_seq` and `SortedVecMap::deserialize_seq`
+//! use a sequence of key-value pairs instead:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! pub struct Index {
+//!   #[serde(
+//!     serialize_with = "SortedVecMap::serialize_seq",
+//!     deserialize_with = "SortedVecMap::deserialize_seq"
+//!   )]
+//!   pub map: SortedVecMap<u64, String>,
+//! }
+//! ```
+
+use std::borrow::Borrow;
+
+use crate::{SortedIterator, UniqueSortedIterator};
+
+/// Map with unique keys, stored as a vector of pairs sorted by key
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SortedVecMap<K: Ord, V> {
+    vec: Vec<(K, V)>,
+}
+
+impl<K: Ord, V> SortedVecMap<K, V> {
+    #[inline]
+    pub fn new() -> Self {
+        SortedVecMap { vec: Vec::new() }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedVecMap {
+            vec: Vec::with_capacity(capacity),
+        }
+    }
+    /// Uses the stable `sort_by()` to sort by key. Of several pairs with equal
+    /// keys, the last one is kept, as if they were inserted in order.
+    pub fn from_unsorted(mut vec: Vec<(K, V)>) -> Self {
+        vec.sort_by(|a, b| a.0.cmp(&b.0));
+        vec.dedup_by(|next, kept| {
+            if next.0 == kept.0 {
+                std::mem::swap(next, kept);
+                true
+            } else {
+                false
+            }
+        });
+        SortedVecMap { vec }
+    }
+    /// Use a vector of pairs as the map without sorting it.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the provided vector is sorted by key and
+    /// that the keys are unique.
+    pub unsafe fn from_unsorted_unchecked(vec: Vec<(K, V)>) -> Self {
+        SortedVecMap { vec }
+    }
+    /// Binary search for `key`, returning the index of its pair with `Ok`, or
+    /// the index where it would be inserted with `Err`.
+    #[inline]
+    pub fn binary_search_keys<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.binary_search_by(|(k, _)| k.borrow().cmp(key))
+    }
+    /// Insert a pair, returning the previous value of `key` if it was present.
+    /// The key itself is not updated.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.binary_search_keys(&key) {
+            Ok(index) => Some(std::mem::replace(&mut self.vec[index].1, value)),
+            Err(index) => {
+                self.vec.insert(index, (key, value));
+                None
+            }
+        }
+    }
+    /// The entry of `key` for in-place manipulation, located with a single
+    /// binary search.
+    ///
+    /// ```
+    /// use sorted_vec2::map::SortedVecMap;
+    ///
+    /// let mut counts = SortedVecMap::new();
+    /// for word in ["b", "a", "b"] {
+    ///     counts.entry(word).and_modify(|n| *n += 1).or_insert(1);
+    /// }
+    /// assert_eq!(counts.as_slice(), [("a", 1), ("b", 2)]);
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match self.binary_search_keys(&key) {
+            Ok(index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            Err(index) => Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                index,
+            }),
+        }
+    }
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+    #[inline]
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.binary_search_keys(key).ok()?;
+        let (k, v) = &self.vec[index];
+        Some((k, v))
+    }
+    #[inline]
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.binary_search_keys(key).ok()?;
+        Some(&mut self.vec[index].1)
+    }
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search_keys(key).is_ok()
+    }
+    #[inline]
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.binary_search_keys(key).ok()?;
+        Some(self.vec.remove(index))
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional)
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.vec.retain_mut(|(k, v)| f(k, v))
+    }
+    /// The pairs sorted by key
+    #[inline]
+    pub fn as_slice(&self) -> &[(K, V)] {
+        self.vec.as_slice()
+    }
+    #[inline]
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.vec
+    }
+    /// Iterator over the pairs in key order
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.vec.iter(),
+        }
+    }
+    /// Iterator over the pairs in key order, with mutable values
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            iter: self.vec.iter_mut(),
+        }
+    }
+    /// Iterator over the keys in order
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            iter: self.vec.iter(),
+        }
+    }
+    /// Iterator over the values in key order
+    #[inline]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            iter: self.vec.iter(),
+        }
+    }
+    /// Iterator over the values in key order
+    #[inline]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            iter: self.vec.iter_mut(),
+        }
+    }
+    /// Iterator over the pairs whose keys fall within `range`, located with
+    /// two binary searches. An empty iterator is returned if the start of the
+    /// range is after its end.
+    ///
+    /// ```
+    /// use sorted_vec2::map::SortedVecMap;
+    ///
+    /// let map: SortedVecMap<_, _> = (0..10).map(|k| (k, k * k)).collect();
+    /// let squares: Vec<_> = map.range(3..6).map(|(_, v)| *v).collect();
+    /// assert_eq!(squares, vec![9, 16, 25]);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: std::ops::RangeBounds<Q>,
+    {
+        use std::ops::Bound;
+        let start = match range.start_bound() {
+            Bound::Included(x) => self.vec.partition_point(|(k, _)| k.borrow() < x),
+            Bound::Excluded(x) => self.vec.partition_point(|(k, _)| k.borrow() <= x),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(x) => self.vec.partition_point(|(k, _)| k.borrow() <= x),
+            Bound::Excluded(x) => self.vec.partition_point(|(k, _)| k.borrow() < x),
+            Bound::Unbounded => self.vec.len(),
+        };
+        Iter {
+            iter: self.vec[start..end.max(start)].iter(),
+        }
+    }
+    /// Serialize as a sequence of key-value pairs instead of a map. See the
+    /// `map` module.
+    #[cfg(feature = "serde")]
+    pub fn serialize_seq<S>(map: &Self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+        K: serde::Serialize,
+        V: serde::Serialize,
+    {
+        serializer.collect_seq(map.vec.iter())
+    }
+    /// Deserialize from a sequence of key-value pairs with sorted unique keys.
+    /// See the `map` module.
+    #[cfg(feature = "serde")]
+    pub fn deserialize_seq<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        K: serde::Deserialize<'de>,
+        V: serde::Deserialize<'de>,
+    {
+        use serde::de::Error;
+        use serde::Deserialize;
+        let vec = Vec::deserialize(deserializer)?;
+        check_keys(&vec).map_err(D::Error::custom)?;
+        Ok(SortedVecMap { vec })
+    }
+    /// The pair with the least key
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.vec.first().map(|(k, v)| (k, v))
+    }
+    /// The pair with the greatest key
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.vec.last().map(|(k, v)| (k, v))
+    }
+}
+
+/// Checks that the keys are sorted and unique
+#[cfg(feature = "serde")]
+fn check_keys<K: Ord, V>(vec: &[(K, V)]) -> Result<(), &'static str> {
+    for pair in vec.windows(2) {
+        match pair[0].0.cmp(&pair[1].0) {
+            std::cmp::Ordering::Less => {}
+            std::cmp::Ordering::Equal => return Err("duplicate map key"),
+            std::cmp::Ordering::Greater => return Err("map keys are not sorted"),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+impl<K, V> serde::Serialize for SortedVecMap<K, V>
+where
+    K: Ord + serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for SortedVecMap<K, V>
+where
+    K: Ord + serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{Error, MapAccess, Visitor};
+        struct MapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+        impl<'de, K, V> Visitor<'de> for MapVisitor<K, V>
+        where
+            K: Ord + serde::Deserialize<'de>,
+            V: serde::Deserialize<'de>,
+        {
+            type Value = SortedVecMap<K, V>;
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a map with sorted unique keys")
+            }
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                // the size hint comes from the input, don't trust it blindly
+                let mut vec = Vec::with_capacity(access.size_hint().unwrap_or(0).min(4096));
+                while let Some(pair) = access.next_entry()? {
+                    vec.push(pair);
+                }
+                check_keys(&vec).map_err(A::Error::custom)?;
+                Ok(SortedVecMap { vec })
+            }
+        }
+        deserializer.deserialize_map(MapVisitor(std::marker::PhantomData))
+    }
+}
+
+//
+//  map iterators
+//
+
+macro_rules! impl_map_iter {
+    ($name:ident, $item:ty, $f:expr) => {
+        impl<'a, K, V> Iterator for $name<'a, K, V> {
+            type Item = $item;
+            #[inline]
+            fn next(&mut self) -> Option<$item> {
+                self.iter.next().map($f)
+            }
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.iter.size_hint()
+            }
+        }
+        impl<'a, K, V> DoubleEndedIterator for $name<'a, K, V> {
+            #[inline]
+            fn next_back(&mut self) -> Option<$item> {
+                self.iter.next_back().map($f)
+            }
+        }
+        impl<K, V> ExactSizeIterator for $name<'_, K, V> {}
+        impl<K, V> std::iter::FusedIterator for $name<'_, K, V> {}
+    };
+}
+
+/// Iterator over the pairs of a `SortedVecMap` in key order
+#[derive(Clone, Debug)]
+pub struct Iter<'a, K, V> {
+    iter: std::slice::Iter<'a, (K, V)>,
+}
+impl_map_iter!(Iter, (&'a K, &'a V), |(k, v)| (k, v));
+// keys are unique, so pairs compare in key order
+unsafe impl<K: Ord, V: Ord> SortedIterator for Iter<'_, K, V> {}
+unsafe impl<K: Ord, V: Ord> UniqueSortedIterator for Iter<'_, K, V> {}
+
+/// Iterator over the pairs of a `SortedVecMap` in key order, with mutable
+/// values
+#[derive(Debug)]
+pub struct IterMut<'a, K, V> {
+    iter: std::slice::IterMut<'a, (K, V)>,
+}
+impl_map_iter!(IterMut, (&'a K, &'a mut V), |(k, v)| (&*k, v));
+
+/// Iterator over the keys of a `SortedVecMap` in order
+#[derive(Clone, Debug)]
+pub struct Keys<'a, K, V> {
+    iter: std::slice::Iter<'a, (K, V)>,
+}
+impl_map_iter!(Keys, &'a K, |(k, _)| k);
+unsafe impl<K: Ord, V> SortedIterator for Keys<'_, K, V> {}
+unsafe impl<K: Ord, V> UniqueSortedIterator for Keys<'_, K, V> {}
+
+/// Iterator over the values of a `SortedVecMap` in key order
+#[derive(Clone, Debug)]
+pub struct Values<'a, K, V> {
+    iter: std::slice::Iter<'a, (K, V)>,
+}
+impl_map_iter!(Values, &'a V, |(_, v)| v);
+
+/// Iterator over the values of a `SortedVecMap` in key order
+#[derive(Debug)]
+pub struct ValuesMut<'a, K, V> {
+    iter: std::slice::IterMut<'a, (K, V)>,
+}
+impl_map_iter!(ValuesMut, &'a mut V, |(_, v)| v);
+
+/// A view into a single entry of a `SortedVecMap`, see `SortedVecMap::entry`
+#[derive(Debug)]
+pub enum Entry<'a, K: Ord, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+/// An entry whose key is present in the map
+#[derive(Debug)]
+pub struct OccupiedEntry<'a, K: Ord, V> {
+    map: &'a mut SortedVecMap<K, V>,
+    index: usize,
+}
+
+/// An entry whose key is absent from the map
+#[derive(Debug)]
+pub struct VacantEntry<'a, K: Ord, V> {
+    map: &'a mut SortedVecMap<K, V>,
+    key: K,
+    index: usize,
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+    /// Insert `default` if the entry is vacant, and return the value.
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+    /// Insert the result of `default` if the entry is vacant, and return the
+    /// value.
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        self.or_insert_with_key(|_| default())
+    }
+    /// Insert the result of `default` applied to the key if the entry is
+    /// vacant, and return the value.
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(&entry.key);
+                entry.insert(value)
+            }
+        }
+    }
+    /// Modify the value in place if the entry is occupied.
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K: Ord, V: Default> Entry<'a, K, V> {
+    #[inline]
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.map.vec[self.index].0
+    }
+    #[inline]
+    pub fn get(&self) -> &V {
+        &self.map.vec[self.index].1
+    }
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.vec[self.index].1
+    }
+    /// The value with the lifetime of the map borrow
+    #[inline]
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.vec[self.index].1
+    }
+    /// Replace the value, returning the old one.
+    #[inline]
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+    #[inline]
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+    #[inline]
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.vec.remove(self.index)
+    }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+    #[inline]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+    /// Insert the value at the position found by `entry`, and return it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.vec.insert(self.index, (self.key, value));
+        &mut self.map.vec[self.index].1
+    }
+}
+
+/// Map with multiple values per key, stored as a vector of pairs sorted by key
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SortedVecMultiMap<K: Ord, V> {
+    vec: Vec<(K, V)>,
+}
+
+impl<K: Ord, V> SortedVecMultiMap<K, V> {
+    #[inline]
+    pub fn new() -> Self {
+        SortedVecMultiMap { vec: Vec::new() }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedVecMultiMap {
+            vec: Vec::with_capacity(capacity),
+        }
+    }
+    /// Uses the stable `sort_by()` to sort by key, so the values of each key
+    /// keep their relative order.
+    pub fn from_unsorted(mut vec: Vec<(K, V)>) -> Self {
+        vec.sort_by(|a, b| a.0.cmp(&b.0));
+        SortedVecMultiMap { vec }
+    }
+    /// Use a vector of pairs as the map without sorting it.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the provided vector is sorted by key.
+    pub unsafe fn from_unsorted_unchecked(vec: Vec<(K, V)>) -> Self {
+        SortedVecMultiMap { vec }
+    }
+    /// Insert a pair after all pairs with an equal key, returning its index.
+    pub fn insert(&mut self, key: K, value: V) -> usize {
+        let index = self.vec.partition_point(|(k, _)| *k <= key);
+        self.vec.insert(index, (key, value));
+        index
+    }
+    /// Index range of the pairs with key `key`.
+    pub fn key_indices<Q>(&self, key: &Q) -> std::ops::Range<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let start = self.vec.partition_point(|(k, _)| k.borrow() < key);
+        let end = start + self.vec[start..].partition_point(|(k, _)| k.borrow() <= key);
+        start..end
+    }
+    /// All pairs with key `key`, in insertion order.
+    #[inline]
+    pub fn get_all<Q>(&self, key: &Q) -> &[(K, V)]
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        &self.vec[self.key_indices(key)]
+    }
+    /// All pairs with key `key`, in insertion order.
+    #[inline]
+    pub fn get_all_mut<Q>(&mut self, key: &Q) -> &mut [(K, V)]
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let range = self.key_indices(key);
+        &mut self.vec[range]
+    }
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec
+            .binary_search_by(|(k, _)| k.borrow().cmp(key))
+            .is_ok()
+    }
+    /// Number of values of `key`
+    #[inline]
+    pub fn count<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.key_indices(key).len()
+    }
+    /// Remove all pairs with key `key`, returning them in insertion order.
+    pub fn remove_all<Q>(&mut self, key: &Q) -> Vec<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let range = self.key_indices(key);
+        self.vec.drain(range).collect()
+    }
+    /// Iterator over each distinct key together with the slice of its pairs.
+    ///
+    /// ```
+    /// use sorted_vec2::map::SortedVecMultiMap;
+    ///
+    /// let map = SortedVecMultiMap::from_unsorted(vec![(2, 'c'), (1, 'a'), (2, 'b')]);
+    /// let groups: Vec<_> = map.groups().map(|(k, pairs)| (*k, pairs.len())).collect();
+    /// assert_eq!(groups, vec![(1, 1), (2, 2)]);
+    /// ```
+    #[inline]
+    pub fn groups(&self) -> Groups<'_, K, V> {
+        Groups { rest: &self.vec }
+    }
+    /// Number of pairs
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional)
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.vec.retain_mut(|(k, v)| f(k, v))
+    }
+    /// The pairs sorted by key
+    #[inline]
+    pub fn as_slice(&self) -> &[(K, V)] {
+        self.vec.as_slice()
+    }
+    #[inline]
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.vec
+    }
+}
+
+/// Iterator over the groups of pairs with equal keys, see
+/// `SortedVecMultiMap::groups`
+#[derive(Clone, Debug)]
+pub struct Groups<'a, K, V> {
+    rest: &'a [(K, V)],
+}
+
+impl<'a, K: Ord, V> Iterator for Groups<'a, K, V> {
+    type Item = (&'a K, &'a [(K, V)]);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, _) = self.rest.first()?;
+        let end = self.rest.partition_point(|(k, _)| k <= key);
+        let (group, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some((key, group))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.rest.len().min(1), Some(self.rest.len()))
+    }
+}
+impl<K: Ord, V> std::iter::FusedIterator for Groups<'_, K, V> {}
+
+impl<K: Ord, V> Default for SortedVecMultiMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<K: Ord, V> From<Vec<(K, V)>> for SortedVecMultiMap<K, V> {
+    fn from(unsorted: Vec<(K, V)>) -> Self {
+        Self::from_unsorted(unsorted)
+    }
+}
+impl<K: Ord, V> FromIterator<(K, V)> for SortedVecMultiMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::from_unsorted(iter.into_iter().collect())
+    }
+}
+impl<K: Ord, V> Extend<(K, V)> for SortedVecMultiMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            let _ = self.insert(k, v);
+        }
+    }
+}
+impl<K: Ord, V> IntoIterator for SortedVecMultiMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+
+impl<K: Ord, V> Default for SortedVecMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<K: Ord, V> From<Vec<(K, V)>> for SortedVecMap<K, V> {
+    fn from(unsorted: Vec<(K, V)>) -> Self {
+        Self::from_unsorted(unsorted)
+    }
+}
+impl<K: Ord, V> FromIterator<(K, V)> for SortedVecMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::from_unsorted(iter.into_iter().collect())
+    }
+}
+impl<K: Ord, V> Extend<(K, V)> for SortedVecMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            let _ = self.insert(k, v);
+        }
+    }
+}
+impl<'a, K: Ord, V> IntoIterator for &'a SortedVecMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+impl<'a, K: Ord, V> IntoIterator for &'a mut SortedVecMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+impl<K: Ord, V> IntoIterator for SortedVecMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.into_iter()
+    }
+}
+impl<K, V, Q> std::ops::Index<&Q> for SortedVecMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+    /// Panics if the key is not present
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sorted_vec_map() {
+        let mut map = SortedVecMap::from_unsorted(vec![(3, "c"), (1, "a"), (3, "d"), (2, "b")]);
+        assert_eq!(map.as_slice(), [(1, "a"), (2, "b"), (3, "d")]);
+        assert_eq!(map.insert(0, "z"), None);
+        assert_eq!(map.insert(2, "B"), Some("b"));
+        assert_eq!(map.binary_search_keys(&2), Ok(2));
+        assert_eq!(map.binary_search_keys(&5), Err(4));
+        *map.get_mut(&1).unwrap() = "A";
+        assert_eq!(map[&1], "A");
+        assert_eq!(map.get_key_value(&3), Some((&3, &"d")));
+        assert_eq!(map.remove(&0), Some("z"));
+        assert_eq!(map.remove(&0), None);
+        assert!(!map.contains_key(&0));
+        map.extend(vec![(4, "e"), (1, "a")]);
+        map.retain(|k, _| *k != 2);
+        assert_eq!(map.into_vec(), vec![(1, "a"), (3, "d"), (4, "e")]);
+        let map: SortedVecMap<String, i32> = vec![("x".to_string(), 1)].into_iter().collect();
+        assert_eq!(map.get("x"), Some(&1));
+    }
+
+    #[test]
+    fn test_sorted_vec_multi_map() {
+        let mut map = SortedVecMultiMap::from_unsorted(vec![(2, "x"), (1, "a"), (2, "y")]);
+        assert_eq!(map.insert(2, "z"), 3);
+        assert_eq!(map.insert(0, "o"), 0);
+        assert_eq!(map.get_all(&2), [(2, "x"), (2, "y"), (2, "z")]);
+        assert_eq!(map.count(&1), 1);
+        assert!(map.get_all(&5).is_empty());
+        map.get_all_mut(&1)[0].1 = "A";
+        let groups: Vec<_> = map.groups().map(|(k, g)| (*k, g.len())).collect();
+        assert_eq!(groups, vec![(0, 1), (1, 1), (2, 3)]);
+        assert_eq!(map.remove_all(&2), vec![(2, "x"), (2, "y"), (2, "z")]);
+        assert!(!map.contains_key(&2));
+        assert_eq!(map.into_vec(), vec![(0, "o"), (1, "A")]);
+    }
+
+    #[test]
+    fn test_map_iterators() {
+        let mut map: SortedVecMap<i32, i32> = (0..6).rev().map(|k| (k, -k)).collect();
+        assert_eq!(
+            map.keys().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+        for v in map.values_mut() {
+            *v *= 2;
+        }
+        for (k, v) in &mut map {
+            *v += k;
+        }
+        assert_eq!(map.values().next_back(), Some(&-5));
+        assert_eq!(map.iter().len(), 6);
+        assert_eq!(
+            map.range(2..=3).collect::<Vec<_>>(),
+            vec![(&2, &-2), (&3, &-3)]
+        );
+        assert_eq!(map.range(4..).count(), 2);
+        assert_eq!(map.range(..0).count(), 0);
+        assert_eq!(map.first_key_value(), Some((&0, &0)));
+        assert_eq!(map.last_key_value(), Some((&5, &-5)));
+        let keys: crate::SortedSet<i32> = map.keys().copied().collect();
+        assert_eq!(keys.len(), 6);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let map = SortedVecMap::from_unsorted(vec![("b".to_string(), 2), ("a".to_string(), 1)]);
+        let s = serde_json::to_string(&map).unwrap();
+        assert_eq!(s, r#"{"a":1,"b":2}"#);
+        assert_eq!(
+            serde_json::from_str::<SortedVecMap<String, i32>>(&s).unwrap(),
+            map
+        );
+        assert!(serde_json::from_str::<SortedVecMap<String, i32>>(r#"{"b":2,"a":1}"#).is_err());
+        assert!(serde_json::from_str::<SortedVecMap<String, i32>>(r#"{"a":1,"a":2}"#).is_err());
+        let mut json = serde_json::Serializer::new(Vec::new());
+        SortedVecMap::serialize_seq(&map, &mut json).unwrap();
+        assert_eq!(json.into_inner(), br#"[["a",1],["b",2]]"#);
+        let mut json = serde_json::Deserializer::from_str("[[1,2],[3,4]]");
+        let map = SortedVecMap::<i32, i32>::deserialize_seq(&mut json).unwrap();
+        assert_eq!(map.get(&3), Some(&4));
+        let mut json = serde_json::Deserializer::from_str("[[3,4],[1,2]]");
+        assert!(SortedVecMap::<i32, i32>::deserialize_seq(&mut json).is_err());
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut map = SortedVecMap::new();
+        assert_eq!(*map.entry(2).or_insert(20), 20);
+        assert_eq!(*map.entry(2).or_insert(0), 20);
+        *map.entry(1).or_default() += 10;
+        map.entry(1).and_modify(|v| *v += 1).or_insert(0);
+        assert_eq!(*map.entry(3).or_insert_with_key(|k| k * 10), 30);
+        match map.entry(2) {
+            Entry::Occupied(mut entry) => {
+                assert_eq!((entry.key(), entry.get()), (&2, &20));
+                assert_eq!(entry.insert(21), 20);
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match map.entry(3) {
+            Entry::Occupied(entry) => assert_eq!(entry.remove_entry(), (3, 30)),
+            Entry::Vacant(_) => unreachable!(),
+        }
+        match map.entry(0) {
+            Entry::Vacant(entry) => assert_eq!(entry.into_key(), 0),
+            Entry::Occupied(_) => unreachable!(),
+        }
+        assert_eq!(map.as_slice(), [(1, 11), (2, 21)]);
+    }
+}
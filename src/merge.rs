@@ -0,0 +1,495 @@
+//! In-place merging of a sorted batch into a container.
+//!
+//! The existing elements and the batch are merged from the back into the
+//! reserved space at the end of the vector, so each element is moved at most
+//! once and no temporary buffer is allocated.
+
+use std::cmp::Ordering;
+use std::ptr;
+
+use crate::order::SortOrder;
+use crate::{SortedIterator, SortedSet, SortedVec};
+
+/// Merge state that restores a valid vector if a comparison panics.
+///
+/// The vector holds `old` unmerged elements at the front and the merged
+/// elements in `dst..total`, while the batch holds `new` unmerged elements at
+/// the front. Dropped duplicates leave a gap of `dst - old - new` slots.
+struct MergeHole<'a, T> {
+    vec: &'a mut Vec<T>,
+    batch: *const T,
+    old: usize,
+    new: usize,
+    dst: usize,
+    total: usize,
+}
+
+impl<T> Drop for MergeHole<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            let base = self.vec.as_mut_ptr();
+            ptr::copy_nonoverlapping(self.batch, base.add(self.old), self.new);
+            let len = self.old + self.new;
+            ptr::copy(base.add(self.dst), base.add(len), self.total - self.dst);
+            self.vec.set_len(len + self.total - self.dst);
+        }
+    }
+}
+
+impl<T: Ord, O: SortOrder> SortedVec<T, O> {
+    /// Move all elements of `other` into `self`, leaving `other` empty. The
+    /// two sorted vectors are merged in a single O(n + m) pass with at most
+    /// one reallocation. Equal elements from `other` are placed after those
+    /// of `self`.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let mut a = SortedVec::from_unsorted(vec![1, 5, 9]);
+    /// let mut b = SortedVec::from_unsorted(vec![2, 5, 10]);
+    /// a.append(&mut b);
+    /// assert_eq!(*a, vec![1, 2, 5, 5, 9, 10]);
+    /// assert!(b.is_empty());
+    /// ```
+    #[inline]
+    pub fn append(&mut self, other: &mut Self) {
+        self.merge_sorted_batch(std::mem::take(&mut other.vec), false)
+    }
+    /// Same as `append`, taking `other` by value.
+    #[inline]
+    pub fn merge_with(&mut self, other: Self) {
+        self.merge_sorted_batch(other.vec, false)
+    }
+    /// Merge two sorted vectors into one in O(n + m). The result reuses the
+    /// allocation with the larger capacity, so no allocation is made when it
+    /// can hold all of the elements. Equal elements from `other` are placed
+    /// after those of `self`.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let shards = vec![
+    ///     SortedVec::from_unsorted(vec![3, 1]),
+    ///     SortedVec::from_unsorted(vec![2, 5]),
+    ///     SortedVec::from_unsorted(vec![4]),
+    /// ];
+    /// let all = shards.into_iter().fold(SortedVec::new(), SortedVec::merge);
+    /// assert_eq!(*all, vec![1, 2, 3, 4, 5]);
+    /// ```
+    pub fn merge(mut self, mut other: Self) -> Self {
+        if other.vec.capacity() > self.vec.capacity() {
+            other.merge_in_place(self.vec, false, true);
+            other
+        } else {
+            self.merge_in_place(other.vec, false, false);
+            self
+        }
+    }
+    /// Insert all of `items` in O(n + m log(n + m)), returning the final
+    /// index of each item in input order. The batch is sorted and merged with
+    /// a single pass instead of shifting the tail of the vector once per item.
+    /// Items are placed after equal elements already in the vector, and equal
+    /// items keep their input order.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let mut v = SortedVec::from_unsorted(vec![10, 20, 30]);
+    /// assert_eq!(v.insert_many([25, 5, 20]), vec![4, 0, 3]);
+    /// assert_eq!(*v, vec![5, 10, 20, 20, 25, 30]);
+    /// ```
+    pub fn insert_many<I>(&mut self, items: I) -> Vec<usize>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut tagged: Vec<(T, usize)> = items.into_iter().zip(0..).collect();
+        tagged.sort_by(|a, b| O::compare(&a.0, &b.0));
+        let mut indices = vec![0; tagged.len()];
+        let mut before = 0;
+        for (k, (x, i)) in tagged.iter().enumerate() {
+            before += self.vec[before..].partition_point(|y| O::compare(y, x).is_le());
+            indices[*i] = k + before;
+        }
+        self.merge_sorted_batch(tagged.into_iter().map(|(x, _)| x).collect(), false);
+        indices
+    }
+    /// Merge elements that are expected to be sorted already, such as the
+    /// results of an ordered database scan, without sorting them first. The
+    /// order is verified in O(m) and the elements are only sorted if the
+    /// check fails.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let mut v = SortedVec::from_unsorted(vec![2, 8]);
+    /// v.extend_from_sorted(vec![1, 5, 9]);
+    /// v.extend_from_sorted(vec![7, 3]);
+    /// assert_eq!(*v, vec![1, 2, 3, 5, 7, 8, 9]);
+    /// ```
+    pub fn extend_from_sorted<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let batch: Vec<T> = iter.into_iter().collect();
+        if batch.windows(2).all(|w| O::compare(&w[0], &w[1]).is_le()) {
+            self.merge_sorted_batch(batch, false)
+        } else {
+            self.merge_unsorted_batch(batch)
+        }
+    }
+    /// Same as `extend_from_sorted` without verifying the order of the
+    /// elements, except in debug builds.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the elements are yielded in order `O`,
+    /// the same contract as `from_unsorted_unchecked`.
+    #[inline]
+    pub unsafe fn extend_from_sorted_unchecked<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.merge_sorted_batch(iter.into_iter().collect(), false)
+    }
+    /// Merge `batch`, which must already be sorted in order `O`, in
+    /// O(n + m). Equal elements from the batch are placed after the existing
+    /// ones, or dropped if `dedup` is set.
+    #[inline]
+    pub(crate) fn merge_sorted_batch(&mut self, batch: Vec<T>, dedup: bool) {
+        self.merge_in_place(batch, dedup, false)
+    }
+    /// With `batch_first`, equal elements from the batch are placed before
+    /// the existing ones, or kept instead of them if `dedup` is set.
+    fn merge_in_place(&mut self, mut batch: Vec<T>, dedup: bool, batch_first: bool) {
+        debug_assert!(batch.windows(2).all(|w| O::compare(&w[0], &w[1]).is_le()));
+        if batch.is_empty() {
+            return;
+        }
+        if self.vec.is_empty() {
+            self.vec = batch;
+            return;
+        }
+        self.vec.reserve(batch.len());
+        let old = self.vec.len();
+        let new = batch.len();
+        unsafe {
+            // the elements are owned by `hole` until it is dropped; the batch
+            // only frees its buffer
+            batch.set_len(0);
+            self.vec.set_len(0);
+        }
+        let mut hole = MergeHole {
+            batch: batch.as_ptr(),
+            vec: &mut self.vec,
+            old,
+            new,
+            dst: old + new,
+            total: old + new,
+        };
+        let base = hole.vec.as_mut_ptr();
+        while hole.new > 0 && hole.old > 0 {
+            unsafe {
+                let a = base.add(hole.old - 1);
+                let b = hole.batch.add(hole.new - 1);
+                match O::compare(&*a, &*b) {
+                    Ordering::Equal if dedup && batch_first => {
+                        hole.old -= 1;
+                        ptr::drop_in_place(a);
+                    }
+                    Ordering::Equal if dedup => {
+                        hole.new -= 1;
+                        ptr::drop_in_place(b as *mut T);
+                    }
+                    Ordering::Equal if batch_first => {
+                        hole.old -= 1;
+                        hole.dst -= 1;
+                        ptr::copy_nonoverlapping(a, base.add(hole.dst), 1);
+                    }
+                    Ordering::Greater => {
+                        hole.old -= 1;
+                        hole.dst -= 1;
+                        ptr::copy_nonoverlapping(a, base.add(hole.dst), 1);
+                    }
+                    _ => {
+                        hole.new -= 1;
+                        hole.dst -= 1;
+                        ptr::copy_nonoverlapping(b, base.add(hole.dst), 1);
+                    }
+                }
+            }
+        }
+        // the remaining batch elements precede every merged element and are
+        // moved into place when `hole` is dropped
+    }
+    /// Sort `batch` and merge it. The stable sort keeps equal elements of
+    /// the batch in their input order.
+    pub(crate) fn merge_unsorted_batch(&mut self, mut batch: Vec<T>) {
+        batch.sort_by(O::compare);
+        self.merge_sorted_batch(batch, false)
+    }
+}
+
+impl<T: Ord, O: SortOrder> SortedSet<T, O> {
+    /// Move all elements of `other` into `self`, leaving `other` empty. The
+    /// two sets are merged in a single O(n + m) pass with at most one
+    /// reallocation, and of two equal elements the one in `self` is kept.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedSet;
+    ///
+    /// let mut a = SortedSet::from_unsorted(vec![1, 5, 9]);
+    /// let mut b = SortedSet::from_unsorted(vec![2, 5, 10]);
+    /// a.append(&mut b);
+    /// assert_eq!(a[..], [1, 2, 5, 9, 10]);
+    /// assert!(b.is_empty());
+    /// ```
+    #[inline]
+    pub fn append(&mut self, other: &mut Self) {
+        self.set
+            .merge_sorted_batch(std::mem::take(&mut other.set.vec), true)
+    }
+    /// Same as `append`, taking `other` by value.
+    #[inline]
+    pub fn merge_with(&mut self, other: Self) {
+        self.set.merge_sorted_batch(other.set.vec, true)
+    }
+    /// Merge two sets into one in O(n + m), reusing the allocation with the
+    /// larger capacity. Of two equal elements the one in `self` is kept.
+    pub fn merge(mut self, mut other: Self) -> Self {
+        if other.set.vec.capacity() > self.set.vec.capacity() {
+            other.set.merge_in_place(self.set.vec, true, true);
+            other
+        } else {
+            self.set.merge_in_place(other.set.vec, true, false);
+            self
+        }
+    }
+    /// See `SortedVec::extend_from_sorted`. Elements already in the set, or
+    /// repeated in `iter`, are dropped.
+    pub fn extend_from_sorted<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let batch: Vec<T> = iter.into_iter().collect();
+        if batch.windows(2).all(|w| O::compare(&w[0], &w[1]).is_le()) {
+            self.merge_sorted_batch(batch)
+        } else {
+            self.merge_unsorted_batch(batch)
+        }
+    }
+    /// See `SortedVec::extend_from_sorted_unchecked`. The elements may
+    /// contain duplicates.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the elements are yielded in order `O`.
+    #[inline]
+    pub unsafe fn extend_from_sorted_unchecked<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.merge_sorted_batch(iter.into_iter().collect())
+    }
+    /// Merge the sorted `batch`, keeping the existing element or the first
+    /// of several equal elements of the batch.
+    pub(crate) fn merge_sorted_batch(&mut self, mut batch: Vec<T>) {
+        batch.dedup();
+        self.set.merge_sorted_batch(batch, true)
+    }
+    pub(crate) fn merge_unsorted_batch(&mut self, mut batch: Vec<T>) {
+        batch.sort_by(O::compare);
+        self.merge_sorted_batch(batch)
+    }
+}
+
+impl<T: Ord> SortedVec<T> {
+    /// Merge the elements of an iterator known to be sorted, without
+    /// verifying their order.
+    #[inline]
+    pub fn extend_from_sorted_iter<I>(&mut self, iter: I)
+    where
+        I: SortedIterator<Item = T>,
+    {
+        self.merge_sorted_batch(iter.collect(), false)
+    }
+}
+
+impl<T: Ord> SortedSet<T> {
+    /// See `SortedVec::extend_from_sorted_iter`.
+    #[inline]
+    pub fn extend_from_sorted_iter<I>(&mut self, iter: I)
+    where
+        I: SortedIterator<Item = T>,
+    {
+        self.merge_sorted_batch(iter.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Descending;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_merge_batch() {
+        let mut v = SortedVec::from_unsorted(vec![1, 4, 4, 9]);
+        v.merge_unsorted_batch(vec![10, 4, 0, 5]);
+        assert_eq!(*v, vec![0, 1, 4, 4, 4, 5, 9, 10]);
+        let mut d = SortedVec::from_unsorted_with_order(vec![3, 1], Descending);
+        d.merge_unsorted_batch(vec![2, 4]);
+        assert_eq!(*d, vec![4, 3, 2, 1]);
+        let mut s = SortedSet::from_unsorted(vec![2, 4, 6]);
+        s.merge_unsorted_batch(vec![6, 5, 1, 5, 2, 7]);
+        assert_eq!(s[..], [1, 2, 4, 5, 6, 7]);
+        let mut e = SortedSet::new();
+        e.merge_unsorted_batch(vec![3, 3]);
+        assert_eq!(e[..], [3]);
+    }
+
+    /// Ordered by the number only, so that equal elements can be told apart
+    #[derive(Clone, Debug)]
+    struct Tagged(u8, char);
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+    impl Eq for Tagged {}
+    impl PartialOrd for Tagged {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for Tagged {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+    fn tags<'a>(elements: impl IntoIterator<Item = &'a Tagged>) -> String {
+        elements.into_iter().map(|t| t.1).collect()
+    }
+
+    #[test]
+    fn test_append() {
+        let mut a = SortedVec::from_unsorted_with_order(vec![1, 7], Descending);
+        let mut b = SortedVec::from_unsorted_with_order(vec![8, 7, 0], Descending);
+        a.append(&mut b);
+        assert_eq!(*a, vec![8, 7, 7, 1, 0]);
+        assert!(b.is_empty());
+        b.append(&mut a);
+        assert_eq!((a.len(), b.len()), (0, 5));
+        a.merge_with(SortedVec::with_order(Descending));
+        assert!(a.is_empty());
+        let mut s = SortedSet::from_unsorted(vec![Tagged(1, 'a'), Tagged(3, 'a')]);
+        let mut t = SortedSet::from_unsorted(vec![Tagged(1, 'b'), Tagged(2, 'b')]);
+        s.append(&mut t);
+        assert!(t.is_empty());
+        s.merge_with(SortedSet::from_unsorted(vec![
+            Tagged(0, 'c'),
+            Tagged(3, 'c'),
+        ]));
+        assert_eq!(tags(s.iter()), "caba");
+    }
+
+    #[test]
+    fn test_merge() {
+        // equal elements of `self` come first whichever allocation is reused
+        let a = SortedVec::from_unsorted(vec![Tagged(1, 'a'), Tagged(3, 'a')]);
+        let mut b = SortedVec::with_capacity(16);
+        b.insert(Tagged(1, 'b'));
+        b.insert(Tagged(2, 'b'));
+        assert_eq!(tags(b.clone().merge(a.clone()).iter()), "baba");
+        assert_eq!(tags(a.clone().merge(SortedVec::new()).iter()), "aa");
+        let merged = a.merge(b);
+        assert!(merged.capacity() >= 16);
+        assert_eq!(tags(merged.iter()), "abba");
+        let s = SortedSet::from_unsorted(vec![Tagged(1, 'a'), Tagged(3, 'a')]);
+        let mut t = SortedSet::with_capacity(16);
+        t.push(Tagged(1, 'b'));
+        t.push(Tagged(3, 'b'));
+        t.push(Tagged(4, 'b'));
+        assert_eq!(tags(t.clone().merge(s.clone()).iter()), "bbb");
+        assert_eq!(tags(s.merge(t).iter()), "aab");
+    }
+
+    #[test]
+    fn test_insert_many() {
+        let mut v =
+            SortedVec::from_unsorted_with_order(vec![Tagged(5, 'a'), Tagged(1, 'a')], Descending);
+        let indices = v.insert_many([
+            Tagged(3, 'b'),
+            Tagged(5, 'b'),
+            Tagged(9, 'b'),
+            Tagged(5, 'c'),
+        ]);
+        assert_eq!(indices, vec![4, 2, 0, 3]);
+        assert_eq!(tags(v.iter()), "babcba");
+        for (i, t) in indices.iter().zip("bbbc".chars()) {
+            assert_eq!(v[*i].1, t);
+        }
+        assert!(v.insert_many([]).is_empty());
+        let mut e = SortedVec::new();
+        assert_eq!(e.insert_many([2, 1, 2]), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_extend_from_sorted() {
+        let mut v = SortedVec::from_unsorted_with_order(vec![4, 2], Descending);
+        v.extend_from_sorted(vec![5, 3, 1]);
+        v.extend_from_sorted(vec![0, 6]);
+        assert_eq!(*v, vec![6, 5, 4, 3, 2, 1, 0]);
+        unsafe { v.extend_from_sorted_unchecked([9, 3]) };
+        assert_eq!(v.len(), 9);
+        let mut s = SortedSet::from_unsorted(vec![2, 4]);
+        s.extend_from_sorted([1, 2, 2, 3]);
+        s.extend_from_sorted([5, 0, 5]);
+        unsafe { s.extend_from_sorted_unchecked([6, 6]) };
+        assert_eq!(s[..], [0, 1, 2, 3, 4, 5, 6]);
+        let mut w = SortedVec::from_unsorted(vec![3]);
+        w.extend_from_sorted_iter((&s).into_iter().copied());
+        s.extend_from_sorted_iter(std::collections::BTreeSet::from([7, 3]).into_iter());
+        assert_eq!(*w, vec![0, 1, 2, 3, 3, 4, 5, 6]);
+        assert_eq!(s.len(), 8);
+    }
+
+    #[test]
+    fn test_merge_batch_drops() {
+        struct Key(u8, #[allow(dead_code)] Rc<()>);
+        impl PartialEq for Key {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+        impl Eq for Key {}
+        impl PartialOrd for Key {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Key {
+            fn cmp(&self, other: &Self) -> Ordering {
+                assert!(self.0 * other.0 != 2, "incomparable");
+                self.0.cmp(&other.0)
+            }
+        }
+        let rc = Rc::new(());
+        let key = |k| Key(k, Rc::clone(&rc));
+        let mut s = SortedSet::from_unsorted(vec![key(1), key(3), key(5)]);
+        s.merge_sorted_batch(vec![key(0), key(3), key(4), key(5)]);
+        assert_eq!(
+            s.iter().map(|k| k.0).collect::<Vec<_>>(),
+            vec![0, 1, 3, 4, 5]
+        );
+        assert_eq!(Rc::strong_count(&rc), 6);
+        // comparing 1 with 2 panics, but every element is left in the vector
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            s.set.merge_sorted_batch(vec![key(2), key(6)], false)
+        }));
+        assert!(result.is_err());
+        assert_eq!(s.len(), 7);
+        assert_eq!(Rc::strong_count(&rc), 8);
+        drop(s);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
@@ -0,0 +1,273 @@
+//! Run-length encoded sorted multiset.
+//!
+//! `SortedMultiSet<T>` stores each distinct element once together with its
+//! multiplicity, so inserting the same element many times only increments a
+//! count.
+//!
+//! ```
+//! use sorted_vec2::multiset::SortedMultiSet;
+//!
+//! let mut a = SortedMultiSet::from_unsorted(vec!['b', 'a', 'b']);
+//! a.insert('c');
+//! assert_eq!(a.count(&'b'), 2);
+//! assert_eq!(a.len(), 4);
+//! let b = SortedMultiSet::from_unsorted(vec!['b', 'c', 'c']);
+//! assert_eq!(a.intersection(&b).as_slice(), [('b', 1), ('c', 1)]);
+//! assert_eq!(a.union(&b).as_slice(), [('a', 1), ('b', 2), ('c', 2)]);
+//! ```
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+/// Sorted multiset storing `(element, count)` pairs with unique elements and
+/// non-zero counts
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SortedMultiSet<T: Ord> {
+    vec: Vec<(T, usize)>,
+}
+
+impl<T: Ord> SortedMultiSet<T> {
+    #[inline]
+    pub fn new() -> Self {
+        SortedMultiSet { vec: Vec::new() }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedMultiSet {
+            vec: Vec::with_capacity(capacity),
+        }
+    }
+    /// Uses `sort_unstable()` and collapses runs of equal elements into counts.
+    pub fn from_unsorted(mut vec: Vec<T>) -> Self {
+        vec.sort_unstable();
+        let mut set = SortedMultiSet::new();
+        for element in vec {
+            match set.vec.last_mut() {
+                Some((last, count)) if *last == element => *count += 1,
+                _ => set.vec.push((element, 1)),
+            }
+        }
+        set
+    }
+    #[inline]
+    fn search<Q>(&self, x: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.binary_search_by(|(y, _)| y.borrow().cmp(x))
+    }
+    /// Insert one occurrence of `element`, returning its new count.
+    #[inline]
+    pub fn insert(&mut self, element: T) -> usize {
+        self.insert_many(element, 1)
+    }
+    /// Insert `n` occurrences of `element`, returning its new count.
+    pub fn insert_many(&mut self, element: T, n: usize) -> usize {
+        match self.search(&element) {
+            Ok(index) => {
+                self.vec[index].1 += n;
+                self.vec[index].1
+            }
+            Err(index) => {
+                if n > 0 {
+                    self.vec.insert(index, (element, n));
+                }
+                n
+            }
+        }
+    }
+    /// Remove one occurrence of `x`, returning `false` if it was not present.
+    pub fn remove_one<Q>(&mut self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(x) {
+            Ok(index) => {
+                if self.vec[index].1 == 1 {
+                    self.vec.remove(index);
+                } else {
+                    self.vec[index].1 -= 1;
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
+    /// Remove all occurrences of `x`, returning how many there were.
+    pub fn remove_all<Q>(&mut self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(x) {
+            Ok(index) => self.vec.remove(index).1,
+            Err(_) => 0,
+        }
+    }
+    /// Number of occurrences of `x`. O(log n).
+    #[inline]
+    pub fn count<Q>(&self, x: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(x).map_or(0, |index| self.vec[index].1)
+    }
+    #[inline]
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(x).is_ok()
+    }
+    /// Total number of elements, counting multiplicity. O(n).
+    pub fn len(&self) -> usize {
+        self.vec.iter().map(|(_, count)| count).sum()
+    }
+    /// Number of distinct elements
+    #[inline]
+    pub fn distinct_len(&self) -> usize {
+        self.vec.len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    /// Distinct elements with their counts, in sorted order
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&T, usize)> + ExactSizeIterator {
+        self.vec.iter().map(|(element, count)| (element, *count))
+    }
+    /// The `(element, count)` pairs in sorted order
+    #[inline]
+    pub fn as_slice(&self) -> &[(T, usize)] {
+        self.vec.as_slice()
+    }
+    #[inline]
+    pub fn into_vec(self) -> Vec<(T, usize)> {
+        self.vec
+    }
+    /// Multiset union: each element with the greater of its two counts.
+    pub fn union(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.merge_counts(other, true, |a, b| a.max(b))
+    }
+    /// Multiset intersection: each element with the lesser of its two counts.
+    pub fn intersection(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.merge_counts(other, false, |a, b| a.min(b))
+    }
+    /// Multiset sum: each element with the sum of its two counts.
+    pub fn sum(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.merge_counts(other, true, |a, b| a + b)
+    }
+    /// Merge the count lists, combining the counts of common elements with
+    /// `f` and keeping the elements of only one side if `keep_unmatched`.
+    fn merge_counts<F>(&self, other: &Self, keep_unmatched: bool, mut f: F) -> Self
+    where
+        T: Clone,
+        F: FnMut(usize, usize) -> usize,
+    {
+        let mut vec = Vec::new();
+        let (mut a, mut b) = (self.vec.iter().peekable(), other.vec.iter().peekable());
+        loop {
+            let next = match (a.peek(), b.peek()) {
+                (Some((x, m)), Some((y, n))) => match x.cmp(y) {
+                    Ordering::Less => {
+                        a.next();
+                        keep_unmatched.then(|| (x.clone(), *m))
+                    }
+                    Ordering::Greater => {
+                        b.next();
+                        keep_unmatched.then(|| (y.clone(), *n))
+                    }
+                    Ordering::Equal => {
+                        let pair = (x.clone(), f(*m, *n));
+                        a.next();
+                        b.next();
+                        Some(pair)
+                    }
+                },
+                (Some((x, m)), None) => {
+                    a.next();
+                    keep_unmatched.then(|| (x.clone(), *m))
+                }
+                (None, Some((y, n))) => {
+                    b.next();
+                    keep_unmatched.then(|| (y.clone(), *n))
+                }
+                (None, None) => break,
+            };
+            vec.extend(next);
+        }
+        SortedMultiSet { vec }
+    }
+}
+
+impl<T: Ord> Default for SortedMultiSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Ord> From<Vec<T>> for SortedMultiSet<T> {
+    fn from(unsorted: Vec<T>) -> Self {
+        Self::from_unsorted(unsorted)
+    }
+}
+impl<T: Ord> From<crate::SortedVec<T>> for SortedMultiSet<T> {
+    fn from(vec: crate::SortedVec<T>) -> Self {
+        Self::from_unsorted(vec.into_vec())
+    }
+}
+impl<T: Ord> FromIterator<T> for SortedMultiSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_unsorted(iter.into_iter().collect())
+    }
+}
+impl<T: Ord> Extend<T> for SortedMultiSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for t in iter {
+            let _ = self.insert(t);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sorted_multi_set() {
+        let mut s: SortedMultiSet<i32> = [3, 1, 3, 3, 2].into_iter().collect();
+        assert_eq!(s.as_slice(), [(1, 1), (2, 1), (3, 3)]);
+        assert_eq!((s.len(), s.distinct_len()), (5, 3));
+        assert_eq!(s.insert(2), 2);
+        assert_eq!(s.insert_many(0, 0), 0);
+        assert!(!s.contains(&0));
+        assert!(s.remove_one(&1));
+        assert!(!s.remove_one(&1));
+        assert_eq!(s.remove_all(&3), 3);
+        assert_eq!(s.remove_all(&3), 0);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![(&2, 2)]);
+        let a = SortedMultiSet::from_unsorted(vec![1, 1, 2, 4]);
+        let b = SortedMultiSet::from_unsorted(vec![1, 2, 2, 3]);
+        assert_eq!(a.union(&b).as_slice(), [(1, 2), (2, 2), (3, 1), (4, 1)]);
+        assert_eq!(a.intersection(&b).as_slice(), [(1, 1), (2, 1)]);
+        assert_eq!(a.sum(&b).count(&2), 3);
+        assert!(a.intersection(&SortedMultiSet::new()).is_empty());
+    }
+}
@@ -0,0 +1,58 @@
+//! Sort order markers.
+//!
+//! `SortedVec<T, O>` and `SortedSet<T, O>` are sorted according to the
+//! zero-sized `O: SortOrder` marker, `Ascending` by default;
+//! `ReverseSortedVec<T>` is `SortedVec<T, Descending>`. Containers in another
+//! order are created with the `*_with_order` constructors. Construction,
+//! insertion, search, bounds, range queries and set operations all compare
+//! through `O::compare`, so "less" and "greater" in their documentation refer
+//! to the container order. Only the `SortedIterator` markers, which promise
+//! ascending items, are limited to the `Ascending` order.
+//!
+//! ```
+//! use sorted_vec2::{Descending, SortedVec};
+//!
+//! let mut v = SortedVec::from_unsorted_with_order(vec![1, 3, 2], Descending);
+//! v.insert(4);
+//! assert_eq!(*v, vec![4, 3, 2, 1]);
+//! assert_eq!(v.binary_search(&2), Ok(2));
+//! assert_eq!(v.range(3..=2).collect::<Vec<_>>(), vec![&3, &2]);
+//! ```
+
+use std::cmp::Ordering;
+
+/// The order in which a container keeps its elements
+pub trait SortOrder {
+    /// Name of the order in lowercase, e.g. "ascending"
+    const NAME: &'static str;
+    /// Whether greater elements are placed first
+    const IS_DESCENDING: bool;
+    /// Compare two elements, `Less` meaning that `a` is placed before `b`
+    fn compare<T: Ord + ?Sized>(a: &T, b: &T) -> Ordering;
+}
+
+/// Sorted from least to greatest
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Ascending;
+
+/// Sorted from greatest to least
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Descending;
+
+impl SortOrder for Ascending {
+    const NAME: &'static str = "ascending";
+    const IS_DESCENDING: bool = false;
+    #[inline]
+    fn compare<T: Ord + ?Sized>(a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl SortOrder for Descending {
+    const NAME: &'static str = "descending";
+    const IS_DESCENDING: bool = true;
+    #[inline]
+    fn compare<T: Ord + ?Sized>(a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
@@ -0,0 +1,104 @@
+//! `ordered-float` interop (feature `ordered-float`).
+//!
+//! `OrderedFloat<F>` and `NotNan<F>` implement `Ord`, so they can be stored in
+//! the total-order containers directly. This module provides aliases for those
+//! containers, constructors from plain floats rejecting NaN, and conversions
+//! to and from the `partial` containers that do not re-sort.
+//!
+//! ```
+//! use sorted_vec2::ordered_float::from_unsorted_f64;
+//! use sorted_vec2::partial;
+//!
+//! let v = from_unsorted_f64(vec![2.5, -1.0, 0.5]).unwrap();
+//! assert_eq!(v[0].into_inner(), -1.0);
+//! let v = partial::SortedVec::<f64>::from(v);
+//! assert_eq!(*v, vec![-1.0, 0.5, 2.5]);
+//! assert!(from_unsorted_f64(vec![1.0, f64::NAN]).is_err());
+//! ```
+
+use ::ordered_float::{FloatIsNan, NotNan, OrderedFloat};
+
+use crate::{partial, SortedSet, SortedVec};
+
+/// Vector of floats ordered with NaN greater than every other value
+pub type OrderedFloatVec<F> = SortedVec<OrderedFloat<F>>;
+/// Set of floats ordered with NaN greater than every other value
+pub type OrderedFloatSet<F> = SortedSet<OrderedFloat<F>>;
+/// Vector of floats that are never NaN
+pub type NotNanVec<F> = SortedVec<NotNan<F>>;
+/// Set of floats that are never NaN
+pub type NotNanSet<F> = SortedSet<NotNan<F>>;
+
+macro_rules! impl_ordered_float {
+    ($($float:ty => $from_unsorted:ident, $set_from_unsorted:ident),*) => {
+        $(
+            #[doc = concat!("Sort a vector of `", stringify!($float), "`, failing if it contains NaN.")]
+            pub fn $from_unsorted(vec: Vec<$float>) -> Result<NotNanVec<$float>, FloatIsNan> {
+                let vec = vec.into_iter().map(NotNan::new).collect::<Result<Vec<_>, _>>()?;
+                Ok(SortedVec::from_unsorted(vec))
+            }
+            #[doc = concat!("Sort and deduplicate a vector of `", stringify!($float), "`, failing if it contains NaN.")]
+            pub fn $set_from_unsorted(vec: Vec<$float>) -> Result<NotNanSet<$float>, FloatIsNan> {
+                let vec = vec.into_iter().map(NotNan::new).collect::<Result<Vec<_>, _>>()?;
+                Ok(SortedSet::from_unsorted(vec))
+            }
+            impl From<NotNanVec<$float>> for partial::SortedVec<$float> {
+                fn from(sorted: NotNanVec<$float>) -> Self {
+                    let vec = sorted.into_vec().into_iter().map(NotNan::into_inner).collect();
+                    unsafe { partial::SortedVec::from_unsorted_unchecked(vec) }
+                }
+            }
+            impl From<NotNanVec<$float>> for OrderedFloatVec<$float> {
+                fn from(sorted: NotNanVec<$float>) -> Self {
+                    let vec = sorted
+                        .into_vec()
+                        .into_iter()
+                        .map(|x| OrderedFloat(x.into_inner()))
+                        .collect();
+                    unsafe { SortedVec::from_unsorted_unchecked(vec) }
+                }
+            }
+            impl TryFrom<partial::SortedVec<$float>> for NotNanVec<$float> {
+                type Error = FloatIsNan;
+                /// Fails if the vector contains NaN, which is only possible if it
+                /// was inserted into an otherwise empty vector.
+                fn try_from(sorted: partial::SortedVec<$float>) -> Result<Self, FloatIsNan> {
+                    let vec = sorted
+                        .into_vec()
+                        .into_iter()
+                        .map(NotNan::new)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(unsafe { SortedVec::from_unsorted_unchecked(vec) })
+                }
+            }
+        )*
+    };
+}
+
+impl_ordered_float!(
+    f32 => from_unsorted_f32, set_from_unsorted_f32,
+    f64 => from_unsorted_f64, set_from_unsorted_f64
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ordered_float() {
+        let set = set_from_unsorted_f32(vec![1.0, 0.0, 1.0]).unwrap();
+        assert_eq!(set.len(), 2);
+        let v = from_unsorted_f64(vec![3.0, -0.5, 1.0]).unwrap();
+        let ordered = OrderedFloatVec::<f64>::from(v.clone());
+        assert_eq!(
+            *ordered,
+            vec![OrderedFloat(-0.5), OrderedFloat(1.0), OrderedFloat(3.0)]
+        );
+        let partial = partial::SortedVec::<f64>::from(v.clone());
+        assert_eq!(NotNanVec::try_from(partial), Ok(v));
+        let mut nan = partial::SortedVec::new();
+        nan.insert(f64::NAN);
+        assert_eq!(NotNanVec::try_from(nan), Err(FloatIsNan));
+        assert_eq!(from_unsorted_f32(vec![f32::NAN]), Err(FloatIsNan));
+    }
+}
@@ -0,0 +1,196 @@
+//! `rayon` support (feature `rayon`).
+//!
+//! The containers can be consumed and borrowed as parallel iterators, and
+//! produced from them with `collect` and `par_extend`. Collected elements are
+//! sorted in parallel.
+//!
+//! ```
+//! use rayon::prelude::*;
+//! use sorted_vec2::SortedSet;
+//!
+//! let set: SortedSet<u32> = (0..1000u32).into_par_iter().map(|x| x % 10).collect();
+//! assert_eq!(set.len(), 10);
+//! assert_eq!(set.par_iter().sum::<u32>(), 45);
+//! ```
+//!
+//! Large vectors can also be sorted in parallel on construction with
+//! `par_from_unsorted`:
+//!
+//! ```
+//! use sorted_vec2::{SortedSet, SortedVec};
+//!
+//! let input: Vec<u64> = (0..100_000).map(|x| x * 7919 % 1000).collect();
+//! let v = SortedVec::par_from_unsorted(input.clone());
+//! assert_eq!(v, SortedVec::from_unsorted(input.clone()));
+//! assert_eq!(SortedSet::par_from_unsorted(input).len(), 1000);
+//! ```
+
+use std::marker::PhantomData;
+
+use ::rayon::prelude::*;
+
+use crate::{Ascending, PriorityQueue, SortOrder, SortedSet, SortedVec};
+
+impl<T: Ord + Send> SortedVec<T> {
+    /// Uses `par_sort_unstable()` to sort in place on the `rayon` thread pool.
+    #[inline]
+    pub fn par_from_unsorted(vec: Vec<T>) -> Self {
+        Self::par_from_unsorted_with_order(vec, Ascending)
+    }
+}
+
+impl<T: Ord + Send, O: SortOrder> SortedVec<T, O> {
+    /// Same as `par_from_unsorted`, sorting in the given order.
+    #[inline]
+    pub fn par_from_unsorted_with_order(vec: Vec<T>, _order: O) -> Self {
+        Self::par_sort_in_order(vec)
+    }
+    fn par_sort_in_order(mut vec: Vec<T>) -> Self {
+        vec.par_sort_unstable_by(O::compare);
+        SortedVec {
+            vec,
+            order: PhantomData,
+        }
+    }
+}
+
+impl<T: Ord + Send> SortedSet<T> {
+    /// Uses `par_sort_unstable()` to sort in place on the `rayon` thread pool
+    /// and `dedup()` to remove duplicates.
+    #[inline]
+    pub fn par_from_unsorted(vec: Vec<T>) -> Self {
+        Self::par_from_unsorted_with_order(vec, Ascending)
+    }
+}
+
+impl<T: Ord + Send, O: SortOrder> SortedSet<T, O> {
+    /// Same as `par_from_unsorted`, sorting in the given order.
+    #[inline]
+    pub fn par_from_unsorted_with_order(vec: Vec<T>, order: O) -> Self {
+        let mut set = SortedVec::par_from_unsorted_with_order(vec, order);
+        set.dedup();
+        SortedSet { set }
+    }
+}
+
+impl<T: Ord + Send, O: SortOrder> IntoParallelIterator for SortedVec<T, O> {
+    type Item = T;
+    type Iter = ::rayon::vec::IntoIter<T>;
+    fn into_par_iter(self) -> Self::Iter {
+        self.vec.into_par_iter()
+    }
+}
+
+impl<'a, T: Ord + Sync, O: SortOrder> IntoParallelIterator for &'a SortedVec<T, O> {
+    type Item = &'a T;
+    type Iter = ::rayon::slice::Iter<'a, T>;
+    fn into_par_iter(self) -> Self::Iter {
+        self.vec.par_iter()
+    }
+}
+
+impl<T: Ord + Send, O: SortOrder> FromParallelIterator<T> for SortedVec<T, O> {
+    fn from_par_iter<I: IntoParallelIterator<Item = T>>(par_iter: I) -> Self {
+        SortedVec::par_sort_in_order(par_iter.into_par_iter().collect())
+    }
+}
+
+impl<T: Ord + Send, O: SortOrder> ParallelExtend<T> for SortedVec<T, O> {
+    fn par_extend<I: IntoParallelIterator<Item = T>>(&mut self, par_iter: I) {
+        let mut other = SortedVec::<T, O>::from_par_iter(par_iter).vec;
+        self.vec.append(&mut other);
+        // merge sort of the two sorted runs
+        self.vec.par_sort_by(O::compare);
+    }
+}
+
+impl<T: Ord + Send, O: SortOrder> IntoParallelIterator for SortedSet<T, O> {
+    type Item = T;
+    type Iter = ::rayon::vec::IntoIter<T>;
+    fn into_par_iter(self) -> Self::Iter {
+        self.set.into_par_iter()
+    }
+}
+
+impl<'a, T: Ord + Sync, O: SortOrder> IntoParallelIterator for &'a SortedSet<T, O> {
+    type Item = &'a T;
+    type Iter = ::rayon::slice::Iter<'a, T>;
+    fn into_par_iter(self) -> Self::Iter {
+        self.set.vec.par_iter()
+    }
+}
+
+impl<T: Ord + Send, O: SortOrder> FromParallelIterator<T> for SortedSet<T, O> {
+    fn from_par_iter<I: IntoParallelIterator<Item = T>>(par_iter: I) -> Self {
+        let mut set = SortedVec::from_par_iter(par_iter);
+        set.dedup();
+        SortedSet { set }
+    }
+}
+
+impl<T: Ord + Send, O: SortOrder> ParallelExtend<T> for SortedSet<T, O> {
+    fn par_extend<I: IntoParallelIterator<Item = T>>(&mut self, par_iter: I) {
+        self.set.par_extend(par_iter);
+        self.set.dedup();
+    }
+}
+
+impl<T: Ord + Send> IntoParallelIterator for PriorityQueue<T> {
+    type Item = T;
+    type Iter = ::rayon::vec::IntoIter<T>;
+    /// Yields elements in ascending order
+    fn into_par_iter(self) -> Self::Iter {
+        self.into_vec().into_par_iter()
+    }
+}
+
+impl<'a, T: Ord + Sync> IntoParallelIterator for &'a PriorityQueue<T> {
+    type Item = &'a T;
+    type Iter = ::rayon::slice::Iter<'a, T>;
+    fn into_par_iter(self) -> Self::Iter {
+        self.as_slice().par_iter()
+    }
+}
+
+impl<T: Ord + Send> FromParallelIterator<T> for PriorityQueue<T> {
+    fn from_par_iter<I: IntoParallelIterator<Item = T>>(par_iter: I) -> Self {
+        PriorityQueue::from(SortedVec::from_par_iter(par_iter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{partial, ReverseSortedVec};
+
+    #[test]
+    fn test_par() {
+        let input: Vec<i32> = (0..10_000).map(|x| (x * 7919) % 1000 - 500).collect();
+        let mut v: SortedVec<i32> = input.par_iter().copied().collect();
+        assert_eq!(v, SortedVec::from_unsorted(input.clone()));
+        v.par_extend(vec![2000, -2000, 0]);
+        assert_eq!((v[0], v[v.len() - 1]), (-2000, 2000));
+        assert!(v.windows(2).all(|w| w[0] <= w[1]));
+        let d: ReverseSortedVec<i32> = input.par_iter().copied().collect();
+        assert_eq!(d.first(), Some(&499));
+        let s: SortedSet<i32> = v.into_par_iter().collect();
+        assert_eq!(s.len(), 1002);
+        assert_eq!(s.par_iter().filter(|x| **x < 0).count(), 501);
+        let p: partial::ReverseSortedSet<f64> = input.par_iter().map(|x| *x as f64).collect();
+        assert_eq!(p.len(), 1000);
+        assert_eq!(p[0], 499.0);
+        let q: PriorityQueue<i32> = input.into_par_iter().collect();
+        assert_eq!(q.peek(), Some(&499));
+    }
+
+    #[test]
+    fn test_par_from_unsorted() {
+        let input: Vec<i32> = (0..10_000).map(|x| (x * 7919) % 1000).collect();
+        let v = SortedVec::par_from_unsorted(input.clone());
+        assert_eq!(v, SortedVec::from_unsorted(input.clone()));
+        let d = SortedVec::par_from_unsorted_with_order(input.clone(), crate::Descending);
+        assert_eq!((d[0], d[d.len() - 1]), (999, 0));
+        let s = SortedSet::par_from_unsorted(input);
+        assert_eq!(**s, (0..1000).collect::<Vec<_>>());
+    }
+}
@@ -1,40 +1,238 @@
 //! Sorted vectors of types implementing `PartialOrd`.
 //!
-//! It is a runtime panic if an incomparable element is compared.
+//! It is a runtime panic if an incomparable element is compared. The `try_*`
+//! methods instead return an `IncomparableError`.
 
 use std;
 use std::hash::{Hash, Hasher};
 
+use crate::float::Float;
+
 
 /// Forward sorted vector
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+  all(feature = "serde", not(feature = "serde-nontransparent")),
+  serde(transparent))]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub struct SortedVec <T : PartialOrd> {
+  #[cfg_attr(feature = "serde", serde(deserialize_with = "SortedVec::parse_vec"))]
+  #[cfg_attr(feature = "serde",
+    serde(bound(deserialize = "T : serde::Deserialize <'de>")))]
   vec : Vec <T>
 }
 
 /// Forward sorted set
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+  all(feature = "serde", not(feature = "serde-nontransparent")),
+  serde(transparent))]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub struct SortedSet <T : PartialOrd> {
+  #[cfg_attr(feature = "serde", serde(rename = "vec",
+    serialize_with = "SortedSet::serialize_vec",
+    deserialize_with = "SortedSet::parse_vec"))]
+  #[cfg_attr(feature = "serde", serde(bound(
+    serialize = "T : serde::Serialize",
+    deserialize = "T : serde::Deserialize <'de>")))]
   set : SortedVec <T>
 }
 
 /// Reverse sorted vector
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+  all(feature = "serde", not(feature = "serde-nontransparent")),
+  serde(transparent))]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub struct ReverseSortedVec <T : PartialOrd> {
+  #[cfg_attr(feature = "serde", serde(deserialize_with = "ReverseSortedVec::parse_vec"))]
+  #[cfg_attr(feature = "serde",
+    serde(bound(deserialize = "T : serde::Deserialize <'de>")))]
   vec : Vec <T>
 }
 
 /// Reverse sorted set
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+  all(feature = "serde", not(feature = "serde-nontransparent")),
+  serde(transparent))]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub struct ReverseSortedSet <T : PartialOrd> {
+  #[cfg_attr(feature = "serde", serde(rename = "vec",
+    serialize_with = "ReverseSortedSet::serialize_vec",
+    deserialize_with = "ReverseSortedSet::parse_vec"))]
+  #[cfg_attr(feature = "serde", serde(bound(
+    serialize = "T : serde::Serialize",
+    deserialize = "T : serde::Deserialize <'de>")))]
   set : ReverseSortedVec <T>
 }
 
+/// An element could not be compared with an element of the container, e.g.
+/// a NaN float
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct IncomparableError;
+
+impl std::fmt::Display for IncomparableError {
+  fn fmt (&self, f : &mut std::fmt::Formatter) -> std::fmt::Result {
+    f.write_str ("elements are not comparable")
+  }
+}
+
+impl std::error::Error for IncomparableError {}
+
+/// Wrapper implementing `Ord` by unwrapping `partial_cmp`, to store
+/// `PartialOrd` types in the total-order containers, e.g. a
+/// `crate::SortedSet <AssertTotalOrd <f64>>`.
+///
+/// It is a runtime panic if incomparable values are compared.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq)]
+pub struct AssertTotalOrd <T : PartialOrd> (pub T);
+
+impl <T : PartialOrd> AssertTotalOrd <T> {
+  #[inline]
+  pub fn into_inner (self) -> T {
+    self.0
+  }
+}
+impl <T : PartialOrd> Eq for AssertTotalOrd <T> {}
+impl <T : PartialOrd> PartialOrd for AssertTotalOrd <T> {
+  fn partial_cmp (&self, other : &Self) -> Option <std::cmp::Ordering> {
+    Some (self.cmp (other))
+  }
+}
+impl <T : PartialOrd> Ord for AssertTotalOrd <T> {
+  fn cmp (&self, other : &Self) -> std::cmp::Ordering {
+    partial_compare (&self.0, &other.0)
+  }
+}
+impl <T : PartialOrd> From <T> for AssertTotalOrd <T> {
+  fn from (t : T) -> Self {
+    AssertTotalOrd (t)
+  }
+}
+impl <T : PartialOrd> From <SortedVec <T>> for crate::SortedVec <AssertTotalOrd <T>> {
+  fn from (sorted : SortedVec <T>) -> Self {
+    let vec = sorted.vec.into_iter().map (AssertTotalOrd).collect();
+    unsafe { crate::SortedVec::from_unsorted_unchecked (vec) }
+  }
+}
+impl <T : PartialOrd> From <crate::SortedVec <AssertTotalOrd <T>>> for SortedVec <T> {
+  fn from (sorted : crate::SortedVec <AssertTotalOrd <T>>) -> Self {
+    let vec = sorted.into_vec().into_iter().map (AssertTotalOrd::into_inner).collect();
+    SortedVec { vec }
+  }
+}
+impl <T : PartialOrd> From <SortedSet <T>> for crate::SortedSet <AssertTotalOrd <T>> {
+  fn from (sorted : SortedSet <T>) -> Self {
+    let set = crate::SortedVec::from (sorted.set);
+    crate::SortedSet { set }
+  }
+}
+impl <T : PartialOrd> From <crate::SortedSet <AssertTotalOrd <T>>> for SortedSet <T> {
+  fn from (sorted : crate::SortedSet <AssertTotalOrd <T>>) -> Self {
+    SortedSet { set: SortedVec::from (sorted.set) }
+  }
+}
+
 /// Unwraps a `partial_cmp`
 fn partial_compare <T : PartialOrd> (lhs : &T, rhs : &T) -> std::cmp::Ordering {
   lhs.partial_cmp (rhs).unwrap()
 }
 
+/// Index of the first element that is not comparable with itself, e.g. a NaN
+/// float. Elements incomparable only with other elements are not detected.
+fn find_incomparable <T : PartialOrd> (vec : &[T]) -> Option <usize> {
+  vec.iter().position (|x| x.partial_cmp (x).is_none())
+}
+
+/// Checks each element as it is deserialized: comparable with itself and
+/// sorted (in reverse) after the previous element, and optionally unique
+#[cfg(feature = "serde")]
+fn check_next_partial <T : PartialOrd> (reverse : bool, unique : bool)
+  -> impl Fn (Option <&T>, &T) -> Result <(), &'static str>
+{
+  move |prev, x| {
+    if x.partial_cmp (x).is_none() {
+      return Err ("input contains incomparable elements")
+    }
+    let ordering = match prev {
+      Some (prev) if reverse => x.partial_cmp (prev),
+      Some (prev) => prev.partial_cmp (x),
+      None => return Ok (())
+    };
+    match ordering {
+      Some (std::cmp::Ordering::Less) => Ok (()),
+      Some (std::cmp::Ordering::Equal) if !unique => Ok (()),
+      Some (std::cmp::Ordering::Equal) =>
+        Err ("input set contains duplicate values"),
+      _ => Err ("input sequence is not sorted")
+    }
+  }
+}
+
+/// Index range of the elements within `epsilon` of `x` in a vector sorted by
+/// `compare`. Bounded on `Float`, which saturates at infinity, since integer
+/// `x ± epsilon` would overflow near the extremes.
+pub(crate) fn within_range <T, C> (vec : &[T], x : T, epsilon : T, compare : C)
+  -> std::ops::Range <usize>
+where
+  T : Float,
+  C : Fn (&T, &T) -> std::cmp::Ordering
+{
+  let (lo, hi) = (x - epsilon, x + epsilon);
+  let (first, last) = if compare (&lo, &hi).is_le() { (lo, hi) } else { (hi, lo) };
+  let start = vec.partition_point (|y| compare (y, &first).is_lt());
+  let end   = vec.partition_point (|y| compare (y, &last).is_le());
+  start..end.max (start)
+}
+
+/// Index of the element closest to `x` among those within `epsilon` of it in
+/// a vector sorted by `compare`, or the insertion point of `x` if there are
+/// none
+pub(crate) fn search_approx <T, C> (vec : &[T], x : T, epsilon : T, compare : C)
+  -> Result <usize, usize>
+where
+  T : Float,
+  C : Fn (&T, &T) -> std::cmp::Ordering
+{
+  let range = within_range (vec, x, epsilon, &compare);
+  let at = vec.partition_point (|y| compare (y, &x).is_lt());
+  if range.is_empty() {
+    return Err (at)
+  }
+  let distance = |y : T| if y < x { x - y } else { y - x };
+  let closest = at.clamp (range.start, range.end - 1);
+  if closest > range.start && distance (vec[closest - 1]) <= distance (vec[closest]) {
+    Ok (closest - 1)
+  } else {
+    Ok (closest)
+  }
+}
+
+/// Binary search returning an error instead of panicking if `x` is
+/// incomparable with itself or any element compared along the way
+fn try_binary_search <T : PartialOrd> (vec : &[T], x : &T, reverse : bool)
+  -> Result <Result <usize, usize>, IncomparableError>
+{
+  if x.partial_cmp (x).is_none() {
+    return Err (IncomparableError)
+  }
+  let mut incomparable = false;
+  let result = vec.binary_search_by (|y| match y.partial_cmp (x) {
+    Some (ordering) if reverse => ordering.reverse(),
+    Some (ordering) => ordering,
+    None => {
+      incomparable = true;
+      std::cmp::Ordering::Equal
+    }
+  });
+  if incomparable {
+    Err (IncomparableError)
+  } else {
+    Ok (result)
+  }
+}
+
 //
 //  impl SortedVec
 //
@@ -54,6 +252,28 @@ impl <T : PartialOrd> SortedVec <T> {
     vec.sort_unstable_by (partial_compare);
     SortedVec { vec }
   }
+  /// Same as `from_unsorted`, except that if an element is not comparable with
+  /// itself (e.g. NaN) the input vector is returned unchanged together with the
+  /// index of the first such element, instead of panicking while sorting.
+  ///
+  /// Only elements that are not comparable with themselves are detected; a
+  /// pair of distinct elements that are incomparable with each other still
+  /// makes sorting panic as in `from_unsorted`.
+  pub fn try_from_unsorted (vec : Vec <T>) -> Result <Self, (Vec <T>, usize)> {
+    match find_incomparable (&vec) {
+      Some (index) => Err ((vec, index)),
+      None => Ok (Self::from_unsorted (vec))
+    }
+  }
+  /// # Safety
+  ///
+  /// The caller must ensure that the provided vector is already sorted and
+  /// contains no elements that are incomparable with each other or with
+  /// themselves (e.g. NaN), since searches rely on `partial_cmp` returning
+  /// `Some` for every pair.
+  pub unsafe fn from_unsorted_unchecked (vec : Vec <T>) -> Self {
+    SortedVec { vec }
+  }
   /// Insert an element into sorted position, returning the order index at which
   /// it was placed.
   ///
@@ -94,6 +314,94 @@ impl <T : PartialOrd> SortedVec <T> {
   pub fn binary_search (&self, x : &T) -> Result <usize, usize> {
     self.vec.binary_search_by (|y| partial_compare (y, x))
   }
+  /// Index of the element closest to `x` among the elements within `epsilon`
+  /// of it with `Ok`, otherwise the index where `x` would be inserted with
+  /// `Err`.
+  ///
+  /// Partial order comparison panics if items are not comparable.
+  pub fn binary_search_approx (&self, x : T, epsilon : T) -> Result <usize, usize>
+  where
+    T : Float
+  {
+    search_approx (&self.vec, x, epsilon, partial_compare)
+  }
+  /// The elements within `epsilon` of `x`.
+  ///
+  /// Partial order comparison panics if items are not comparable.
+  ///
+  /// Only float elements are supported, as `x ± epsilon` could overflow for
+  /// integers:
+  ///
+  /// ```compile_fail
+  /// use sorted_vec2::partial::SortedVec;
+  ///
+  /// let v = SortedVec::from_unsorted (vec![i32::MAX - 1, i32::MAX]);
+  /// v.find_within (i32::MAX, 1);
+  /// ```
+  pub fn find_within (&self, x : T, epsilon : T) -> &[T] where
+    T : Float
+  {
+    &self.vec[within_range (&self.vec, x, epsilon, partial_compare)]
+  }
+  /// Same as `binary_search`, returning an error instead of panicking if
+  /// items are not comparable.
+  #[inline]
+  pub fn try_binary_search (&self, x : &T)
+    -> Result <Result <usize, usize>, IncomparableError>
+  {
+    try_binary_search (&self.vec, x, false)
+  }
+  /// Same as `insert`, returning an error instead of panicking if items are
+  /// not comparable.
+  pub fn try_insert (&mut self, element : T) -> Result <usize, IncomparableError> {
+    let insert_at = match self.try_binary_search (&element)? {
+      Ok (insert_at) | Err (insert_at) => insert_at
+    };
+    self.vec.insert (insert_at, element);
+    Ok (insert_at)
+  }
+  /// Same as `find_or_insert`, returning an error instead of panicking if items
+  /// are not comparable.
+  pub fn try_find_or_insert (&mut self, element : T)
+    -> Result <Result <usize, usize>, IncomparableError>
+  {
+    match self.try_binary_search (&element)? {
+      Ok (found_at) => Ok (Ok (found_at)),
+      Err (insert_at) => {
+        self.vec.insert (insert_at, element);
+        Ok (Err (insert_at))
+      }
+    }
+  }
+  /// Same as `remove_item`, returning an error instead of panicking if items
+  /// are not comparable.
+  pub fn try_remove_item (&mut self, item : &T)
+    -> Result <Option <T>, IncomparableError>
+  {
+    let remove_at = self.try_binary_search (item)?.ok();
+    Ok (remove_at.map (|remove_at| self.vec.remove (remove_at)))
+  }
+  /// Index of the first element that does not come before `x` in the
+  /// sorted order, i.e. where `x` would be inserted before any equal
+  /// elements.
+  #[inline]
+  pub fn lower_bound (&self, x : &T) -> usize {
+    self.vec.partition_point (|y| partial_compare (y, x).is_lt())
+  }
+  /// Index of the first element that comes after `x` in the sorted
+  /// order, i.e. where `x` would be inserted after any equal elements.
+  #[inline]
+  pub fn upper_bound (&self, x : &T) -> usize {
+    self.vec.partition_point (|y| partial_compare (y, x).is_le())
+  }
+  /// Index of the first element for which `pred` is false. `pred` must hold
+  /// for a (possibly empty) prefix of the elements in sorted order.
+  #[inline]
+  pub fn partition_point <P> (&self, pred : P) -> usize where
+    P : FnMut (&T) -> bool
+  {
+    self.vec.partition_point (pred)
+  }
   #[inline]
   pub fn pop (&mut self) -> Option <T> {
     self.vec.pop()
@@ -119,10 +427,23 @@ impl <T : PartialOrd> SortedVec <T> {
   {
     self.vec.drain (range)
   }
+  /// Remove consecutive elements related by `same_bucket`, see
+  /// `Vec::dedup_by`.
+  #[inline]
+  pub fn dedup_by <F> (&mut self, same_bucket : F) where
+    F : FnMut (&mut T, &mut T) -> bool
+  {
+    self.vec.dedup_by (same_bucket);
+  }
   #[inline]
   pub fn retain <F> (&mut self, f : F) where F : FnMut (&T) -> bool {
     self.vec.retain (f)
   }
+  /// Same as `retain`, except that `f` may mutate the elements. Uses
+  /// `sort_unstable_by()` to re-sort the retained elements.
+  pub fn retain_mut <F> (&mut self, f : F) where F : FnMut (&mut T) -> bool {
+    self.mutate_vec (|vec| vec.retain_mut (f))
+  }
   /// NOTE: to_vec() is a slice method that is accessible through deref,
   /// use this instead to avoid cloning
   #[inline]
@@ -138,6 +459,31 @@ impl <T : PartialOrd> SortedVec <T> {
     self.vec.sort_unstable_by (partial_compare);
     res
   }
+  /// Perform sorting on the input sequence when deserializing with `serde`,
+  /// failing if it contains incomparable elements.
+  ///
+  /// Use with
+  /// `#[serde(deserialize_with = "partial::SortedVec::deserialize_unsorted")]`.
+  #[cfg(feature = "serde")]
+  pub fn deserialize_unsorted <'de, D> (deserializer : D) -> Result <Self, D::Error>
+  where
+    D : serde::Deserializer <'de>,
+    T : serde::Deserialize <'de>
+  {
+    use serde::de::Error;
+    use serde::Deserialize;
+    let v = Vec::deserialize (deserializer)?;
+    Self::try_from_unsorted (v).map_err (|_|
+      D::Error::custom ("input sequence contains incomparable elements"))
+  }
+  #[cfg(feature = "serde")]
+  fn parse_vec <'de, D> (deserializer : D) -> Result <Vec <T>, D::Error> where
+    D : serde::Deserializer <'de>,
+    T : serde::Deserialize <'de>
+  {
+    crate::de::deserialize_sorted_vec (deserializer, usize::MAX,
+      check_next_partial (false, false))
+  }
 }
 impl <T : PartialOrd> Default for SortedVec <T> {
   fn default() -> Self {
@@ -190,6 +536,18 @@ impl <T : PartialOrd> SortedSet <T> {
     set.dedup();
     SortedSet { set }
   }
+  /// Same as `from_unsorted`, except that if an element is not comparable with
+  /// itself (e.g. NaN) the input vector is returned unchanged together with the
+  /// index of the first such element, instead of panicking while sorting.
+  ///
+  /// Only elements that are not comparable with themselves are detected; a
+  /// pair of distinct elements that are incomparable with each other still
+  /// makes sorting panic as in `from_unsorted`.
+  pub fn try_from_unsorted (vec : Vec <T>) -> Result <Self, (Vec <T>, usize)> {
+    let mut set = SortedVec::try_from_unsorted (vec)?;
+    set.dedup();
+    Ok (SortedSet { set })
+  }
   /// Insert an element into sorted position, returning the order index at which
   /// it was placed.
   #[inline]
@@ -203,6 +561,42 @@ impl <T : PartialOrd> SortedSet <T> {
   pub fn find_or_insert (&mut self, element : T) -> Result <usize, usize> {
     self.set.find_or_insert (element)
   }
+  /// Same as `insert`, returning an error instead of panicking if items are
+  /// not comparable.
+  pub fn try_insert (&mut self, element : T) -> Result <usize, IncomparableError> {
+    let _ = self.try_remove_item (&element)?;
+    self.set.try_insert (element)
+  }
+  /// Same as `find_or_insert`, returning an error instead of panicking if items
+  /// are not comparable.
+  #[inline]
+  pub fn try_find_or_insert (&mut self, element : T)
+    -> Result <Result <usize, usize>, IncomparableError>
+  {
+    self.set.try_find_or_insert (element)
+  }
+  /// Same as `remove_item`, returning an error instead of panicking if items
+  /// are not comparable.
+  #[inline]
+  pub fn try_remove_item (&mut self, item : &T)
+    -> Result <Option <T>, IncomparableError>
+  {
+    self.set.try_remove_item (item)
+  }
+  /// Insert an element into sorted position, returning the order index at which
+  /// it was placed. If an existing item was found it will be returned.
+  pub fn replace (&mut self, mut element : T) -> (usize, Option <T>) {
+    match self.set.binary_search (&element) {
+      Ok (existing_index) => {
+        std::mem::swap (&mut element, &mut self.set.vec[existing_index]);
+        (existing_index, Some (element))
+      }
+      Err (insert_index) => {
+        self.set.vec.insert (insert_index, element);
+        (insert_index, None)
+      }
+    }
+  }
   #[inline]
   pub fn remove_item (&mut self, item : &T) -> Option <T> {
     self.set.remove_item (item)
@@ -230,6 +624,27 @@ impl <T : PartialOrd> SortedSet <T> {
   pub fn retain <F> (&mut self, f : F) where F : FnMut (&T) -> bool {
     self.set.retain (f)
   }
+  /// Same as `retain`, except that `f` may mutate the elements. Uses
+  /// `sort_unstable_by()` to re-sort and `dedup()` to remove any duplicates
+  /// among the retained elements.
+  pub fn retain_mut <F> (&mut self, f : F) where F : FnMut (&mut T) -> bool {
+    self.mutate_vec (|vec| vec.retain_mut (f))
+  }
+  /// Remove consecutive elements related by `same_bucket`, see
+  /// `Vec::dedup_by`.
+  #[inline]
+  pub fn dedup_by <F> (&mut self, same_bucket : F) where
+    F : FnMut (&mut T, &mut T) -> bool
+  {
+    self.set.dedup_by (same_bucket);
+  }
+  #[inline]
+  pub fn dedup_by_key <F, K> (&mut self, key : F) where
+    F : FnMut (&mut T) -> K,
+    K : PartialEq <K>
+  {
+    self.set.dedup_by_key (key);
+  }
   /// NOTE: to_vec() is a slice method that is accessible through deref, use
   /// this instead to avoid cloning
   #[inline]
@@ -246,6 +661,58 @@ impl <T : PartialOrd> SortedSet <T> {
     self.set.dedup();
     res
   }
+  /// Perform deduplication and sorting on the input sequence when
+  /// deserializing with `serde`, failing if it contains incomparable elements.
+  ///
+  /// Use with
+  /// `#[serde(deserialize_with = "partial::SortedSet::deserialize_dedup_unsorted")]`.
+  #[cfg(feature = "serde")]
+  pub fn deserialize_dedup_unsorted <'de, D> (deserializer : D)
+    -> Result <Self, D::Error>
+  where
+    D : serde::Deserializer <'de>,
+    T : serde::Deserialize <'de>
+  {
+    use serde::de::Error;
+    use serde::Deserialize;
+    let v = Vec::deserialize (deserializer)?;
+    Self::try_from_unsorted (v).map_err (|_|
+      D::Error::custom ("input set contains incomparable elements"))
+  }
+  /// Remove duplicates from the input sequence when deserializing with
+  /// `serde`, still failing if it is not sorted.
+  ///
+  /// Use with
+  /// `#[serde(deserialize_with = "partial::SortedSet::deserialize_dedup")]`.
+  #[cfg(feature = "serde")]
+  pub fn deserialize_dedup <'de, D> (deserializer : D) -> Result <Self, D::Error>
+  where
+    D : serde::Deserializer <'de>,
+    T : serde::Deserialize <'de>
+  {
+    let mut vec = crate::de::deserialize_sorted_vec (deserializer, usize::MAX,
+      check_next_partial (false, false))?;
+    vec.dedup();
+    Ok (SortedSet { set: SortedVec { vec } })
+  }
+  #[cfg(feature = "serde")]
+  fn serialize_vec <S> (set : &SortedVec <T>, serializer : S) -> Result <S::Ok, S::Error>
+  where
+    S : serde::Serializer,
+    T : serde::Serialize
+  {
+    serializer.collect_seq (set.iter())
+  }
+  #[cfg(feature = "serde")]
+  fn parse_vec <'de, D> (deserializer : D) -> Result <SortedVec <T>, D::Error>
+  where
+    D : serde::Deserializer <'de>,
+    T : serde::Deserialize <'de>
+  {
+    let vec = crate::de::deserialize_sorted_vec (deserializer, usize::MAX,
+      check_next_partial (false, true))?;
+    Ok (SortedVec { vec })
+  }
 }
 impl <T : PartialOrd> Default for SortedSet <T> {
   fn default() -> Self {
@@ -296,6 +763,19 @@ impl <T : PartialOrd> ReverseSortedVec <T> {
     vec.sort_unstable_by (|x,y| partial_compare (x,y).reverse());
     ReverseSortedVec { vec }
   }
+  /// Same as `from_unsorted`, except that if an element is not comparable with
+  /// itself (e.g. NaN) the input vector is returned unchanged together with the
+  /// index of the first such element, instead of panicking while sorting.
+  ///
+  /// Only elements that are not comparable with themselves are detected; a
+  /// pair of distinct elements that are incomparable with each other still
+  /// makes sorting panic as in `from_unsorted`.
+  pub fn try_from_unsorted (vec : Vec <T>) -> Result <Self, (Vec <T>, usize)> {
+    match find_incomparable (&vec) {
+      Some (index) => Err ((vec, index)),
+      None => Ok (Self::from_unsorted (vec))
+    }
+  }
   /// Insert an element into (reverse) sorted position, returning the order
   /// index at which it was placed.
   ///
@@ -336,6 +816,84 @@ impl <T : PartialOrd> ReverseSortedVec <T> {
   pub fn binary_search (&self, x : &T) -> Result <usize, usize> {
     self.vec.binary_search_by (|y| partial_compare (y, x).reverse())
   }
+  /// Index of the element closest to `x` among the elements within `epsilon`
+  /// of it with `Ok`, otherwise the index where `x` would be inserted with
+  /// `Err`.
+  ///
+  /// Partial order comparison panics if items are not comparable.
+  pub fn binary_search_approx (&self, x : T, epsilon : T) -> Result <usize, usize>
+  where
+    T : Float
+  {
+    search_approx (&self.vec, x, epsilon, |a, b| partial_compare (a, b).reverse())
+  }
+  /// The elements within `epsilon` of `x`.
+  ///
+  /// Partial order comparison panics if items are not comparable.
+  pub fn find_within (&self, x : T, epsilon : T) -> &[T] where
+    T : Float
+  {
+    &self.vec[within_range (&self.vec, x, epsilon, |a, b| partial_compare (a, b).reverse())]
+  }
+  /// Same as `binary_search`, returning an error instead of panicking if
+  /// items are not comparable.
+  #[inline]
+  pub fn try_binary_search (&self, x : &T)
+    -> Result <Result <usize, usize>, IncomparableError>
+  {
+    try_binary_search (&self.vec, x, true)
+  }
+  /// Same as `insert`, returning an error instead of panicking if items are
+  /// not comparable.
+  pub fn try_insert (&mut self, element : T) -> Result <usize, IncomparableError> {
+    let insert_at = match self.try_binary_search (&element)? {
+      Ok (insert_at) | Err (insert_at) => insert_at
+    };
+    self.vec.insert (insert_at, element);
+    Ok (insert_at)
+  }
+  /// Same as `find_or_insert`, returning an error instead of panicking if items
+  /// are not comparable.
+  pub fn try_find_or_insert (&mut self, element : T)
+    -> Result <Result <usize, usize>, IncomparableError>
+  {
+    match self.try_binary_search (&element)? {
+      Ok (found_at) => Ok (Ok (found_at)),
+      Err (insert_at) => {
+        self.vec.insert (insert_at, element);
+        Ok (Err (insert_at))
+      }
+    }
+  }
+  /// Same as `remove_item`, returning an error instead of panicking if items
+  /// are not comparable.
+  pub fn try_remove_item (&mut self, item : &T)
+    -> Result <Option <T>, IncomparableError>
+  {
+    let remove_at = self.try_binary_search (item)?.ok();
+    Ok (remove_at.map (|remove_at| self.vec.remove (remove_at)))
+  }
+  /// Index of the first element that does not come before `x` in the
+  /// reverse sorted order, i.e. where `x` would be inserted before any equal
+  /// elements.
+  #[inline]
+  pub fn lower_bound (&self, x : &T) -> usize {
+    self.vec.partition_point (|y| partial_compare (y, x).reverse().is_lt())
+  }
+  /// Index of the first element that comes after `x` in the reverse sorted
+  /// order, i.e. where `x` would be inserted after any equal elements.
+  #[inline]
+  pub fn upper_bound (&self, x : &T) -> usize {
+    self.vec.partition_point (|y| partial_compare (y, x).reverse().is_le())
+  }
+  /// Index of the first element for which `pred` is false. `pred` must hold
+  /// for a (possibly empty) prefix of the elements in reverse sorted order.
+  #[inline]
+  pub fn partition_point <P> (&self, pred : P) -> usize where
+    P : FnMut (&T) -> bool
+  {
+    self.vec.partition_point (pred)
+  }
   #[inline]
   pub fn pop (&mut self) -> Option <T> {
     self.vec.pop()
@@ -360,10 +918,23 @@ impl <T : PartialOrd> ReverseSortedVec <T> {
   {
     self.vec.drain (range)
   }
+  /// Remove consecutive elements related by `same_bucket`, see
+  /// `Vec::dedup_by`.
+  #[inline]
+  pub fn dedup_by <F> (&mut self, same_bucket : F) where
+    F : FnMut (&mut T, &mut T) -> bool
+  {
+    self.vec.dedup_by (same_bucket);
+  }
   #[inline]
   pub fn retain <F> (&mut self, f : F) where F : FnMut (&T) -> bool {
     self.vec.retain (f)
   }
+  /// Same as `retain`, except that `f` may mutate the elements. Uses
+  /// `sort_unstable_by()` to re-sort the retained elements.
+  pub fn retain_mut <F> (&mut self, f : F) where F : FnMut (&mut T) -> bool {
+    self.mutate_vec (|vec| vec.retain_mut (f))
+  }
   /// NOTE: to_vec() is a slice method that is accessible through deref,
   /// use this instead to avoid cloning
   #[inline]
@@ -379,6 +950,31 @@ impl <T : PartialOrd> ReverseSortedVec <T> {
     self.vec.sort_unstable_by (|x,y| partial_compare (x,y).reverse());
     res
   }
+  /// Perform sorting on the input sequence when deserializing with `serde`,
+  /// failing if it contains incomparable elements.
+  ///
+  /// Use with
+  /// `#[serde(deserialize_with = "partial::ReverseSortedVec::deserialize_unsorted")]`.
+  #[cfg(feature = "serde")]
+  pub fn deserialize_unsorted <'de, D> (deserializer : D) -> Result <Self, D::Error>
+  where
+    D : serde::Deserializer <'de>,
+    T : serde::Deserialize <'de>
+  {
+    use serde::de::Error;
+    use serde::Deserialize;
+    let v = Vec::deserialize (deserializer)?;
+    Self::try_from_unsorted (v).map_err (|_|
+      D::Error::custom ("input sequence contains incomparable elements"))
+  }
+  #[cfg(feature = "serde")]
+  fn parse_vec <'de, D> (deserializer : D) -> Result <Vec <T>, D::Error> where
+    D : serde::Deserializer <'de>,
+    T : serde::Deserialize <'de>
+  {
+    crate::de::deserialize_sorted_vec (deserializer, usize::MAX,
+      check_next_partial (true, false))
+  }
 }
 impl <T : PartialOrd> Default for ReverseSortedVec <T> {
   fn default() -> Self {
@@ -431,6 +1027,18 @@ impl <T : PartialOrd> ReverseSortedSet <T> {
     set.dedup();
     ReverseSortedSet { set }
   }
+  /// Same as `from_unsorted`, except that if an element is not comparable with
+  /// itself (e.g. NaN) the input vector is returned unchanged together with the
+  /// index of the first such element, instead of panicking while sorting.
+  ///
+  /// Only elements that are not comparable with themselves are detected; a
+  /// pair of distinct elements that are incomparable with each other still
+  /// makes sorting panic as in `from_unsorted`.
+  pub fn try_from_unsorted (vec : Vec <T>) -> Result <Self, (Vec <T>, usize)> {
+    let mut set = ReverseSortedVec::try_from_unsorted (vec)?;
+    set.dedup();
+    Ok (ReverseSortedSet { set })
+  }
   /// Insert an element into sorted position, returning the order index at which
   /// it was placed.
   #[inline]
@@ -444,6 +1052,42 @@ impl <T : PartialOrd> ReverseSortedSet <T> {
   pub fn find_or_insert (&mut self, element : T) -> Result <usize, usize> {
     self.set.find_or_insert (element)
   }
+  /// Same as `insert`, returning an error instead of panicking if items are
+  /// not comparable.
+  pub fn try_insert (&mut self, element : T) -> Result <usize, IncomparableError> {
+    let _ = self.try_remove_item (&element)?;
+    self.set.try_insert (element)
+  }
+  /// Same as `find_or_insert`, returning an error instead of panicking if items
+  /// are not comparable.
+  #[inline]
+  pub fn try_find_or_insert (&mut self, element : T)
+    -> Result <Result <usize, usize>, IncomparableError>
+  {
+    self.set.try_find_or_insert (element)
+  }
+  /// Same as `remove_item`, returning an error instead of panicking if items
+  /// are not comparable.
+  #[inline]
+  pub fn try_remove_item (&mut self, item : &T)
+    -> Result <Option <T>, IncomparableError>
+  {
+    self.set.try_remove_item (item)
+  }
+  /// Insert an element into sorted position, returning the order index at which
+  /// it was placed. If an existing item was found it will be returned.
+  pub fn replace (&mut self, mut element : T) -> (usize, Option <T>) {
+    match self.set.binary_search (&element) {
+      Ok (existing_index) => {
+        std::mem::swap (&mut element, &mut self.set.vec[existing_index]);
+        (existing_index, Some (element))
+      }
+      Err (insert_index) => {
+        self.set.vec.insert (insert_index, element);
+        (insert_index, None)
+      }
+    }
+  }
   #[inline]
   pub fn remove_item (&mut self, item : &T) -> Option <T> {
     self.set.remove_item (item)
@@ -471,6 +1115,27 @@ impl <T : PartialOrd> ReverseSortedSet <T> {
   pub fn retain <F> (&mut self, f : F) where F : FnMut (&T) -> bool {
     self.set.retain (f)
   }
+  /// Same as `retain`, except that `f` may mutate the elements. Uses
+  /// `sort_unstable_by()` to re-sort and `dedup()` to remove any duplicates
+  /// among the retained elements.
+  pub fn retain_mut <F> (&mut self, f : F) where F : FnMut (&mut T) -> bool {
+    self.mutate_vec (|vec| vec.retain_mut (f))
+  }
+  /// Remove consecutive elements related by `same_bucket`, see
+  /// `Vec::dedup_by`.
+  #[inline]
+  pub fn dedup_by <F> (&mut self, same_bucket : F) where
+    F : FnMut (&mut T, &mut T) -> bool
+  {
+    self.set.dedup_by (same_bucket);
+  }
+  #[inline]
+  pub fn dedup_by_key <F, K> (&mut self, key : F) where
+    F : FnMut (&mut T) -> K,
+    K : PartialEq <K>
+  {
+    self.set.dedup_by_key (key);
+  }
   /// NOTE: to_vec() is a slice method that is accessible through deref, use
   /// this instead to avoid cloning
   #[inline]
@@ -487,6 +1152,58 @@ impl <T : PartialOrd> ReverseSortedSet <T> {
     self.set.dedup();
     res
   }
+  /// Perform deduplication and sorting on the input sequence when
+  /// deserializing with `serde`, failing if it contains incomparable elements.
+  ///
+  /// Use with
+  /// `#[serde(deserialize_with = "partial::ReverseSortedSet::deserialize_dedup_unsorted")]`.
+  #[cfg(feature = "serde")]
+  pub fn deserialize_dedup_unsorted <'de, D> (deserializer : D)
+    -> Result <Self, D::Error>
+  where
+    D : serde::Deserializer <'de>,
+    T : serde::Deserialize <'de>
+  {
+    use serde::de::Error;
+    use serde::Deserialize;
+    let v = Vec::deserialize (deserializer)?;
+    Self::try_from_unsorted (v).map_err (|_|
+      D::Error::custom ("input set contains incomparable elements"))
+  }
+  /// Remove duplicates from the input sequence when deserializing with
+  /// `serde`, still failing if it is not sorted.
+  ///
+  /// Use with
+  /// `#[serde(deserialize_with = "partial::ReverseSortedSet::deserialize_dedup")]`.
+  #[cfg(feature = "serde")]
+  pub fn deserialize_dedup <'de, D> (deserializer : D) -> Result <Self, D::Error>
+  where
+    D : serde::Deserializer <'de>,
+    T : serde::Deserialize <'de>
+  {
+    let mut vec = crate::de::deserialize_sorted_vec (deserializer, usize::MAX,
+      check_next_partial (true, false))?;
+    vec.dedup();
+    Ok (ReverseSortedSet { set: ReverseSortedVec { vec } })
+  }
+  #[cfg(feature = "serde")]
+  fn serialize_vec <S> (set : &ReverseSortedVec <T>, serializer : S) -> Result <S::Ok, S::Error>
+  where
+    S : serde::Serializer,
+    T : serde::Serialize
+  {
+    serializer.collect_seq (set.iter())
+  }
+  #[cfg(feature = "serde")]
+  fn parse_vec <'de, D> (deserializer : D) -> Result <ReverseSortedVec <T>, D::Error>
+  where
+    D : serde::Deserializer <'de>,
+    T : serde::Deserialize <'de>
+  {
+    let vec = crate::de::deserialize_sorted_vec (deserializer, usize::MAX,
+      check_next_partial (true, true))?;
+    Ok (ReverseSortedVec { vec })
+  }
 }
 impl <T : PartialOrd> Default for ReverseSortedSet <T> {
   fn default() -> Self {
@@ -518,6 +1235,89 @@ impl <T : PartialOrd + Hash> Hash for ReverseSortedSet <T> {
   }
 }
 
+//
+//  impl rayon traits
+//
+
+#[cfg(feature = "rayon")]
+mod par {
+  use rayon::prelude::*;
+  use super::*;
+
+  macro_rules! impl_par_vec {
+    ($container:ident, $compare:expr) => {
+      impl <T : PartialOrd + Send> IntoParallelIterator for $container <T> {
+        type Item = T;
+        type Iter = rayon::vec::IntoIter <T>;
+        fn into_par_iter (self) -> Self::Iter {
+          self.vec.into_par_iter()
+        }
+      }
+      impl <'a, T : PartialOrd + Sync> IntoParallelIterator for &'a $container <T> {
+        type Item = &'a T;
+        type Iter = rayon::slice::Iter <'a, T>;
+        fn into_par_iter (self) -> Self::Iter {
+          self.vec.par_iter()
+        }
+      }
+      impl <T : PartialOrd + Send> FromParallelIterator <T> for $container <T> {
+        /// Panics if an element is not comparable with itself (e.g. NaN)
+        fn from_par_iter <I : IntoParallelIterator <Item = T>> (par_iter : I) -> Self {
+          let mut vec : Vec <T> = par_iter.into_par_iter().collect();
+          vec.par_sort_unstable_by ($compare);
+          $container { vec }
+        }
+      }
+      impl <T : PartialOrd + Send> ParallelExtend <T> for $container <T> {
+        fn par_extend <I : IntoParallelIterator <Item = T>> (&mut self, par_iter : I) {
+          let mut other = Self::from_par_iter (par_iter).vec;
+          self.vec.append (&mut other);
+          // merge sort of the two sorted runs
+          self.vec.par_sort_by ($compare);
+        }
+      }
+    }
+  }
+
+  macro_rules! impl_par_set {
+    ($container:ident, $inner:ident) => {
+      impl <T : PartialOrd + Send> IntoParallelIterator for $container <T> {
+        type Item = T;
+        type Iter = rayon::vec::IntoIter <T>;
+        fn into_par_iter (self) -> Self::Iter {
+          self.set.into_par_iter()
+        }
+      }
+      impl <'a, T : PartialOrd + Sync> IntoParallelIterator for &'a $container <T> {
+        type Item = &'a T;
+        type Iter = rayon::slice::Iter <'a, T>;
+        fn into_par_iter (self) -> Self::Iter {
+          self.set.vec.par_iter()
+        }
+      }
+      impl <T : PartialOrd + Send> FromParallelIterator <T> for $container <T> {
+        /// Panics if an element is not comparable with itself (e.g. NaN)
+        fn from_par_iter <I : IntoParallelIterator <Item = T>> (par_iter : I) -> Self {
+          let mut set = $inner::from_par_iter (par_iter);
+          set.dedup();
+          $container { set }
+        }
+      }
+      impl <T : PartialOrd + Send> ParallelExtend <T> for $container <T> {
+        fn par_extend <I : IntoParallelIterator <Item = T>> (&mut self, par_iter : I) {
+          self.set.par_extend (par_iter);
+          self.set.dedup();
+        }
+      }
+    }
+  }
+
+  impl_par_vec!(SortedVec, partial_compare);
+  impl_par_vec!(ReverseSortedVec, |x : &T, y : &T| partial_compare (x,y).reverse());
+  impl_par_set!(SortedSet, SortedVec);
+  impl_par_set!(ReverseSortedSet, ReverseSortedVec);
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -557,6 +1357,9 @@ mod tests {
     assert_eq!(s.find_or_insert (4.0), Ok (1));
     assert_eq!(s.len(), 3);
     assert_eq!(s.binary_search (&3.0), Ok (0));
+    assert_eq!(s.replace (4.0), (1, Some (4.0)));
+    assert_eq!(s.replace (6.0), (3, None));
+    assert_eq!(**s, vec![3.0, 4.0, 5.0, 6.0]);
     assert_eq!(**SortedSet::from_unsorted (
       vec![  5.0, -10.0, 99.0, -10.0, -11.0,  10.0, 2.0, 17.0, 10.0]),
       vec![-11.0, -10.0,  2.0,   5.0, 10.0, 17.0, 99.0]);
@@ -576,6 +1379,121 @@ mod tests {
       vec![-10.0, 2.0, 5.0, 10.0, 11.0, 17.0, 99.0]);
   }
 
+  #[test]
+  fn test_try_insert() {
+    let mut v = SortedVec::from_unsorted (vec![3.0, 1.0, 2.0]);
+    assert_eq!(v.try_insert (f64::NAN), Err (IncomparableError));
+    assert_eq!(v.try_insert (1.5), Ok (1));
+    assert_eq!(v.try_find_or_insert (2.0), Ok (Ok (2)));
+    assert_eq!(v.try_binary_search (&f64::NAN), Err (IncomparableError));
+    assert_eq!(v.try_remove_item (&1.5), Ok (Some (1.5)));
+    assert_eq!(*v, vec![1.0, 2.0, 3.0]);
+    let mut v = ReverseSortedVec::new();
+    assert_eq!(v.try_insert (f32::NAN), Err (IncomparableError));
+    assert_eq!(v.try_insert (1.0), Ok (0));
+    assert_eq!(v.try_find_or_insert (2.0), Ok (Err (0)));
+    let mut s = SortedSet::from_unsorted (vec![1.0, 2.0]);
+    assert_eq!(s.try_insert (2.0), Ok (1));
+    assert_eq!(s.try_remove_item (&f64::NAN), Err (IncomparableError));
+    let mut s = ReverseSortedSet::from_unsorted (vec![1.0, 2.0]);
+    assert_eq!(s.try_find_or_insert (0.0), Ok (Err (2)));
+    assert_eq!(s.try_insert (f64::NAN), Err (IncomparableError));
+    assert_eq!(**s, vec![2.0, 1.0, 0.0]);
+  }
+
+  #[test]
+  fn test_try_from_unsorted() {
+    let v = SortedVec::try_from_unsorted (vec![2.0, 1.0]).unwrap();
+    assert_eq!(*v, vec![1.0, 2.0]);
+    let (vec, index) =
+      SortedVec::try_from_unsorted (vec![2.0, 1.0, f64::NAN]).unwrap_err();
+    assert_eq!((vec[..2].to_vec(), index), (vec![2.0, 1.0], 2));
+    assert_eq!(
+      ReverseSortedSet::try_from_unsorted (vec![1.0, 2.0, 1.0]).map (|s| s.into_vec()),
+      Ok (vec![2.0, 1.0]));
+    assert!(SortedSet::try_from_unsorted (vec![f32::NAN]).is_err());
+    assert!(ReverseSortedVec::try_from_unsorted (vec![0.0, f32::NAN]).is_err());
+  }
+
+  #[test]
+  fn test_retain_mut() {
+    let mut v = SortedVec::from_unsorted (vec![1.0, 2.0, 3.0, 4.0]);
+    v.retain_mut (|x| {
+      *x = -*x;
+      *x < -1.0
+    });
+    assert_eq!(*v, vec![-4.0, -3.0, -2.0]);
+    v.dedup_by (|a, b| (*a - *b) < 1.5);
+    assert_eq!(*v, vec![-4.0, -2.0]);
+    let mut s = ReverseSortedSet::from_unsorted (vec![1.5f64, 2.5, 3.0]);
+    s.retain_mut (|x| {
+      *x = x.floor();
+      true
+    });
+    assert_eq!(**s, vec![3.0, 2.0, 1.0]);
+    s.dedup_by_key (|x| (*x / 2.0).floor());
+    assert_eq!(**s, vec![3.0, 1.0]);
+  }
+
+  #[cfg(all(feature = "serde", not(feature = "serde-nontransparent")))]
+  #[test]
+  fn test_serde() {
+    let v = SortedVec::from_unsorted (vec![2.5, -1.0]);
+    let s = serde_json::to_string (&v).unwrap();
+    assert_eq!(s, "[-1.0,2.5]");
+    assert_eq!(serde_json::from_str::<SortedVec <f64>> (&s).unwrap(), v);
+    assert!(serde_json::from_str::<SortedVec <f64>> ("[2.5,-1.0]").is_err());
+    assert!(serde_json::from_str::<ReverseSortedVec <f64>> ("[2.5,-1.0]").is_ok());
+    assert!(serde_json::from_str::<SortedSet <f64>> ("[1.0,1.0]").is_err());
+    assert!(
+      serde_json::from_str::<ReverseSortedSet <f64>> ("[-1.0,2.5]").is_err());
+    let s = ReverseSortedSet::<f64>::deserialize_dedup (
+      &mut serde_json::Deserializer::from_str ("[2.5,2.5,-1.0]")).unwrap();
+    assert_eq!(**s, vec![2.5, -1.0]);
+  }
+
+  #[test]
+  fn test_approx() {
+    let v = SortedVec::from_unsorted (vec![0.1, 0.2, 0.30000000000000004, 0.5]);
+    assert_eq!(v.binary_search (&0.3), Err (2));
+    assert_eq!(v.binary_search_approx (0.3, 1e-9), Ok (2));
+    assert_eq!(v.binary_search_approx (0.42, 0.1), Ok (3));
+    assert_eq!(v.binary_search_approx (0.4, 0.05), Err (3));
+    assert_eq!(v.find_within (0.2, 0.1 + 1e-9), [0.1, 0.2, 0.30000000000000004]);
+    assert!(v.find_within (1.0, 0.1).is_empty());
+    let v = ReverseSortedVec::from_unsorted (vec![1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(v.find_within (2.4, 1.0), [3.0, 2.0]);
+    assert_eq!(v.binary_search_approx (2.4, 1.0), Ok (2));
+    assert_eq!(v.binary_search_approx (0.0, 0.5), Err (4));
+    let v = SortedVec::from_unsorted (vec![f64::MIN, -1.0, f64::MAX]);
+    assert_eq!(v.find_within (f64::MAX, f64::MAX), [f64::MAX]);
+    assert_eq!(v.binary_search_approx (f64::MIN, 1.0), Ok (0));
+  }
+
+  #[test]
+  fn test_assert_total_ord() {
+    let mut s = crate::SortedSet::from_unsorted (
+      vec![2.0, 1.0, 2.0].into_iter().map (AssertTotalOrd).collect());
+    assert_eq!(s.len(), 2);
+    assert_eq!(s.find_or_insert (AssertTotalOrd (0.5)).index(), 0);
+    let s = SortedSet::from (s);
+    assert_eq!(**s, vec![0.5, 1.0, 2.0]);
+    let v = crate::SortedVec::from (SortedVec::from_unsorted (vec![3.0, -1.0]));
+    assert_eq!(v.binary_search (&AssertTotalOrd (3.0)), Ok (1));
+    assert_eq!(*SortedVec::from (v), vec![-1.0, 3.0]);
+  }
+
+  #[test]
+  fn test_bounds() {
+    let v = SortedVec::from_unsorted (vec![1.0, 3.0, 3.0, 5.0]);
+    assert_eq!((v.lower_bound (&3.0), v.upper_bound (&3.0)), (1, 3));
+    assert_eq!(v.partition_point (|x| *x < 5.0), 3);
+    let r = ReverseSortedSet::from_unsorted (vec![1.0, 3.0, 5.0]);
+    assert_eq!((r.lower_bound (&3.0), r.upper_bound (&3.0)), (1, 2));
+    assert_eq!((r.lower_bound (&4.0), r.upper_bound (&0.0)), (1, 3));
+    assert_eq!(r.partition_point (|x| *x > 1.0), 2);
+  }
+
   #[test]
   fn test_reverse_sorted_vec() {
     let mut v = ReverseSortedVec::new();
@@ -613,6 +1531,9 @@ mod tests {
     assert_eq!(s.find_or_insert (4.0), Ok (2));
     assert_eq!(s.len(), 4);
     assert_eq!(s.binary_search (&3.0), Ok (3));
+    assert_eq!(s.replace (4.0), (2, Some (4.0)));
+    assert_eq!(s.replace (1.0), (4, None));
+    assert_eq!(**s, vec![6.0, 5.0, 4.0, 3.0, 1.0]);
     assert_eq!(**ReverseSortedSet::from_unsorted (
       vec![5.0, -10.0, 99.0, -11.0, 2.0,  17.0,  10.0, -10.0]),
       vec![99.0, 17.0, 10.0,   5.0, 2.0, -10.0, -11.0]);
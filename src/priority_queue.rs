@@ -0,0 +1,226 @@
+//! Priority queue backed by a sorted vector.
+//!
+//! `PriorityQueue<T>` has the same semantics as
+//! `std::collections::BinaryHeap<T>`: `pop()` and `peek()` return the greatest
+//! element, and a min-queue is obtained by wrapping elements in
+//! `std::cmp::Reverse`. In addition the elements are always kept sorted, so
+//! iteration is ordered and range queries are available through the
+//! underlying `SortedVec`.
+//!
+//! Compared to a binary heap, `pop()` and `peek()` are O(1) while `push()` is
+//! O(n) in the worst case (O(1) when pushing a new greatest element).
+//!
+//! ```
+//! use sorted_vec2::PriorityQueue;
+//!
+//! let mut queue = PriorityQueue::new();
+//! queue.push(3);
+//! queue.push(7);
+//! queue.push(5);
+//! assert_eq!(queue.peek(), Some(&7));
+//! assert_eq!(queue.pop(), Some(7));
+//! assert_eq!(queue.into_sorted_vec(), vec![3, 5]);
+//! ```
+
+use crate::SortedVec;
+
+/// Max-priority queue backed by a `SortedVec`
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PriorityQueue<T: Ord> {
+    vec: SortedVec<T>,
+}
+
+impl<T: Ord> PriorityQueue<T> {
+    #[inline]
+    pub fn new() -> Self {
+        PriorityQueue {
+            vec: SortedVec::new(),
+        }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        PriorityQueue {
+            vec: SortedVec::with_capacity(capacity),
+        }
+    }
+    /// Push an element onto the queue.
+    #[inline]
+    pub fn push(&mut self, item: T) {
+        let _ = self.vec.push(item);
+    }
+    /// Remove the greatest element and return it, or `None` if empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.vec.pop()
+    }
+    /// The greatest element, or `None` if empty.
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        self.vec.last()
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional)
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    /// Move all elements of `other` into `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        self.vec.append(&mut other.vec);
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.vec.retain(f)
+    }
+    /// Elements in ascending order. Unlike `BinaryHeap::iter` the order is
+    /// not arbitrary.
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.vec.iter()
+    }
+    /// Elements in ascending order
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self.vec.as_slice()
+    }
+    /// Remove all elements, yielding them in ascending order.
+    #[inline]
+    pub fn drain(&mut self) -> std::vec::Drain<'_, T> {
+        self.vec.drain(..)
+    }
+    /// Remove all elements, yielding them in queue order (greatest first).
+    #[inline]
+    pub fn drain_sorted(&mut self) -> std::iter::Rev<std::vec::Drain<'_, T>> {
+        self.vec.drain(..).rev()
+    }
+    /// Consume the queue, returning a vector in ascending order.
+    #[inline]
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.vec.into_vec()
+    }
+    /// Consume the queue, returning the underlying vector (in ascending order).
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec.into_vec()
+    }
+    /// Consume the queue, yielding elements in queue order (greatest first).
+    #[inline]
+    pub fn into_iter_sorted(self) -> std::iter::Rev<crate::IntoIter<T>> {
+        self.vec.into_iter().rev()
+    }
+    /// Access the underlying sorted vector.
+    #[inline]
+    pub fn as_sorted_vec(&self) -> &SortedVec<T> {
+        &self.vec
+    }
+}
+
+impl<T: Ord> Default for PriorityQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Ord> From<Vec<T>> for PriorityQueue<T> {
+    fn from(vec: Vec<T>) -> Self {
+        PriorityQueue {
+            vec: SortedVec::from_unsorted(vec),
+        }
+    }
+}
+impl<T: Ord> From<SortedVec<T>> for PriorityQueue<T> {
+    fn from(vec: SortedVec<T>) -> Self {
+        PriorityQueue { vec }
+    }
+}
+impl<T: Ord> From<PriorityQueue<T>> for SortedVec<T> {
+    fn from(queue: PriorityQueue<T>) -> Self {
+        queue.vec
+    }
+}
+impl<T: Ord> From<std::collections::BinaryHeap<T>> for PriorityQueue<T> {
+    fn from(heap: std::collections::BinaryHeap<T>) -> Self {
+        PriorityQueue {
+            vec: unsafe { SortedVec::from_unsorted_unchecked(heap.into_sorted_vec()) },
+        }
+    }
+}
+impl<T: Ord> From<PriorityQueue<T>> for std::collections::BinaryHeap<T> {
+    fn from(queue: PriorityQueue<T>) -> Self {
+        // a sorted vector is not a valid max-heap layout, let `From<Vec>`
+        // rebuild it in O(n)
+        queue.vec.into_vec().into()
+    }
+}
+impl<T: Ord> FromIterator<T> for PriorityQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        PriorityQueue {
+            vec: iter.into_iter().collect(),
+        }
+    }
+}
+impl<T: Ord> Extend<T> for PriorityQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.vec.extend(iter)
+    }
+}
+impl<T: Ord> IntoIterator for PriorityQueue<T> {
+    type Item = T;
+    type IntoIter = crate::IntoIter<T>;
+    /// Yields elements in ascending order
+    fn into_iter(self) -> crate::IntoIter<T> {
+        self.vec.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    #[test]
+    fn test_priority_queue() {
+        let input = [5, -10, 99, -11, 2, 17, 10, 2];
+        let mut queue: PriorityQueue<i32> = input.into_iter().collect();
+        let mut heap: BinaryHeap<i32> = input.into_iter().collect();
+        assert_eq!(queue.peek(), heap.peek());
+        queue.push(50);
+        heap.push(50);
+        while let Some(x) = heap.pop() {
+            assert_eq!(queue.pop(), Some(x));
+        }
+        assert!(queue.is_empty());
+        let mut min = PriorityQueue::from(vec![Reverse(3), Reverse(1), Reverse(2)]);
+        assert_eq!(min.pop(), Some(Reverse(1)));
+        let mut other = PriorityQueue::from(vec![Reverse(0)]);
+        min.append(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(
+            min.drain_sorted().collect::<Vec<_>>(),
+            vec![Reverse(0), Reverse(2), Reverse(3)]
+        );
+        let queue = PriorityQueue::from(BinaryHeap::from(vec![4, 1, 3]));
+        assert_eq!(
+            queue.clone().into_iter_sorted().collect::<Vec<_>>(),
+            vec![4, 3, 1]
+        );
+        assert_eq!(BinaryHeap::from(queue).into_sorted_vec(), vec![1, 3, 4]);
+    }
+}
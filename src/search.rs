@@ -0,0 +1,342 @@
+//! Batched and restricted searches.
+//!
+//! `SortedVec::binary_search_batch` looks up many needles in a single pass
+//! when the needles are themselves sorted: each search gallops forward from
+//! the position of the previous result instead of starting over, so nearby
+//! needles only touch nearby memory.
+//!
+//! `SortedVec::binary_search_in` restricts a search to a known subrange, e.g.
+//! the remainder of a sequential scan, and `SortedVec::search_hinted` searches
+//! outwards from the index of the previous result, which is O(log d) for a
+//! result at distance d from the hint. `SortedVec::insert_hint` inserts at the
+//! position found the same way, and `SortedVec::insert_galloping` uses the
+//! back of the vector as the hint for insertions that cluster there.
+//!
+//! Like the other searches these compare through the container's `SortOrder`,
+//! so "less" and "greater" below refer to the order of the container.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::Range;
+
+use crate::set_ops::gallop;
+use crate::{SortOrder, SortedVec};
+
+// bounds over a slice sorted by `O`, shared by `SortedVec` and
+// `frozen::FrozenSortedVec`
+
+/// Index of the first element of `slice` that is not less than `x` in order `O`
+#[inline]
+pub(crate) fn lower_bound<O: SortOrder, T: Borrow<Q>, Q: Ord + ?Sized>(
+    slice: &[T],
+    x: &Q,
+) -> usize {
+    slice.partition_point(|y| O::compare(y.borrow(), x).is_lt())
+}
+
+/// Index of the first element of `slice` that is greater than `x` in order `O`
+#[inline]
+pub(crate) fn upper_bound<O: SortOrder, T: Borrow<Q>, Q: Ord + ?Sized>(
+    slice: &[T],
+    x: &Q,
+) -> usize {
+    slice.partition_point(|y| O::compare(y.borrow(), x).is_le())
+}
+
+/// The greatest element of `slice` less than or equal to `x` in order `O`
+#[inline]
+pub(crate) fn floor<'a, O: SortOrder, T: Borrow<Q>, Q: Ord + ?Sized>(
+    slice: &'a [T],
+    x: &Q,
+) -> Option<&'a T> {
+    upper_bound::<O, T, Q>(slice, x)
+        .checked_sub(1)
+        .map(|i| &slice[i])
+}
+
+/// The least element of `slice` greater than or equal to `x` in order `O`
+#[inline]
+pub(crate) fn ceiling<'a, O: SortOrder, T: Borrow<Q>, Q: Ord + ?Sized>(
+    slice: &'a [T],
+    x: &Q,
+) -> Option<&'a T> {
+    slice.get(lower_bound::<O, T, Q>(slice, x))
+}
+
+/// Index range of the elements of `slice` equal to `x`, located at the
+/// insertion point of `x` if there are none
+pub(crate) fn equal_range_indices<O: SortOrder, T: Borrow<Q>, Q: Ord + ?Sized>(
+    slice: &[T],
+    x: &Q,
+) -> Range<usize> {
+    let start = lower_bound::<O, T, Q>(slice, x);
+    let end = start + upper_bound::<O, T, Q>(&slice[start..], x);
+    start..end
+}
+
+/// Index range of the elements of `slice` within `range` in order `O`, empty if
+/// the start of the range comes after its end
+pub(crate) fn range_indices<O, T, Q, R>(slice: &[T], range: R) -> Range<usize>
+where
+    O: SortOrder,
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: std::ops::RangeBounds<Q>,
+{
+    use std::ops::Bound;
+    let start = match range.start_bound() {
+        Bound::Included(x) => lower_bound::<O, T, Q>(slice, x),
+        Bound::Excluded(x) => upper_bound::<O, T, Q>(slice, x),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(x) => upper_bound::<O, T, Q>(slice, x),
+        Bound::Excluded(x) => lower_bound::<O, T, Q>(slice, x),
+        Bound::Unbounded => slice.len(),
+    };
+    start..end.max(start)
+}
+
+impl<T: Ord, O: SortOrder> SortedVec<T, O> {
+    /// Binary search for each of the `needles`, in one pass if they are sorted.
+    ///
+    /// Found needles return the index of the leftmost equal element. A needle
+    /// that is less than its predecessor restarts the search from the front,
+    /// so unsorted needles give correct results but lose the speedup.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let v = SortedVec::from_unsorted(vec![10, 20, 30, 40]);
+    /// assert_eq!(
+    ///     v.binary_search_batch(&[5, 20, 35, 40]),
+    ///     vec![Err(0), Ok(1), Err(3), Ok(3)]
+    /// );
+    /// ```
+    pub fn binary_search_batch(&self, needles: &[T]) -> Vec<Result<usize, usize>> {
+        let mut results = Vec::with_capacity(needles.len());
+        let mut start = 0;
+        let mut previous: Option<&T> = None;
+        for needle in needles {
+            if previous.is_some_and(|previous| O::compare(needle, previous).is_lt()) {
+                start = 0;
+            }
+            start += gallop::<O, T>(&self.vec[start..], needle);
+            results.push(match self.vec.get(start) {
+                Some(x) if x == needle => Ok(start),
+                _ => Err(start),
+            });
+            previous = Some(needle);
+        }
+        results
+    }
+    /// Binary search for `x` within the elements at `range`, returning
+    /// indices into the whole vector. The result is only meaningful for the
+    /// whole vector if the caller knows `x` belongs in `range`.
+    ///
+    /// Panics if `range` is out of bounds.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let v = SortedVec::from_unsorted(vec![1, 3, 5, 7, 9]);
+    /// assert_eq!(v.binary_search_in(2..5, &7), Ok(3));
+    /// assert_eq!(v.binary_search_in(2..5, &6), Err(3));
+    /// ```
+    pub fn binary_search_in<Q>(&self, range: Range<usize>, x: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let start = range.start;
+        self.vec[range]
+            .binary_search_by(|y| O::compare(y.borrow(), x))
+            .map(|index| start + index)
+            .map_err(|index| start + index)
+    }
+    /// Binary search for `x` starting with an exponential search around
+    /// `*hint`, then store the resulting index in `*hint` for the next call.
+    /// Queries with temporal locality only probe the elements near the
+    /// previous result. Any `hint` value is valid.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let v = SortedVec::from_unsorted((0..1000).collect());
+    /// let mut hint = 0;
+    /// assert_eq!(v.search_hinted(&mut hint, &500), Ok(500));
+    /// assert_eq!(v.search_hinted(&mut hint, &503), Ok(503));
+    /// assert_eq!(hint, 503);
+    /// ```
+    pub fn search_hinted<Q>(&self, hint: &mut usize, x: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let len = self.vec.len();
+        if len == 0 {
+            *hint = 0;
+            return Err(0);
+        }
+        let h = (*hint).min(len - 1);
+        let result = match O::compare(self.vec[h].borrow(), x) {
+            Ordering::Equal => Ok(h),
+            Ordering::Less => {
+                // self.vec[h + bound / 2] < x
+                let mut bound = 1;
+                while h + bound < len && O::compare(self.vec[h + bound].borrow(), x).is_lt() {
+                    bound *= 2;
+                }
+                self.binary_search_in(h + bound / 2 + 1..(h + bound + 1).min(len), x)
+            }
+            Ordering::Greater => {
+                // self.vec[h - bound / 2] > x
+                let mut bound = 1;
+                while bound <= h && O::compare(self.vec[h - bound].borrow(), x).is_gt() {
+                    bound *= 2;
+                }
+                self.binary_search_in(h.saturating_sub(bound)..h - bound / 2, x)
+            }
+        };
+        match result {
+            Ok(index) | Err(index) => *hint = index,
+        }
+        result
+    }
+    /// Insert an element into sorted position, searching outwards from
+    /// `hint` as `search_hinted` does, and return the index at which it was
+    /// placed. Like `std::set::insert` with a hint in C++, a good hint, such
+    /// as `len()` for a stream of mostly increasing timestamps, makes the
+    /// search O(1). Any `hint` value is valid.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let mut events = SortedVec::from_unsorted(vec![100, 200, 300]);
+    /// assert_eq!(events.insert_hint(events.len(), 400), 3);
+    /// assert_eq!(events.insert_hint(events.len(), 250), 2);
+    /// assert_eq!(*events, vec![100, 200, 250, 300, 400]);
+    /// ```
+    pub fn insert_hint(&mut self, mut hint: usize, element: T) -> usize {
+        let insert_at = match self.search_hinted(&mut hint, &element) {
+            Ok(insert_at) | Err(insert_at) => insert_at,
+        };
+        self.vec.insert(insert_at, element);
+        insert_at
+    }
+    /// Insert an element into sorted position, galloping backwards from the
+    /// last element, and return the index at which it was placed. This is
+    /// O(log d) for an element placed d positions before the back, so it
+    /// beats `insert` when insertions cluster near the back, e.g. slightly
+    /// late events.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let mut v = SortedVec::from_unsorted((0..100).map(|x| x * 10).collect());
+    /// assert_eq!(v.insert_galloping(985), 99);
+    /// assert_eq!(v.insert_galloping(1000), 101);
+    /// ```
+    #[inline]
+    pub fn insert_galloping(&mut self, element: T) -> usize {
+        self.insert_hint(self.vec.len(), element)
+    }
+    /// The elements equal to each of the `needles`, see `binary_search_batch`.
+    pub fn get_many(&self, needles: &[T]) -> Vec<Option<&T>> {
+        self.binary_search_batch(needles)
+            .into_iter()
+            .map(|result| result.ok().map(|index| &self.vec[index]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ReverseSortedVec, SortedVec};
+
+    #[test]
+    fn test_binary_search_batch() {
+        let v = SortedVec::from_unsorted((0..100).map(|x| x * 2).collect());
+        let needles = [-1, 0, 0, 7, 8, 101, 150, 198, 199, 3];
+        let expected: Vec<_> = needles
+            .iter()
+            .map(|x| match v.binary_search(x) {
+                Ok(_) => Ok(v.lower_bound(x)),
+                Err(index) => Err(index),
+            })
+            .collect();
+        assert_eq!(v.binary_search_batch(&needles), expected);
+        let v = SortedVec::from_unsorted(vec![1, 1, 2]);
+        assert_eq!(v.get_many(&[1, 3]), vec![Some(&1), None]);
+        assert!(v.binary_search_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_binary_search_in() {
+        let v = SortedVec::from_unsorted(vec![1, 3, 5, 7, 9]);
+        assert_eq!(v.binary_search_in(0..5, &1), Ok(0));
+        assert_eq!(v.binary_search_in(1..3, &9), Err(3));
+        assert_eq!(v.binary_search_in(4..4, &9), Err(4));
+    }
+
+    #[test]
+    fn test_search_hinted() {
+        let v = SortedVec::from_unsorted((0..50).map(|x| x * 2).collect());
+        for start in [0, 1, 17, 49, 50, 1000] {
+            let mut hint = start;
+            for x in [-1, 0, 1, 2, 33, 34, 98, 99, 100, 51, 50, 7] {
+                let expected = v.binary_search(&x);
+                assert_eq!(v.search_hinted(&mut hint, &x), expected);
+                assert_eq!(hint, expected.unwrap_or_else(|index| index));
+            }
+        }
+        let mut hint = 3;
+        assert_eq!(SortedVec::<i32>::new().search_hinted(&mut hint, &1), Err(0));
+        assert_eq!(hint, 0);
+    }
+
+    #[test]
+    fn test_insert_hint() {
+        let mut v = SortedVec::new();
+        let mut w = SortedVec::new();
+        for (i, x) in [5, 3, 9, 3, 7, 0, 12, 12, 6].into_iter().enumerate() {
+            let index = v.insert_hint(i * 7 % 5, x);
+            assert_eq!(v[index], x);
+            w.insert(x);
+        }
+        assert_eq!(v, w);
+        assert_eq!(v.insert_hint(usize::MAX, 13), v.len() - 1);
+        assert_eq!(v.insert_hint(0, -1), 0);
+    }
+
+    #[test]
+    fn test_insert_galloping() {
+        let mut v = SortedVec::new();
+        let mut w = SortedVec::new();
+        for x in [10, 20, 15, 30, 29, 1, 30, 31] {
+            let index = v.insert_galloping(x);
+            assert_eq!(v[index], x);
+            w.insert(x);
+        }
+        assert_eq!(v, w);
+    }
+
+    #[test]
+    fn test_descending_searches() {
+        let mut v = ReverseSortedVec::from((0..50).map(|x| x * 2).collect::<Vec<_>>());
+        assert_eq!(
+            v.binary_search_batch(&[99, 98, 51, 50, -1]),
+            vec![Err(0), Ok(0), Err(24), Ok(24), Err(50)]
+        );
+        assert_eq!(v.get_many(&[2, 3]), vec![Some(&2), None]);
+        assert_eq!(v.binary_search_in(24..30, &40), Ok(29));
+        let mut hint = 0;
+        for x in [-1, 0, 33, 34, 98, 99, 7] {
+            let expected = v.binary_search(&x);
+            assert_eq!(v.search_hinted(&mut hint, &x), expected);
+        }
+        assert_eq!(v.insert_galloping(-3), 50);
+        assert_eq!(v.insert_hint(0, 51), 24);
+        assert_eq!(v.binary_search_in(0..v.len(), &51), Ok(24));
+    }
+}
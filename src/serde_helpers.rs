@@ -0,0 +1,144 @@
+//! `#[serde(with = "...")]` modules for plain `Vec<T>` fields (feature
+//! `serde`).
+//!
+//! - `sorted` -- deserializing fails unless the sequence is sorted in
+//!   ascending order, like `SortedVec`
+//! - `sorted_unique` -- deserializing fails unless the sequence is sorted in
+//!   ascending order without duplicates, like `SortedSet`
+//! - `sort` -- the sequence is sorted on deserialization
+//!
+//! Serialization is unchanged. For `SortedVec` and `SortedSet` fields that
+//! should be sorted on deserialization rather than validated, see the
+//! `unsorted` module.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Index {
+//!     #[serde(with = "sorted_vec2::serde_helpers::sorted_unique")]
+//!     ids: Vec<u64>,
+//!     #[serde(with = "sorted_vec2::serde_helpers::sort")]
+//!     scores: Vec<u32>,
+//! }
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::de;
+
+fn serialize_vec<T, S>(vec: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    serializer.collect_seq(vec)
+}
+
+/// Validate that a `Vec<T>` is sorted, see the module documentation
+pub mod sorted {
+    use super::*;
+
+    pub fn serialize<T, S>(vec: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        serialize_vec(vec, serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        T: Ord + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        de::deserialize_sorted_vec(
+            deserializer,
+            usize::MAX,
+            |prev: Option<&T>, x: &T| match prev {
+                Some(prev) if prev > x => Err("input sequence is not sorted"),
+                _ => Ok(()),
+            },
+        )
+    }
+}
+
+/// Validate that a `Vec<T>` is sorted and unique, see the module documentation
+pub mod sorted_unique {
+    use super::*;
+
+    pub fn serialize<T, S>(vec: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        serialize_vec(vec, serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        T: Ord + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        de::deserialize_sorted_vec(
+            deserializer,
+            usize::MAX,
+            |prev: Option<&T>, x: &T| match prev.map(|prev| prev.cmp(x)) {
+                Some(std::cmp::Ordering::Greater) => Err("input set is not sorted"),
+                Some(std::cmp::Ordering::Equal) => Err("input set contains duplicate values"),
+                _ => Ok(()),
+            },
+        )
+    }
+}
+
+/// Sort a `Vec<T>` on deserialization, see the module documentation
+pub mod sort {
+    use super::*;
+
+    pub fn serialize<T, S>(vec: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        serialize_vec(vec, serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        T: Ord + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let mut vec = Vec::deserialize(deserializer)?;
+        vec.sort_unstable();
+        Ok(vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Foo {
+        #[serde(with = "crate::serde_helpers::sorted")]
+        a: Vec<i32>,
+        #[serde(with = "crate::serde_helpers::sorted_unique")]
+        b: Vec<i32>,
+        #[serde(with = "crate::serde_helpers::sort")]
+        c: Vec<i32>,
+    }
+
+    #[test]
+    fn test_serde_helpers() {
+        let s = r#"{"a":[1,1,2],"b":[1,2],"c":[3,1,2]}"#;
+        let foo = serde_json::from_str::<Foo>(s).unwrap();
+        assert_eq!(foo.c, vec![1, 2, 3]);
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"a":[1,1,2],"b":[1,2],"c":[1,2,3]}"#
+        );
+        assert!(serde_json::from_str::<Foo>(r#"{"a":[2,1],"b":[],"c":[]}"#).is_err());
+        assert!(serde_json::from_str::<Foo>(r#"{"a":[],"b":[1,1],"c":[]}"#).is_err());
+    }
+}
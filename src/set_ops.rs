@@ -0,0 +1,737 @@
+//! Lazy set operations on `SortedSet`.
+//!
+//! The iterators walk both sets in a single merge pass and yield references in
+//! sorted order without duplicates, mirroring the `BTreeSet` operations. They
+//! are available for sets in either `SortOrder`, and are `SortedIterator`s
+//! for the default `Ascending` order.
+//!
+//! The `is_subset`, `is_superset` and `is_disjoint` predicates are also
+//! provided for `SortedVec`, with multiset semantics, and `SortedVec::merge_iter`
+//! streams the merged order of two vectors without allocating.
+
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::marker::PhantomData;
+
+use crate::{Ascending, SortOrder, SortedIterator, SortedSet, SortedVec, UniqueSortedIterator};
+
+/// Iterator over the elements in either set, see `SortedSet::union`
+#[derive(Clone, Debug)]
+pub struct Union<'a, T, O = Ascending> {
+    a: Peekable<std::slice::Iter<'a, T>>,
+    b: Peekable<std::slice::Iter<'a, T>>,
+    order: PhantomData<O>,
+}
+
+impl<'a, T: Ord, O: SortOrder> Iterator for Union<'a, T, O> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        match (self.a.peek(), self.b.peek()) {
+            (Some(a), Some(b)) => match O::compare(*a, *b) {
+                Ordering::Less => self.a.next(),
+                Ordering::Greater => self.b.next(),
+                Ordering::Equal => {
+                    self.b.next();
+                    self.a.next()
+                }
+            },
+            (Some(_), None) => self.a.next(),
+            (None, _) => self.b.next(),
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = (self.a.len(), self.b.len());
+        (a.max(b), Some(a + b))
+    }
+}
+impl<T: Ord, O: SortOrder> std::iter::FusedIterator for Union<'_, T, O> {}
+unsafe impl<T: Ord> SortedIterator for Union<'_, T> {}
+unsafe impl<T: Ord> UniqueSortedIterator for Union<'_, T> {}
+
+/// Size ratio above which `Intersection` gallops through the larger set
+/// instead of stepping one element at a time
+const GALLOP_RATIO: usize = 16;
+
+/// Index of the first element of `slice` that is not ordered before `x`,
+/// probing exponentially from the front. O(log d) where d is the returned
+/// index.
+pub(crate) fn gallop<O: SortOrder, T: Ord>(slice: &[T], x: &T) -> usize {
+    let mut bound = 1;
+    while bound < slice.len() && O::compare(&slice[bound], x).is_lt() {
+        bound *= 2;
+    }
+    let lo = bound / 2;
+    let hi = (bound + 1).min(slice.len());
+    lo + slice[lo..hi].partition_point(|y| O::compare(y, x).is_lt())
+}
+
+/// Iterator over the elements in both sets, see `SortedSet::intersection`
+#[derive(Clone, Debug)]
+pub struct Intersection<'a, T, O = Ascending> {
+    a: &'a [T],
+    b: &'a [T],
+    gallop: bool,
+    order: PhantomData<O>,
+}
+
+impl<'a, T: Ord, O: SortOrder> Intersection<'a, T, O> {
+    fn new(a: &'a [T], b: &'a [T]) -> Self {
+        let (small, large) = (a.len().min(b.len()), a.len().max(b.len()));
+        Intersection {
+            a,
+            b,
+            gallop: large / GALLOP_RATIO > small,
+            order: PhantomData,
+        }
+    }
+    #[inline]
+    fn advance(&self, slice: &'a [T], x: &T) -> &'a [T] {
+        if self.gallop {
+            &slice[gallop::<O, T>(slice, x)..]
+        } else {
+            &slice[1..]
+        }
+    }
+}
+
+impl<'a, T: Ord, O: SortOrder> Iterator for Intersection<'a, T, O> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let (a, b) = (self.a.first()?, self.b.first()?);
+            match O::compare(a, b) {
+                Ordering::Less => self.a = self.advance(self.a, b),
+                Ordering::Greater => self.b = self.advance(self.b, a),
+                Ordering::Equal => {
+                    self.a = &self.a[1..];
+                    self.b = &self.b[1..];
+                    return Some(a);
+                }
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.a.len().min(self.b.len())))
+    }
+}
+impl<T: Ord, O: SortOrder> std::iter::FusedIterator for Intersection<'_, T, O> {}
+unsafe impl<T: Ord> SortedIterator for Intersection<'_, T> {}
+unsafe impl<T: Ord> UniqueSortedIterator for Intersection<'_, T> {}
+
+/// Iterator over the elements in `self` but not in `other`, see
+/// `SortedSet::difference`
+#[derive(Clone, Debug)]
+pub struct Difference<'a, T, O = Ascending> {
+    a: &'a [T],
+    b: &'a [T],
+    order: PhantomData<O>,
+}
+
+impl<'a, T: Ord, O: SortOrder> Iterator for Difference<'a, T, O> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let a = self.a.first()?;
+            match self.b.first().map(|b| O::compare(a, b)) {
+                None | Some(Ordering::Less) => {
+                    self.a = &self.a[1..];
+                    return Some(a);
+                }
+                Some(Ordering::Greater) => self.b = &self.b[1..],
+                Some(Ordering::Equal) => {
+                    self.a = &self.a[1..];
+                    self.b = &self.b[1..];
+                }
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = (self.a.len(), self.b.len());
+        (a.saturating_sub(b), Some(a))
+    }
+}
+impl<T: Ord, O: SortOrder> std::iter::FusedIterator for Difference<'_, T, O> {}
+unsafe impl<T: Ord> SortedIterator for Difference<'_, T> {}
+unsafe impl<T: Ord> UniqueSortedIterator for Difference<'_, T> {}
+
+/// Iterator over the elements in exactly one of the sets, see
+/// `SortedSet::symmetric_difference`
+#[derive(Clone, Debug)]
+pub struct SymmetricDifference<'a, T, O = Ascending> {
+    a: &'a [T],
+    b: &'a [T],
+    order: PhantomData<O>,
+}
+
+impl<'a, T: Ord, O: SortOrder> Iterator for SymmetricDifference<'a, T, O> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            match (self.a.first(), self.b.first()) {
+                (Some(a), Some(b)) => match O::compare(a, b) {
+                    Ordering::Less => {
+                        self.a = &self.a[1..];
+                        return Some(a);
+                    }
+                    Ordering::Greater => {
+                        self.b = &self.b[1..];
+                        return Some(b);
+                    }
+                    Ordering::Equal => {
+                        self.a = &self.a[1..];
+                        self.b = &self.b[1..];
+                    }
+                },
+                (Some(a), None) => {
+                    self.a = &self.a[1..];
+                    return Some(a);
+                }
+                (None, Some(b)) => {
+                    self.b = &self.b[1..];
+                    return Some(b);
+                }
+                (None, None) => return None,
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.a.len() + self.b.len()))
+    }
+}
+impl<T: Ord, O: SortOrder> std::iter::FusedIterator for SymmetricDifference<'_, T, O> {}
+unsafe impl<T: Ord> SortedIterator for SymmetricDifference<'_, T> {}
+unsafe impl<T: Ord> UniqueSortedIterator for SymmetricDifference<'_, T> {}
+
+/// Iterator over the elements of both vectors in sorted order, see
+/// `SortedVec::merge_iter`
+#[derive(Clone, Debug)]
+pub struct MergeIter<'a, T, O = Ascending> {
+    a: &'a [T],
+    b: &'a [T],
+    dedup: bool,
+    order: PhantomData<O>,
+}
+
+impl<'a, T: Ord, O: SortOrder> MergeIter<'a, T, O> {
+    /// Yield each distinct element once, taken from the first vector if it
+    /// occurs in both.
+    pub fn dedup(mut self) -> Self {
+        self.dedup = true;
+        self
+    }
+}
+
+impl<'a, T: Ord, O: SortOrder> Iterator for MergeIter<'a, T, O> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        let next = match (self.a.first(), self.b.first()) {
+            (Some(a), Some(b)) if O::compare(a, b).is_gt() => {
+                self.b = &self.b[1..];
+                b
+            }
+            (Some(a), _) => {
+                self.a = &self.a[1..];
+                a
+            }
+            (None, b) => {
+                self.b = self.b.get(1..).unwrap_or_default();
+                b?
+            }
+        };
+        if self.dedup {
+            while self.a.first() == Some(next) {
+                self.a = &self.a[1..];
+            }
+            while self.b.first() == Some(next) {
+                self.b = &self.b[1..];
+            }
+        }
+        Some(next)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.a.len() + self.b.len();
+        if self.dedup {
+            (len.min(1), Some(len))
+        } else {
+            (len, Some(len))
+        }
+    }
+}
+impl<T: Ord, O: SortOrder> std::iter::FusedIterator for MergeIter<'_, T, O> {}
+unsafe impl<T: Ord> SortedIterator for MergeIter<'_, T> {}
+
+/// Whether every element of `a` is matched by a distinct equal element of `b`
+fn is_subset<O: SortOrder, T: Ord>(mut a: &[T], mut b: &[T]) -> bool {
+    if a.len() > b.len() {
+        return false;
+    }
+    while let Some(x) = a.first() {
+        match b.first().map(|y| O::compare(x, y)) {
+            None | Some(Ordering::Less) => return false,
+            Some(Ordering::Greater) => b = &b[1..],
+            Some(Ordering::Equal) => {
+                a = &a[1..];
+                b = &b[1..];
+            }
+        }
+        if a.len() > b.len() {
+            return false;
+        }
+    }
+    true
+}
+
+fn is_disjoint<O: SortOrder, T: Ord>(mut a: &[T], mut b: &[T]) -> bool {
+    while let (Some(x), Some(y)) = (a.first(), b.first()) {
+        match O::compare(x, y) {
+            Ordering::Less => a = &a[1..],
+            Ordering::Greater => b = &b[1..],
+            Ordering::Equal => return false,
+        }
+    }
+    true
+}
+
+/// Single pass `retain` of the elements of `vec` that are matched (when
+/// `matched` is true) or not matched (when false) by a distinct equal element
+/// of `other`
+fn retain_matched<O: SortOrder, T: Ord>(vec: &mut Vec<T>, mut other: &[T], matched: bool) {
+    vec.retain(|x| {
+        while other.first().is_some_and(|y| O::compare(y, x).is_lt()) {
+            other = &other[1..];
+        }
+        let is_match = other.first() == Some(x);
+        if is_match {
+            other = &other[1..];
+        }
+        is_match == matched
+    })
+}
+
+impl<T: Ord, O: SortOrder> SortedVec<T, O> {
+    /// Multiset inclusion: every element occurs in `other` at least as many
+    /// times as in `self`. Single merge pass.
+    pub fn is_subset(&self, other: &SortedVec<T, O>) -> bool {
+        is_subset::<O, T>(self, other)
+    }
+    /// Multiset inclusion: every element occurs in `self` at least as many
+    /// times as in `other`. Single merge pass.
+    pub fn is_superset(&self, other: &SortedVec<T, O>) -> bool {
+        is_subset::<O, T>(other, self)
+    }
+    /// No element occurs in both `self` and `other`. Single merge pass.
+    pub fn is_disjoint(&self, other: &SortedVec<T, O>) -> bool {
+        is_disjoint::<O, T>(self, other)
+    }
+    /// Lazy iterator over the elements of `self` and `other` in sorted order,
+    /// without allocating. Equal elements are yielded from `self` first, or
+    /// only once after calling `MergeIter::dedup`.
+    ///
+    /// ```
+    /// use sorted_vec2::SortedVec;
+    ///
+    /// let a = SortedVec::from_unsorted(vec![1, 3, 3, 5]);
+    /// let b = SortedVec::from_unsorted(vec![2, 3, 6]);
+    /// assert!(a.merge_iter(&b).eq(&[1, 2, 3, 3, 3, 5, 6]));
+    /// assert!(a.merge_iter(&b).dedup().eq(&[1, 2, 3, 5, 6]));
+    /// ```
+    pub fn merge_iter<'a>(&'a self, other: &'a SortedVec<T, O>) -> MergeIter<'a, T, O> {
+        MergeIter {
+            a: self,
+            b: other,
+            dedup: false,
+            order: PhantomData,
+        }
+    }
+    /// Merge clones of all elements of `other` into `self` in O(n + m) with a
+    /// single allocation. Duplicates are kept, so this is equivalent to
+    /// `extend` without inserting each element separately.
+    pub fn union_with(&mut self, other: &SortedVec<T, O>)
+    where
+        T: Clone,
+    {
+        self.union_with_owned_iter(other.iter().cloned())
+    }
+    /// Consuming version of `union_with`.
+    pub fn union_with_owned(&mut self, other: SortedVec<T, O>) {
+        self.union_with_owned_iter(other.vec)
+    }
+    /// Size of the multiset intersection: the number of elements counted as
+    /// many times as they occur in both `self` and `other`. No allocation.
+    pub fn intersection_len(&self, other: &SortedVec<T, O>) -> usize {
+        Intersection::<T, O>::new(self, other).count()
+    }
+    /// Size of the multiset union: the number of elements counted as many
+    /// times as they occur in `self` or in `other`, whichever is more. No
+    /// allocation.
+    pub fn union_len(&self, other: &SortedVec<T, O>) -> usize {
+        self.len() + other.len() - self.intersection_len(other)
+    }
+    /// Multiset intersection in place: each element is kept as many times as
+    /// it occurs in both `self` and `other`. Single merge pass, no allocation.
+    pub fn intersect_in_place(&mut self, other: &SortedVec<T, O>) {
+        retain_matched::<O, T>(&mut self.vec, other, true)
+    }
+    /// Multiset difference in place: each occurrence of an element in `other`
+    /// removes one occurrence from `self`. Single merge pass, no allocation.
+    pub fn difference_in_place(&mut self, other: &SortedVec<T, O>) {
+        retain_matched::<O, T>(&mut self.vec, other, false)
+    }
+    fn union_with_owned_iter<I: IntoIterator<Item = T>>(&mut self, other: I) {
+        let vec = std::mem::take(&mut self.vec);
+        self.vec = crate::merge_vec::<O, _, _, _>(vec, other, |vec, l, r| {
+            vec.push(l);
+            vec.push(r);
+        });
+    }
+}
+
+impl<T: Ord, O: SortOrder> SortedSet<T, O> {
+    /// Every element of `self` is in `other`. Single merge pass.
+    pub fn is_subset(&self, other: &SortedSet<T, O>) -> bool {
+        is_subset::<O, T>(self, other)
+    }
+    /// Every element of `other` is in `self`. Single merge pass.
+    pub fn is_superset(&self, other: &SortedSet<T, O>) -> bool {
+        is_subset::<O, T>(other, self)
+    }
+    /// No element is in both `self` and `other`. Single merge pass.
+    pub fn is_disjoint(&self, other: &SortedSet<T, O>) -> bool {
+        is_disjoint::<O, T>(self, other)
+    }
+    /// Add clones of the elements of `other` that are not in `self`, in
+    /// O(n + m) with a single allocation.
+    pub fn union_with(&mut self, other: &SortedSet<T, O>)
+    where
+        T: Clone,
+    {
+        self.union_with_owned_iter(other.iter().cloned())
+    }
+    /// Consuming version of `union_with`. Elements of `self` are kept over
+    /// equal elements of `other`.
+    pub fn union_with_owned(&mut self, other: SortedSet<T, O>) {
+        self.union_with_owned_iter(other.set.vec)
+    }
+    /// Keep only the elements that are also in `other`. Single merge pass, no
+    /// allocation.
+    pub fn intersect_in_place(&mut self, other: &SortedSet<T, O>) {
+        retain_matched::<O, T>(&mut self.set.vec, other, true)
+    }
+    /// Remove the elements that are in `other`. Single merge pass, no
+    /// allocation.
+    pub fn difference_in_place(&mut self, other: &SortedSet<T, O>) {
+        retain_matched::<O, T>(&mut self.set.vec, other, false)
+    }
+    fn union_with_owned_iter<I: IntoIterator<Item = T>>(&mut self, other: I) {
+        let vec = std::mem::take(&mut self.set.vec);
+        self.set.vec = crate::merge_vec::<O, _, _, _>(vec, other, |vec, l, _| vec.push(l));
+    }
+
+    /// Elements in `self` or `other`, in sorted order without duplicates.
+    ///
+    /// Equal elements are yielded from `self`.
+    pub fn union<'a>(&'a self, other: &'a SortedSet<T, O>) -> Union<'a, T, O> {
+        Union {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+            order: PhantomData,
+        }
+    }
+    /// Elements in both `self` and `other`, in sorted order.
+    ///
+    /// Steps through both sets linearly when they have similar sizes, and
+    /// gallops through the larger set when one is much smaller than the other.
+    /// Elements are yielded from `self`.
+    pub fn intersection<'a>(&'a self, other: &'a SortedSet<T, O>) -> Intersection<'a, T, O> {
+        Intersection::new(self, other)
+    }
+    /// Number of elements in both `self` and `other`, counted without
+    /// allocating.
+    pub fn intersection_len(&self, other: &SortedSet<T, O>) -> usize {
+        self.intersection(other).count()
+    }
+    /// Number of elements in `self` or `other`, counted without allocating.
+    pub fn union_len(&self, other: &SortedSet<T, O>) -> usize {
+        self.len() + other.len() - self.intersection_len(other)
+    }
+    /// Jaccard index `|A ∩ B| / |A ∪ B|`, in `[0, 1]`. Two empty sets are
+    /// considered identical (1.0).
+    pub fn jaccard(&self, other: &SortedSet<T, O>) -> f64 {
+        let intersection = self.intersection_len(other);
+        let union = self.len() + other.len() - intersection;
+        if union == 0 {
+            1.0
+        } else {
+            intersection as f64 / union as f64
+        }
+    }
+    /// Overlap (Szymkiewicz–Simpson) coefficient `|A ∩ B| / min(|A|, |B|)`, in
+    /// `[0, 1]`. It is 1.0 exactly when one set is a subset of the other,
+    /// including when either set is empty.
+    pub fn overlap_coefficient(&self, other: &SortedSet<T, O>) -> f64 {
+        let min = self.len().min(other.len());
+        if min == 0 {
+            1.0
+        } else {
+            self.intersection_len(other) as f64 / min as f64
+        }
+    }
+    /// Elements in `self` that are not in `other`, in sorted order.
+    pub fn difference<'a>(&'a self, other: &'a SortedSet<T, O>) -> Difference<'a, T, O> {
+        Difference {
+            a: self.as_slice(),
+            b: other.as_slice(),
+            order: PhantomData,
+        }
+    }
+    /// Elements in `self` or in `other` but not in both, in sorted order.
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a SortedSet<T, O>,
+    ) -> SymmetricDifference<'a, T, O> {
+        SymmetricDifference {
+            a: self.as_slice(),
+            b: other.as_slice(),
+            order: PhantomData,
+        }
+    }
+}
+
+macro_rules! impl_set_op {
+    ($op:ident, $fn:ident, $method:ident, $doc:literal) => {
+        impl<T: Ord + Clone, O: SortOrder> std::ops::$op<&SortedSet<T, O>> for &SortedSet<T, O> {
+            type Output = SortedSet<T, O>;
+            #[doc = $doc]
+            fn $fn(self, rhs: &SortedSet<T, O>) -> SortedSet<T, O> {
+                SortedSet {
+                    set: SortedVec {
+                        vec: self.$method(rhs).cloned().collect(),
+                        order: PhantomData,
+                    },
+                }
+            }
+        }
+    };
+}
+impl_set_op!(BitOr, bitor, union, "Returns the union of `self` and `rhs`");
+impl_set_op!(
+    BitAnd,
+    bitand,
+    intersection,
+    "Returns the intersection of `self` and `rhs`"
+);
+impl_set_op!(
+    BitXor,
+    bitxor,
+    symmetric_difference,
+    "Returns the symmetric difference of `self` and `rhs`"
+);
+impl_set_op!(
+    Sub,
+    sub,
+    difference,
+    "Returns the difference of `self` and `rhs`"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union() {
+        let a = SortedSet::from_unsorted(vec![1, 3, 5, 7]);
+        let b = SortedSet::from_unsorted(vec![2, 3, 4, 7, 9]);
+        let e = SortedSet::new();
+        assert_eq!(
+            a.union(&b).copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 7, 9]
+        );
+        assert_eq!(b.union(&a).size_hint(), (5, Some(9)));
+        assert!(a.union(&e).eq(a.iter()));
+        assert!(e.union(&b).eq(b.iter()));
+        assert_eq!(
+            SortedSet::from_sorted_iter(a.union(&b).cloned()),
+            SortedSet::from_unsorted(vec![9, 7, 5, 4, 3, 2, 1])
+        );
+    }
+
+    #[test]
+    fn test_merge_iter() {
+        let a = SortedVec::from_unsorted(vec![(1, 'a'), (4, 'a')]);
+        let b = SortedVec::from_unsorted(vec![(0, 'b'), (1, 'a'), (4, 'b')]);
+        assert_eq!(
+            a.merge_iter(&b).map(|x| x.0).collect::<Vec<_>>(),
+            vec![0, 1, 1, 4, 4]
+        );
+        assert_eq!(a.merge_iter(&b).size_hint(), (5, Some(5)));
+        assert_eq!(a.merge_iter(&b).dedup().count(), 4);
+        let v = SortedVec::from_unsorted(vec![2, 2, 2]);
+        let e = SortedVec::new();
+        assert!(v.merge_iter(&e).dedup().eq(&[2]));
+        assert!(e.merge_iter(&v).eq(v.iter()));
+        assert_eq!(e.merge_iter(&e).dedup().size_hint(), (0, Some(0)));
+        let w = SortedVec::from_sorted_iter(v.merge_iter(&v).cloned());
+        assert_eq!(w.len(), 6);
+    }
+
+    #[test]
+    fn test_gallop() {
+        let v = [1, 2, 4, 8, 16, 32, 64];
+        for x in 0..70 {
+            assert_eq!(
+                gallop::<Ascending, _>(&v, &x),
+                v.partition_point(|y| *y < x)
+            );
+        }
+        assert_eq!(gallop::<Ascending, _>(&[] as &[i32], &0), 0);
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = SortedSet::from_unsorted(vec![1, 3, 5, 7]);
+        let b = SortedSet::from_unsorted(vec![2, 3, 4, 7, 9]);
+        assert_eq!(a.intersection(&b).copied().collect::<Vec<_>>(), vec![3, 7]);
+        assert_eq!(b.intersection(&SortedSet::new()).next(), None);
+        let large = SortedSet::from_unsorted((0..1000).collect());
+        let small = SortedSet::from_unsorted(vec![-1, 5, 500, 999, 1000]);
+        assert!(small.intersection(&large).gallop);
+        assert_eq!(
+            small.intersection(&large).copied().collect::<Vec<_>>(),
+            vec![5, 500, 999]
+        );
+        assert!(large.intersection(&small).eq(small.intersection(&large)));
+    }
+
+    #[test]
+    fn test_difference() {
+        let a = SortedSet::from_unsorted(vec![1, 3, 5, 7]);
+        let b = SortedSet::from_unsorted(vec![2, 3, 4, 7, 9]);
+        assert_eq!(a.difference(&b).copied().collect::<Vec<_>>(), vec![1, 5]);
+        assert_eq!(b.difference(&a).copied().collect::<Vec<_>>(), vec![2, 4, 9]);
+        assert!(a.difference(&SortedSet::new()).eq(a.iter()));
+        assert_eq!(a.difference(&a).next(), None);
+        assert_eq!(b.difference(&a).size_hint(), (1, Some(5)));
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let a = SortedSet::from_unsorted(vec![1, 3, 5, 7]);
+        let b = SortedSet::from_unsorted(vec![2, 3, 4, 7, 9]);
+        assert_eq!(
+            a.symmetric_difference(&b).copied().collect::<Vec<_>>(),
+            vec![1, 2, 4, 5, 9]
+        );
+        assert!(a.symmetric_difference(&b).eq(b.symmetric_difference(&a)));
+        assert!(a.symmetric_difference(&SortedSet::new()).eq(a.iter()));
+        assert_eq!(a.symmetric_difference(&a).next(), None);
+    }
+
+    #[test]
+    fn test_predicates() {
+        let a = SortedSet::from_unsorted(vec![3, 7]);
+        let b = SortedSet::from_unsorted(vec![2, 3, 4, 7, 9]);
+        let c = SortedSet::from_unsorted(vec![1, 5]);
+        let e = SortedSet::new();
+        assert!(a.is_subset(&b) && b.is_superset(&a));
+        assert!(!b.is_subset(&a) && !a.is_superset(&b));
+        assert!(!a.is_subset(&c));
+        assert!(e.is_subset(&a) && a.is_subset(&a));
+        assert!(a.is_disjoint(&c) && e.is_disjoint(&e));
+        assert!(!a.is_disjoint(&b));
+        let v = SortedVec::from_unsorted(vec![1, 1, 2]);
+        let w = SortedVec::from_unsorted(vec![1, 2, 2, 3]);
+        let x = SortedVec::from_unsorted(vec![0, 1, 1, 2, 2]);
+        assert!(!v.is_subset(&w));
+        assert!(v.is_subset(&x) && !x.is_superset(&w));
+        assert!(x.is_superset(&v));
+        assert!(!v.is_disjoint(&w));
+        assert!(v.is_disjoint(&SortedVec::from_unsorted(vec![0, 3])));
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = SortedSet::from_unsorted(vec![1, 3, 5, 7]);
+        let b = SortedSet::from_unsorted(vec![2, 3, 4, 7, 9]);
+        assert_eq!(**(&a | &b), vec![1, 2, 3, 4, 5, 7, 9]);
+        assert_eq!(**(&a & &b), vec![3, 7]);
+        assert_eq!(**(&a ^ &b), vec![1, 2, 4, 5, 9]);
+        assert_eq!(**(&a - &b), vec![1, 5]);
+    }
+
+    #[test]
+    fn test_union_with() {
+        let mut a = SortedSet::from_unsorted(vec![1, 3, 5, 7]);
+        let b = SortedSet::from_unsorted(vec![2, 3, 4, 7, 9]);
+        a.union_with(&b);
+        assert_eq!(**a, vec![1, 2, 3, 4, 5, 7, 9]);
+        a.union_with_owned(SortedSet::from_unsorted(vec![0, 9, 10]));
+        assert_eq!(**a, vec![0, 1, 2, 3, 4, 5, 7, 9, 10]);
+        let mut v = SortedVec::from_unsorted(vec![1, 1, 4]);
+        v.union_with(&SortedVec::from_unsorted(vec![1, 2, 5]));
+        assert_eq!(*v, vec![1, 1, 1, 2, 4, 5]);
+        v.union_with_owned(SortedVec::new());
+        assert_eq!(v.len(), 6);
+    }
+
+    #[test]
+    fn test_in_place() {
+        let b = SortedSet::from_unsorted(vec![2, 3, 4, 7, 9]);
+        let mut a = SortedSet::from_unsorted(vec![1, 3, 5, 7]);
+        a.intersect_in_place(&b);
+        assert_eq!(**a, vec![3, 7]);
+        let mut a = SortedSet::from_unsorted(vec![1, 3, 5, 7]);
+        a.difference_in_place(&b);
+        assert_eq!(**a, vec![1, 5]);
+        let w = SortedVec::from_unsorted(vec![1, 2, 2, 3]);
+        let mut v = SortedVec::from_unsorted(vec![1, 1, 2, 2, 2, 4]);
+        v.intersect_in_place(&w);
+        assert_eq!(*v, vec![1, 2, 2]);
+        let mut v = SortedVec::from_unsorted(vec![1, 1, 2, 2, 2, 4]);
+        v.difference_in_place(&w);
+        assert_eq!(*v, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_overlap_len() {
+        let a = SortedSet::from_unsorted(vec![1, 3, 5, 7]);
+        let b = SortedSet::from_unsorted(vec![2, 3, 4, 7, 9]);
+        assert_eq!(a.intersection_len(&b), 2);
+        assert_eq!(a.union_len(&b), 7);
+        assert_eq!(a.union_len(&SortedSet::new()), 4);
+        let v = SortedVec::from_unsorted(vec![1, 1, 2, 2, 2, 4]);
+        let w = SortedVec::from_unsorted(vec![1, 2, 2, 3]);
+        assert_eq!(v.intersection_len(&w), 3);
+        assert_eq!(v.union_len(&w), 7);
+    }
+
+    #[test]
+    fn test_similarity() {
+        let a = SortedSet::from_unsorted(vec![1, 3, 5, 7]);
+        let b = SortedSet::from_unsorted(vec![2, 3, 4, 7, 9]);
+        let e = SortedSet::new();
+        assert_eq!(a.jaccard(&b), 2.0 / 7.0);
+        assert_eq!(a.jaccard(&a), 1.0);
+        assert_eq!(a.jaccard(&e), 0.0);
+        assert_eq!(e.jaccard(&e), 1.0);
+        assert_eq!(a.overlap_coefficient(&b), 0.5);
+        assert_eq!(a.overlap_coefficient(&e), 1.0);
+        let c = SortedSet::from_unsorted(vec![3, 7]);
+        assert_eq!(b.overlap_coefficient(&c), 1.0);
+    }
+
+    #[test]
+    fn test_descending_set_ops() {
+        let a: crate::ReverseSortedSet<i32> = vec![1, 2, 3, 4, 8].into();
+        let b: crate::ReverseSortedSet<i32> = vec![3, 4, 5].into();
+        let ops = |i: &mut dyn Iterator<Item = &i32>| i.copied().collect::<Vec<_>>();
+        assert_eq!(ops(&mut a.union(&b)), vec![8, 5, 4, 3, 2, 1]);
+        assert_eq!(ops(&mut a.intersection(&b)), vec![4, 3]);
+        assert_eq!(ops(&mut a.difference(&b)), vec![8, 2, 1]);
+        assert_eq!(ops(&mut a.symmetric_difference(&b)), vec![8, 5, 2, 1]);
+        assert_eq!(**(&a & &b), vec![4, 3]);
+        assert!(!b.is_subset(&a) && a.is_superset(&(&a & &b)));
+        let mut v: crate::ReverseSortedVec<i32> = vec![1, 3].into();
+        v.union_with_owned(vec![4, 2, 0].into());
+        assert_eq!(*v, vec![4, 3, 2, 1, 0]);
+    }
+}
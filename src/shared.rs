@@ -0,0 +1,146 @@
+//! Read-optimized shared sorted vector (feature `arc-swap`).
+//!
+//! `SharedSortedVec<T>` holds the current contents as an immutable
+//! `Arc<SortedVec<T>>` in an `ArcSwap`. Readers take a snapshot with `load`
+//! without locking and keep it for as long as they like, while writers apply
+//! a batch of changes to a copy and then swap the pointer, so readers never
+//! observe a partially applied batch. Writers are serialized with each other.
+//!
+//! ```
+//! use sorted_vec2::shared::SharedSortedVec;
+//!
+//! let index = SharedSortedVec::from_unsorted(vec![30, 10]);
+//! let before = index.load();
+//! index.update(|v| {
+//!     v.insert(20);
+//!     v.remove_item(&30);
+//! });
+//! assert_eq!(**before, vec![10, 30]);
+//! assert_eq!(**index.load(), vec![10, 20]);
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use ::arc_swap::ArcSwap;
+
+use crate::SortedVec;
+
+/// Sorted vector shared between lock-free readers and batched writers
+pub struct SharedSortedVec<T: Ord> {
+    current: ArcSwap<SortedVec<T>>,
+    writer: Mutex<()>,
+}
+
+impl<T: Ord> SharedSortedVec<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::from(SortedVec::new())
+    }
+    /// Uses `SortedVec::from_unsorted()` to sort the initial contents.
+    #[inline]
+    pub fn from_unsorted(vec: Vec<T>) -> Self {
+        Self::from(SortedVec::from_unsorted(vec))
+    }
+    /// Snapshot of the current contents, unaffected by later updates.
+    #[inline]
+    pub fn load(&self) -> Arc<SortedVec<T>> {
+        self.current.load_full()
+    }
+    /// Replace the contents, returning the previous snapshot.
+    pub fn store(&self, sorted: SortedVec<T>) -> Arc<SortedVec<T>> {
+        let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        self.current.swap(Arc::new(sorted))
+    }
+    /// Apply `f` to a copy of the current contents and publish the result.
+    /// Readers see either all or none of the changes made by `f`. If `f`
+    /// panics the contents are left unchanged.
+    pub fn update<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut SortedVec<T>) -> R,
+        T: Clone,
+    {
+        let _writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let mut next = SortedVec::clone(&self.current.load_full());
+        let result = f(&mut next);
+        self.current.store(Arc::new(next));
+        result
+    }
+    /// Insert a batch of elements with a single copy and swap.
+    pub fn insert_batch<I>(&self, elements: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: Clone,
+    {
+        let mut batch: Vec<T> = elements.into_iter().collect();
+        if batch.is_empty() {
+            return;
+        }
+        batch.sort_unstable();
+        self.update(|v| {
+            let mut merged = Vec::with_capacity(v.len() + batch.len());
+            let mut old = std::mem::take(v).into_vec().into_iter().peekable();
+            for x in batch {
+                while let Some(y) = old.next_if(|y| *y <= x) {
+                    merged.push(y);
+                }
+                merged.push(x);
+            }
+            merged.extend(old);
+            *v = unsafe { SortedVec::from_unsorted_unchecked(merged) };
+        })
+    }
+}
+
+impl<T: Ord> Default for SharedSortedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Ord> From<SortedVec<T>> for SharedSortedVec<T> {
+    fn from(sorted: SortedVec<T>) -> Self {
+        SharedSortedVec {
+            current: ArcSwap::from_pointee(sorted),
+            writer: Mutex::new(()),
+        }
+    }
+}
+impl<T: Ord + std::fmt::Debug> std::fmt::Debug for SharedSortedVec<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("SharedSortedVec")
+            .field(&self.load())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared() {
+        let shared = Arc::new(SharedSortedVec::from_unsorted(vec![5, 1]));
+        let snapshot = shared.load();
+        let writers: Vec<_> = (0..4)
+            .map(|t| {
+                let shared = Arc::clone(&shared);
+                std::thread::spawn(move || {
+                    for i in 0..25 {
+                        shared.insert_batch([t * 100 + i, t * 100 + i]);
+                        assert!(shared.load().windows(2).all(|w| w[0] <= w[1]));
+                    }
+                })
+            })
+            .collect();
+        for w in writers {
+            w.join().unwrap();
+        }
+        assert_eq!(**snapshot, vec![1, 5]);
+        let v = shared.load();
+        assert_eq!(v.len(), 202);
+        assert_eq!(v.count(&5), 3);
+        assert_eq!(shared.update(|v| v.remove_item(&1)), Some(1));
+        let previous = shared.store(SortedVec::new());
+        assert_eq!(previous.len(), 201);
+        assert!(shared.load().is_empty());
+    }
+}
@@ -0,0 +1,466 @@
+//! SmallVec-backed sorted containers (feature `smallvec`).
+//!
+//! `SortedSmallVec<A>` and `SortedSmallSet<A>` are ascending containers that
+//! store up to `A::size()` elements inline and only allocate once they grow
+//! past that, saving a heap allocation per collection when most of them are
+//! small. They provide the same insertion and search methods as `SortedVec`
+//! and `SortedSet`.
+//!
+//! ```
+//! use sorted_vec2::small::SortedSmallSet;
+//!
+//! let mut set = SortedSmallSet::<[u32; 4]>::new();
+//! set.replace(7);
+//! set.replace(3);
+//! set.replace(7);
+//! assert_eq!(set[..], [3, 7]);
+//! assert!(!set.spilled());
+//! ```
+
+use std::borrow::Borrow;
+
+use ::smallvec::{Array, SmallVec};
+
+use crate::storage::{grow, Storage};
+use crate::FindOrInsert;
+
+/// Vector sorted in ascending order, storing up to `A::size()` elements
+/// inline, may contain duplicates
+pub struct SortedSmallVec<A: Array>
+where
+    A::Item: Ord,
+{
+    vec: SmallVec<A>,
+}
+
+/// Set sorted in ascending order, storing up to `A::size()` elements inline
+pub struct SortedSmallSet<A: Array>
+where
+    A::Item: Ord,
+{
+    set: SortedSmallVec<A>,
+}
+
+// derives would bound `A` instead of the element type
+macro_rules! impl_traits {
+    ($($container:ident { $field:ident }),*) => {
+        $(
+            impl<A: Array> Clone for $container<A>
+            where
+                A::Item: Ord + Clone,
+            {
+                fn clone(&self) -> Self {
+                    $container {
+                        $field: self.$field.clone(),
+                    }
+                }
+            }
+            impl<A: Array> std::fmt::Debug for $container<A>
+            where
+                A::Item: Ord + std::fmt::Debug,
+            {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.debug_struct(stringify!($container))
+                        .field(stringify!($field), &self.$field)
+                        .finish()
+                }
+            }
+            impl<A: Array> PartialEq for $container<A>
+            where
+                A::Item: Ord,
+            {
+                fn eq(&self, other: &Self) -> bool {
+                    self.$field == other.$field
+                }
+            }
+            impl<A: Array> Eq for $container<A> where A::Item: Ord {}
+            impl<A: Array> std::hash::Hash for $container<A>
+            where
+                A::Item: Ord + std::hash::Hash,
+            {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    self.$field.hash(state)
+                }
+            }
+        )*
+    };
+}
+
+impl_traits!(SortedSmallVec { vec }, SortedSmallSet { set });
+
+//
+//  impl SortedSmallVec
+//
+
+impl<A: Array> SortedSmallVec<A>
+where
+    A::Item: Ord,
+{
+    #[inline]
+    pub fn new() -> Self {
+        SortedSmallVec {
+            vec: SmallVec::new(),
+        }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedSmallVec {
+            vec: SmallVec::with_capacity(capacity),
+        }
+    }
+    /// Uses `sort_unstable()` to sort in place.
+    #[inline]
+    pub fn from_unsorted(mut vec: SmallVec<A>) -> Self {
+        vec.sort_unstable();
+        SortedSmallVec { vec }
+    }
+    /// # Safety
+    ///
+    /// The caller must ensure that the provided vector is already sorted.
+    #[inline]
+    pub unsafe fn from_unsorted_unchecked(vec: SmallVec<A>) -> Self {
+        SortedSmallVec { vec }
+    }
+    /// Insert an element into sorted position, returning the order index at which
+    /// it was placed.
+    #[inline]
+    pub fn insert(&mut self, element: A::Item) -> usize {
+        grow(self.vec.insert_sorted(element))
+    }
+    /// Find the element and return the index with `Ok`, otherwise insert the
+    /// element and return the new element index with `Err`.
+    #[inline]
+    pub fn find_or_insert(&mut self, element: A::Item) -> FindOrInsert {
+        grow(self.vec.find_or_insert_sorted(element))
+    }
+    /// Same as insert, except performance is O(1) when the element belongs at the
+    /// back of the container.
+    #[inline]
+    pub fn push(&mut self, element: A::Item) -> usize {
+        grow(self.vec.push_sorted(element))
+    }
+    /// Reserves additional capacity, moving the elements to the heap if they
+    /// no longer fit inline. See smallvec::SmallVec::reserve.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional);
+    }
+    /// Binary search for `x`, which may be any borrowed form of the element
+    /// type. See `slice::binary_search`.
+    #[inline]
+    pub fn binary_search<Q>(&self, x: &Q) -> Result<usize, usize>
+    where
+        A::Item: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.search(x)
+    }
+    /// Binary search for `x`, shadowing the linear `slice::contains` reached
+    /// through `Deref`. O(log n).
+    #[inline]
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        A::Item: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(x).is_ok()
+    }
+    #[inline]
+    pub fn remove_item<Q>(&mut self, item: &Q) -> Option<A::Item>
+    where
+        A::Item: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.remove_sorted(item)
+    }
+    /// Panics if index is out of bounds
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> A::Item {
+        self.vec.remove(index)
+    }
+    #[inline]
+    pub fn pop(&mut self) -> Option<A::Item> {
+        self.vec.pop()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    #[inline]
+    pub fn dedup(&mut self) {
+        self.vec.dedup();
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&A::Item) -> bool,
+    {
+        self.vec.retain(|x| f(x))
+    }
+    /// Whether the elements have been moved to the heap
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        self.vec.spilled()
+    }
+    /// The sorted elements as a `SmallVec`
+    #[inline]
+    pub fn into_inner(self) -> SmallVec<A> {
+        self.vec
+    }
+    /// The sorted elements as a `Vec`, reusing the heap allocation if the
+    /// elements have spilled
+    #[inline]
+    pub fn into_vec(self) -> Vec<A::Item> {
+        self.vec.into_vec()
+    }
+}
+
+impl<A: Array> Default for SortedSmallVec<A>
+where
+    A::Item: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<A: Array> std::ops::Deref for SortedSmallVec<A>
+where
+    A::Item: Ord,
+{
+    type Target = SmallVec<A>;
+    fn deref(&self) -> &SmallVec<A> {
+        &self.vec
+    }
+}
+impl<A: Array> Extend<A::Item> for SortedSmallVec<A>
+where
+    A::Item: Ord,
+{
+    fn extend<I: IntoIterator<Item = A::Item>>(&mut self, iter: I) {
+        for t in iter {
+            let _ = self.insert(t);
+        }
+    }
+}
+impl<A: Array> FromIterator<A::Item> for SortedSmallVec<A>
+where
+    A::Item: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = A::Item>>(iter: I) -> Self {
+        Self::from_unsorted(iter.into_iter().collect())
+    }
+}
+impl<A: Array> From<SmallVec<A>> for SortedSmallVec<A>
+where
+    A::Item: Ord,
+{
+    fn from(unsorted: SmallVec<A>) -> Self {
+        Self::from_unsorted(unsorted)
+    }
+}
+impl<A: Array> IntoIterator for SortedSmallVec<A>
+where
+    A::Item: Ord,
+{
+    type Item = A::Item;
+    type IntoIter = ::smallvec::IntoIter<A>;
+    fn into_iter(self) -> ::smallvec::IntoIter<A> {
+        self.vec.into_iter()
+    }
+}
+impl<'a, A: Array> IntoIterator for &'a SortedSmallVec<A>
+where
+    A::Item: Ord,
+{
+    type Item = &'a A::Item;
+    type IntoIter = std::slice::Iter<'a, A::Item>;
+    fn into_iter(self) -> std::slice::Iter<'a, A::Item> {
+        self.vec.iter()
+    }
+}
+
+//
+//  impl SortedSmallSet
+//
+
+impl<A: Array> SortedSmallSet<A>
+where
+    A::Item: Ord,
+{
+    #[inline]
+    pub fn new() -> Self {
+        SortedSmallSet {
+            set: SortedSmallVec::new(),
+        }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedSmallSet {
+            set: SortedSmallVec::with_capacity(capacity),
+        }
+    }
+    /// Uses `sort_unstable()` to sort in place and `dedup()` to remove
+    /// duplicates.
+    #[inline]
+    pub fn from_unsorted(vec: SmallVec<A>) -> Self {
+        let mut set = SortedSmallVec::from_unsorted(vec);
+        set.dedup();
+        SortedSmallSet { set }
+    }
+    /// # Safety
+    ///
+    /// The caller must ensure that the provided vector is already sorted and
+    /// deduplicated.
+    #[inline]
+    pub unsafe fn from_unsorted_unchecked(vec: SmallVec<A>) -> Self {
+        SortedSmallSet {
+            set: SortedSmallVec::from_unsorted_unchecked(vec),
+        }
+    }
+    /// Insert an element into sorted position, returning the order index at which
+    /// it was placed. If an existing item was found it will be returned.
+    #[inline]
+    pub fn replace(&mut self, element: A::Item) -> (usize, Option<A::Item>) {
+        grow(self.set.vec.replace_sorted(element))
+    }
+    /// Find the element and return the index with `Ok`, otherwise insert the
+    /// element and return the new element index with `Err`.
+    #[inline]
+    pub fn find_or_insert(&mut self, element: A::Item) -> FindOrInsert {
+        self.set.find_or_insert(element)
+    }
+    /// Reserves additional capacity, moving the elements to the heap if they
+    /// no longer fit inline. See smallvec::SmallVec::reserve.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.set.reserve(additional);
+    }
+    #[inline]
+    pub fn remove_item<Q>(&mut self, item: &Q) -> Option<A::Item>
+    where
+        A::Item: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.set.remove_item(item)
+    }
+    /// Panics if index is out of bounds
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> A::Item {
+        self.set.remove_index(index)
+    }
+    #[inline]
+    pub fn pop(&mut self) -> Option<A::Item> {
+        self.set.pop()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.set.clear()
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&A::Item) -> bool,
+    {
+        self.set.retain(f)
+    }
+    /// The sorted elements as a `SmallVec`
+    #[inline]
+    pub fn into_inner(self) -> SmallVec<A> {
+        self.set.into_inner()
+    }
+    /// The sorted elements as a `Vec`, reusing the heap allocation if the
+    /// elements have spilled
+    #[inline]
+    pub fn into_vec(self) -> Vec<A::Item> {
+        self.set.into_vec()
+    }
+}
+
+impl<A: Array> Default for SortedSmallSet<A>
+where
+    A::Item: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<A: Array> std::ops::Deref for SortedSmallSet<A>
+where
+    A::Item: Ord,
+{
+    type Target = SortedSmallVec<A>;
+    fn deref(&self) -> &SortedSmallVec<A> {
+        &self.set
+    }
+}
+impl<A: Array> Extend<A::Item> for SortedSmallSet<A>
+where
+    A::Item: Ord,
+{
+    fn extend<I: IntoIterator<Item = A::Item>>(&mut self, iter: I) {
+        for t in iter {
+            let _ = self.replace(t);
+        }
+    }
+}
+impl<A: Array> FromIterator<A::Item> for SortedSmallSet<A>
+where
+    A::Item: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = A::Item>>(iter: I) -> Self {
+        Self::from_unsorted(iter.into_iter().collect())
+    }
+}
+impl<A: Array> From<SmallVec<A>> for SortedSmallSet<A>
+where
+    A::Item: Ord,
+{
+    fn from(unsorted: SmallVec<A>) -> Self {
+        Self::from_unsorted(unsorted)
+    }
+}
+impl<A: Array> IntoIterator for SortedSmallSet<A>
+where
+    A::Item: Ord,
+{
+    type Item = A::Item;
+    type IntoIter = ::smallvec::IntoIter<A>;
+    fn into_iter(self) -> ::smallvec::IntoIter<A> {
+        self.set.into_iter()
+    }
+}
+impl<'a, A: Array> IntoIterator for &'a SortedSmallSet<A>
+where
+    A::Item: Ord,
+{
+    type Item = &'a A::Item;
+    type IntoIter = std::slice::Iter<'a, A::Item>;
+    fn into_iter(self) -> std::slice::Iter<'a, A::Item> {
+        self.set.vec.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small() {
+        let mut v: SortedSmallVec<[i32; 4]> = [5, 1, 3].into_iter().collect();
+        assert_eq!(v.push(7), 3);
+        assert_eq!(v.find_or_insert(3), FindOrInsert::Found(1));
+        assert_eq!(**v, [1, 3, 5, 7]);
+        assert!(!v.spilled());
+        assert_eq!(v.insert(2), 1);
+        assert!(v.spilled());
+        assert_eq!(v.remove_item(&5), Some(5));
+        v.retain(|x| *x != 1);
+        assert_eq!(v.into_vec(), vec![2, 3, 7]);
+        let mut s: SortedSmallSet<[i32; 4]> = [3, 1, 3].into_iter().collect();
+        assert_eq!(s.replace(1), (0, Some(1)));
+        assert_eq!(s.replace(2), (1, None));
+        assert_eq!(***s, [1, 2, 3]);
+        assert!(s.contains(&2));
+        assert_eq!(s.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+}
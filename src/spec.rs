@@ -0,0 +1,78 @@
+//! Specialized `FromIterator`/`Extend` implementations (feature `unstable`).
+//!
+//! - `SortedIterator`s are collected without sorting (and `SortedSet` skips
+//!   deduplication for `UniqueSortedIterator`s)
+//! - `Extend` merges the elements of a `SortedIterator` without sorting them
+//!   first
+//!
+//! `SortedIterator`s yield ascending elements, so only the `Ascending`
+//! containers are specialized; other orders always sort.
+
+use crate::{Ascending, SortOrder, SortedIterator, SortedSet, SortedVec, UniqueSortedIterator};
+
+pub(crate) trait SpecFromIter<I> {
+    fn spec_from_iter(iter: I) -> Self;
+}
+
+pub(crate) trait SpecExtend<I> {
+    fn spec_extend(&mut self, iter: I);
+}
+
+//
+//  SortedVec
+//
+
+impl<T: Ord, O: SortOrder, I: Iterator<Item = T>> SpecFromIter<I> for SortedVec<T, O> {
+    default fn spec_from_iter(iter: I) -> Self {
+        SortedVec::from(iter.collect::<Vec<T>>())
+    }
+}
+impl<T: Ord, I: SortedIterator<Item = T>> SpecFromIter<I> for SortedVec<T, Ascending> {
+    fn spec_from_iter(iter: I) -> Self {
+        SortedVec::from_sorted_iter(iter)
+    }
+}
+
+impl<T: Ord, O: SortOrder, I: Iterator<Item = T>> SpecExtend<I> for SortedVec<T, O> {
+    default fn spec_extend(&mut self, iter: I) {
+        self.merge_unsorted_batch(iter.collect())
+    }
+}
+impl<T: Ord, I: SortedIterator<Item = T>> SpecExtend<I> for SortedVec<T, Ascending> {
+    fn spec_extend(&mut self, iter: I) {
+        self.merge_sorted_batch(iter.collect(), false)
+    }
+}
+
+//
+//  SortedSet
+//
+
+impl<T: Ord, O: SortOrder, I: Iterator<Item = T>> SpecFromIter<I> for SortedSet<T, O> {
+    default fn spec_from_iter(iter: I) -> Self {
+        SortedSet::from(iter.collect::<Vec<T>>())
+    }
+}
+impl<T: Ord, I: SortedIterator<Item = T>> SpecFromIter<I> for SortedSet<T, Ascending> {
+    default fn spec_from_iter(iter: I) -> Self {
+        let mut set = SortedVec::from_sorted_iter(iter);
+        set.dedup();
+        SortedSet { set }
+    }
+}
+impl<T: Ord, I: UniqueSortedIterator<Item = T>> SpecFromIter<I> for SortedSet<T, Ascending> {
+    fn spec_from_iter(iter: I) -> Self {
+        SortedSet::from_sorted_iter(iter)
+    }
+}
+
+impl<T: Ord, O: SortOrder, I: Iterator<Item = T>> SpecExtend<I> for SortedSet<T, O> {
+    default fn spec_extend(&mut self, iter: I) {
+        self.merge_unsorted_batch(iter.collect())
+    }
+}
+impl<T: Ord, I: SortedIterator<Item = T>> SpecExtend<I> for SortedSet<T, Ascending> {
+    fn spec_extend(&mut self, iter: I) {
+        self.merge_sorted_batch(iter.collect())
+    }
+}
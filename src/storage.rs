@@ -0,0 +1,266 @@
+//! Sorted insertion, search and removal shared by the alternative backends.
+//!
+//! `Storage` abstracts over the handful of operations the algorithms need from
+//! an ordered buffer, so `array`, `deque`, `heapless`, `critical`, `small` and
+//! `tiny` only describe how to insert into and remove from their storage.
+//! Fixed capacity storage reports a full buffer by returning the element with
+//! `Err`; growable storage never does, see `grow`.
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+
+use crate::FindOrInsert;
+
+// which helpers are used depends on the enabled backend features
+#[allow(dead_code)]
+pub(crate) trait Storage {
+    type Item: Ord;
+
+    fn len(&self) -> usize;
+    fn last(&self) -> Option<&Self::Item>;
+    fn get_mut(&mut self, index: usize) -> &mut Self::Item;
+    fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&Self::Item) -> Ordering;
+    /// Returns the element with `Err` if the storage is full
+    fn insert(&mut self, index: usize, element: Self::Item) -> Result<(), Self::Item>;
+    /// Returns the element with `Err` if the storage is full
+    fn push(&mut self, element: Self::Item) -> Result<(), Self::Item>;
+    /// Panics if index is out of bounds
+    fn remove(&mut self, index: usize) -> Self::Item;
+
+    /// Binary search for `x`, which may be any borrowed form of the element
+    /// type. See `slice::binary_search`.
+    #[inline]
+    fn search<Q>(&self, x: &Q) -> Result<usize, usize>
+    where
+        Self::Item: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search_by(|y| y.borrow().cmp(x))
+    }
+    /// Insert an element into sorted position, returning the order index at
+    /// which it was placed.
+    fn insert_sorted(&mut self, element: Self::Item) -> Result<usize, Self::Item> {
+        let insert_at = match self.search(&element) {
+            Ok(insert_at) | Err(insert_at) => insert_at,
+        };
+        self.insert(insert_at, element)?;
+        Ok(insert_at)
+    }
+    /// Same as `insert_sorted`, except performance is O(1) when the element
+    /// belongs at the back.
+    fn push_sorted(&mut self, element: Self::Item) -> Result<usize, Self::Item> {
+        match self.last() {
+            Some(last) if element < *last => self.insert_sorted(element),
+            _ => {
+                self.push(element)?;
+                Ok(self.len() - 1)
+            }
+        }
+    }
+    fn find_or_insert_sorted(&mut self, element: Self::Item) -> Result<FindOrInsert, Self::Item> {
+        match self.search(&element) {
+            Ok(found_at) => Ok(FindOrInsert::Found(found_at)),
+            Err(insert_at) => {
+                self.insert(insert_at, element)?;
+                Ok(FindOrInsert::Inserted(insert_at))
+            }
+        }
+    }
+    /// Insert an element into sorted position, returning the order index at
+    /// which it was placed and the equal element it replaced, if any.
+    fn replace_sorted(
+        &mut self,
+        element: Self::Item,
+    ) -> Result<(usize, Option<Self::Item>), Self::Item> {
+        match self.search(&element) {
+            Ok(existing_index) => {
+                let existing = core::mem::replace(self.get_mut(existing_index), element);
+                Ok((existing_index, Some(existing)))
+            }
+            Err(insert_index) => {
+                self.insert(insert_index, element)?;
+                Ok((insert_index, None))
+            }
+        }
+    }
+    fn remove_sorted<Q>(&mut self, item: &Q) -> Option<Self::Item>
+    where
+        Self::Item: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(item) {
+            Ok(remove_at) => Some(self.remove(remove_at)),
+            Err(_) => None,
+        }
+    }
+}
+
+/// Unwraps the result of an operation on growable storage, which is never full
+#[inline]
+pub(crate) fn grow<R, T>(result: Result<R, T>) -> R {
+    match result {
+        Ok(r) => r,
+        Err(_) => unreachable!("growable storage is never full"),
+    }
+}
+
+impl<T: Ord> Storage for std::collections::VecDeque<T> {
+    type Item = T;
+
+    #[inline]
+    fn len(&self) -> usize {
+        std::collections::VecDeque::len(self)
+    }
+    #[inline]
+    fn last(&self) -> Option<&T> {
+        self.back()
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> &mut T {
+        &mut self[index]
+    }
+    #[inline]
+    fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        std::collections::VecDeque::binary_search_by(self, f)
+    }
+    #[inline]
+    fn insert(&mut self, index: usize, element: T) -> Result<(), T> {
+        std::collections::VecDeque::insert(self, index, element);
+        Ok(())
+    }
+    #[inline]
+    fn push(&mut self, element: T) -> Result<(), T> {
+        self.push_back(element);
+        Ok(())
+    }
+    #[inline]
+    fn remove(&mut self, index: usize) -> T {
+        std::collections::VecDeque::remove(self, index).expect("index out of bounds")
+    }
+}
+
+#[cfg(any(feature = "heapless", feature = "critical-section"))]
+impl<T: Ord, const N: usize> Storage for ::heapless::Vec<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+    #[inline]
+    fn last(&self) -> Option<&T> {
+        self.as_slice().last()
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> &mut T {
+        &mut self[index]
+    }
+    #[inline]
+    fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+    #[inline]
+    fn insert(&mut self, index: usize, element: T) -> Result<(), T> {
+        ::heapless::Vec::insert(self, index, element)
+    }
+    #[inline]
+    fn push(&mut self, element: T) -> Result<(), T> {
+        ::heapless::Vec::push(self, element)
+    }
+    #[inline]
+    fn remove(&mut self, index: usize) -> T {
+        ::heapless::Vec::remove(self, index)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: ::smallvec::Array> Storage for ::smallvec::SmallVec<A>
+where
+    A::Item: Ord,
+{
+    type Item = A::Item;
+
+    #[inline]
+    fn len(&self) -> usize {
+        ::smallvec::SmallVec::len(self)
+    }
+    #[inline]
+    fn last(&self) -> Option<&A::Item> {
+        self.as_slice().last()
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> &mut A::Item {
+        &mut self[index]
+    }
+    #[inline]
+    fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&A::Item) -> Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+    #[inline]
+    fn insert(&mut self, index: usize, element: A::Item) -> Result<(), A::Item> {
+        ::smallvec::SmallVec::insert(self, index, element);
+        Ok(())
+    }
+    #[inline]
+    fn push(&mut self, element: A::Item) -> Result<(), A::Item> {
+        ::smallvec::SmallVec::push(self, element);
+        Ok(())
+    }
+    #[inline]
+    fn remove(&mut self, index: usize) -> A::Item {
+        ::smallvec::SmallVec::remove(self, index)
+    }
+}
+
+#[cfg(feature = "tinyvec")]
+impl<A: ::tinyvec::Array> Storage for ::tinyvec::TinyVec<A>
+where
+    A::Item: Ord,
+{
+    type Item = A::Item;
+
+    #[inline]
+    fn len(&self) -> usize {
+        ::tinyvec::TinyVec::len(self)
+    }
+    #[inline]
+    fn last(&self) -> Option<&A::Item> {
+        self.as_slice().last()
+    }
+    #[inline]
+    fn get_mut(&mut self, index: usize) -> &mut A::Item {
+        &mut self[index]
+    }
+    #[inline]
+    fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&A::Item) -> Ordering,
+    {
+        self.as_slice().binary_search_by(f)
+    }
+    #[inline]
+    fn insert(&mut self, index: usize, element: A::Item) -> Result<(), A::Item> {
+        ::tinyvec::TinyVec::insert(self, index, element);
+        Ok(())
+    }
+    #[inline]
+    fn push(&mut self, element: A::Item) -> Result<(), A::Item> {
+        ::tinyvec::TinyVec::push(self, element);
+        Ok(())
+    }
+    #[inline]
+    fn remove(&mut self, index: usize) -> A::Item {
+        ::tinyvec::TinyVec::remove(self, index)
+    }
+}
@@ -0,0 +1,109 @@
+//! `proptest` strategies (feature `proptest`).
+//!
+//! `sorted_vec` and `sorted_set` build containers from a strategy for the
+//! elements, and the containers implement `Arbitrary`. The values are
+//! generated as a `Vec` (or a `BTreeSet` for the sets) and then sorted, so
+//! shrinking shrinks the underlying collection and every shrunk value is
+//! sorted as well.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use sorted_vec2::strategy::sorted_set;
+//!
+//! proptest! {
+//!     fn set_is_unique(set in sorted_set(0..100u32, 0..20)) {
+//!         prop_assert!(set.windows(2).all(|w| w[0] < w[1]));
+//!     }
+//! }
+//! # set_is_unique();
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+
+use ::proptest::arbitrary::{any_with, Arbitrary};
+use ::proptest::collection::{self, BTreeSetStrategy, SizeRange, VecStrategy};
+use ::proptest::strategy::{Map, Strategy};
+
+use crate::{SortOrder, SortedSet, SortedVec};
+
+/// Strategy for `SortedVec`s of elements from `element`, with a length in
+/// `size`
+pub fn sorted_vec<S>(
+    element: S,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = SortedVec<S::Value>>
+where
+    S: Strategy,
+    S::Value: Ord,
+{
+    collection::vec(element, size).prop_map(SortedVec::from_unsorted)
+}
+
+/// Strategy for `SortedSet`s of elements from `element`, with a length in
+/// `size`
+pub fn sorted_set<S>(
+    element: S,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = SortedSet<S::Value>>
+where
+    S: Strategy,
+    S::Value: Ord,
+{
+    collection::btree_set(element, size).prop_map(from_btree_set)
+}
+
+fn from_btree_set<T: Ord, O: SortOrder>(set: BTreeSet<T>) -> SortedSet<T, O> {
+    SortedSet {
+        set: SortedVec::sort_in_order(set.into_iter().collect()),
+    }
+}
+
+impl<T, O> Arbitrary for SortedVec<T, O>
+where
+    T: Ord + Arbitrary,
+    O: SortOrder + Debug,
+{
+    type Parameters = (SizeRange, T::Parameters);
+    type Strategy = Map<VecStrategy<T::Strategy>, fn(Vec<T>) -> Self>;
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        any_with::<Vec<T>>(args).prop_map(Self::sort_in_order as fn(Vec<T>) -> Self)
+    }
+}
+
+impl<T, O> Arbitrary for SortedSet<T, O>
+where
+    T: Ord + Arbitrary,
+    O: SortOrder + Debug,
+{
+    type Parameters = (SizeRange, T::Parameters);
+    type Strategy = Map<BTreeSetStrategy<T::Strategy>, fn(BTreeSet<T>) -> Self>;
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        any_with::<BTreeSet<T>>(args).prop_map(from_btree_set as fn(BTreeSet<T>) -> Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReverseSortedSet;
+    use ::proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn test_sorted_vec(v in sorted_vec(any::<i16>(), 0..50)) {
+            prop_assert!(v.windows(2).all(|w| w[0] <= w[1]));
+        }
+
+        #[test]
+        fn test_sorted_set(s in sorted_set(0..10u8, 3..=5)) {
+            prop_assert!((3..=5).contains(&s.len()));
+            prop_assert!(s.windows(2).all(|w| w[0] < w[1]));
+        }
+
+        #[test]
+        fn test_arbitrary(s in any::<ReverseSortedSet<char>>()) {
+            prop_assert!(s.windows(2).all(|w| w[0] > w[1]));
+        }
+    }
+}
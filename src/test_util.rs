@@ -0,0 +1,450 @@
+//! Model-based testing harness.
+//!
+//! Operations are applied both to a sorted container and to a reference model
+//! built on `BTreeMap`/`BTreeSet`. After every step the results of the
+//! operation are compared and the container invariants are checked. When a
+//! sequence of operations fails, `shrink` can be used to find a minimal
+//! failing sub-sequence.
+//!
+//! Requires the `test-util` feature.
+//!
+//! ```
+//! use sorted_vec2::test_util::{assert_sorted_vec_model, Op};
+//!
+//! assert_sorted_vec_model(&[Op::Insert(3), Op::Push(5), Op::Insert(3), Op::Pop]);
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+
+use crate::{FindOrInsert, SortedSet, SortedVec};
+
+/// An operation to apply to both a container and its reference model.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Op<T> {
+    /// `insert` for `SortedVec`, `replace` for `SortedSet`
+    Insert(T),
+    FindOrInsert(T),
+    Push(T),
+    FindOrPush(T),
+    RemoveItem(T),
+    /// Out of bounds indices are skipped
+    RemoveIndex(usize),
+    Pop,
+    Clear,
+    /// Only applicable to `SortedVec`
+    Dedup,
+}
+
+/// Describes the first step at which the container and the model diverged.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mismatch<T> {
+    /// Index of the failing operation in the sequence
+    pub step: usize,
+    pub op: Op<T>,
+    pub message: String,
+}
+
+impl<T: Debug> std::fmt::Display for Mismatch<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "step {} ({:?}): {}", self.step, self.op, self.message)
+    }
+}
+
+macro_rules! ensure {
+    ($step:expr, $op:expr, $cond:expr, $($arg:tt)*) => {
+        if !$cond {
+            return Err(Mismatch {
+                step: $step,
+                op: $op.clone(),
+                message: format!($($arg)*),
+            });
+        }
+    };
+}
+
+/// Number of model elements strictly less than `element`
+fn rank<T: Ord>(model: &BTreeMap<T, usize>, element: &T) -> usize {
+    model.range(..element).map(|(_, count)| count).sum()
+}
+
+fn flatten<T: Clone>(model: &BTreeMap<T, usize>) -> Vec<T> {
+    model
+        .iter()
+        .flat_map(|(t, count)| std::iter::repeat_n(t.clone(), *count))
+        .collect()
+}
+
+fn model_remove<T: Ord>(model: &mut BTreeMap<T, usize>, element: &T) {
+    if let Some(count) = model.get_mut(element) {
+        *count -= 1;
+        if *count == 0 {
+            model.remove(element);
+        }
+    }
+}
+
+/// Apply `ops` to a `SortedVec` and a `BTreeMap` multiset model, returning the
+/// first divergence.
+pub fn check_sorted_vec<T>(ops: &[Op<T>]) -> Result<(), Mismatch<T>>
+where
+    T: Ord + Clone + Debug,
+{
+    let mut vec = SortedVec::new();
+    let mut model: BTreeMap<T, usize> = BTreeMap::new();
+    for (step, op) in ops.iter().enumerate() {
+        match op {
+            Op::Insert(t) | Op::Push(t) => {
+                let index = match op {
+                    Op::Insert(_) => vec.insert(t.clone()),
+                    _ => vec.push(t.clone()),
+                };
+                let lower = rank(&model, t);
+                let upper = lower + model.get(t).copied().unwrap_or(0);
+                ensure!(
+                    step,
+                    op,
+                    lower <= index && index <= upper,
+                    "index {} outside of equal range {}..={}",
+                    index,
+                    lower,
+                    upper
+                );
+                *model.entry(t.clone()).or_insert(0) += 1;
+            }
+            Op::FindOrInsert(t) | Op::FindOrPush(t) => {
+                let result = match op {
+                    Op::FindOrInsert(_) => vec.find_or_insert(t.clone()),
+                    _ => vec.find_or_push(t.clone()),
+                };
+                let lower = rank(&model, t);
+                match model.get(t).copied() {
+                    Some(count) => ensure!(
+                        step,
+                        op,
+                        result.is_found()
+                            && lower <= result.index()
+                            && result.index() < lower + count,
+                        "expected Found in {}..{}, got {:?}",
+                        lower,
+                        lower + count,
+                        result
+                    ),
+                    None => {
+                        ensure!(
+                            step,
+                            op,
+                            result == FindOrInsert::Inserted(lower),
+                            "expected Inserted({}), got {:?}",
+                            lower,
+                            result
+                        );
+                        model.insert(t.clone(), 1);
+                    }
+                }
+            }
+            Op::RemoveItem(t) => {
+                let removed = vec.remove_item(t);
+                let expected = model.contains_key(t);
+                ensure!(
+                    step,
+                    op,
+                    removed.as_ref() == expected.then_some(t),
+                    "expected {:?}, got {:?}",
+                    expected.then_some(t),
+                    removed
+                );
+                model_remove(&mut model, t);
+            }
+            Op::RemoveIndex(index) => {
+                if *index < vec.len() {
+                    let expected = flatten(&model).swap_remove(*index);
+                    let removed = vec.remove_index(*index);
+                    ensure!(
+                        step,
+                        op,
+                        removed == expected,
+                        "expected {:?}, got {:?}",
+                        expected,
+                        removed
+                    );
+                    model_remove(&mut model, &expected);
+                }
+            }
+            Op::Pop => {
+                let expected = model.keys().next_back().cloned();
+                let popped = vec.pop();
+                ensure!(
+                    step,
+                    op,
+                    popped == expected,
+                    "expected {:?}, got {:?}",
+                    expected,
+                    popped
+                );
+                if let Some(t) = expected {
+                    model_remove(&mut model, &t);
+                }
+            }
+            Op::Clear => {
+                vec.clear();
+                model.clear();
+            }
+            Op::Dedup => {
+                vec.dedup();
+                model.values_mut().for_each(|count| *count = 1);
+            }
+        }
+        ensure!(
+            step,
+            op,
+            vec.windows(2).all(|w| w[0] <= w[1]),
+            "container is not sorted: {:?}",
+            *vec
+        );
+        let expected = flatten(&model);
+        ensure!(
+            step,
+            op,
+            *vec == expected,
+            "container {:?} differs from model {:?}",
+            *vec,
+            expected
+        );
+    }
+    Ok(())
+}
+
+/// Apply `ops` to a `SortedSet` and a `BTreeSet` model, returning the first
+/// divergence.
+pub fn check_sorted_set<T>(ops: &[Op<T>]) -> Result<(), Mismatch<T>>
+where
+    T: Ord + Clone + Debug,
+{
+    let mut set = SortedSet::new();
+    let mut model: BTreeSet<T> = BTreeSet::new();
+    for (step, op) in ops.iter().enumerate() {
+        match op {
+            Op::Insert(t) | Op::Push(t) => {
+                let (index, replaced) = match op {
+                    Op::Insert(_) => set.replace(t.clone()),
+                    _ => set.push(t.clone()),
+                };
+                let expected = model.range(..t).count();
+                ensure!(
+                    step,
+                    op,
+                    index == expected,
+                    "expected index {}, got {}",
+                    expected,
+                    index
+                );
+                ensure!(
+                    step,
+                    op,
+                    replaced.is_some() == model.contains(t),
+                    "expected replaced element {:?}, got {:?}",
+                    model.get(t),
+                    replaced
+                );
+                model.insert(t.clone());
+            }
+            Op::FindOrInsert(t) | Op::FindOrPush(t) => {
+                let result = match op {
+                    Op::FindOrInsert(_) => set.find_or_insert(t.clone()),
+                    _ => set.find_or_push(t.clone()),
+                };
+                let index = model.range(..t).count();
+                let expected = if model.insert(t.clone()) {
+                    FindOrInsert::Inserted(index)
+                } else {
+                    FindOrInsert::Found(index)
+                };
+                ensure!(
+                    step,
+                    op,
+                    result == expected,
+                    "expected {:?}, got {:?}",
+                    expected,
+                    result
+                );
+            }
+            Op::RemoveItem(t) => {
+                let removed = set.remove_item(t);
+                let expected = model.take(t);
+                ensure!(
+                    step,
+                    op,
+                    removed == expected,
+                    "expected {:?}, got {:?}",
+                    expected,
+                    removed
+                );
+            }
+            Op::RemoveIndex(index) => {
+                if let Some(expected) = model.iter().nth(*index).cloned() {
+                    let removed = set.remove_index(*index);
+                    ensure!(
+                        step,
+                        op,
+                        removed == expected,
+                        "expected {:?}, got {:?}",
+                        expected,
+                        removed
+                    );
+                    model.remove(&expected);
+                }
+            }
+            Op::Pop => {
+                let expected = model.pop_last();
+                let popped = set.pop();
+                ensure!(
+                    step,
+                    op,
+                    popped == expected,
+                    "expected {:?}, got {:?}",
+                    expected,
+                    popped
+                );
+            }
+            Op::Clear => {
+                set.clear();
+                model.clear();
+            }
+            Op::Dedup => {}
+        }
+        ensure!(
+            step,
+            op,
+            set.windows(2).all(|w| w[0] < w[1]),
+            "container is not strictly sorted: {:?}",
+            **set
+        );
+        ensure!(
+            step,
+            op,
+            set.iter().eq(model.iter()),
+            "container {:?} differs from model {:?}",
+            **set,
+            model
+        );
+    }
+    Ok(())
+}
+
+/// Shrink a failing operation sequence.
+///
+/// `fails` must return `true` for the input sequence. Chunks of operations are
+/// repeatedly removed, halving the chunk size down to single operations, as
+/// long as the remaining sequence still fails. The result is a sequence from
+/// which no single operation can be removed without the failure disappearing.
+pub fn shrink<T, F>(mut ops: Vec<Op<T>>, mut fails: F) -> Vec<Op<T>>
+where
+    T: Clone,
+    F: FnMut(&[Op<T>]) -> bool,
+{
+    let mut chunk = ops.len().div_ceil(2).max(1);
+    loop {
+        let mut removed_any = false;
+        let mut start = 0;
+        while start < ops.len() {
+            let end = (start + chunk).min(ops.len());
+            let mut candidate = Vec::with_capacity(ops.len() - (end - start));
+            candidate.extend_from_slice(&ops[..start]);
+            candidate.extend_from_slice(&ops[end..]);
+            if fails(&candidate) {
+                ops = candidate;
+                removed_any = true;
+            } else {
+                start = end;
+            }
+        }
+        if chunk == 1 && !removed_any {
+            return ops;
+        }
+        chunk = (chunk / 2).max(1);
+    }
+}
+
+/// Panics with a shrunk operation sequence if `check_sorted_vec` fails.
+pub fn assert_sorted_vec_model<T>(ops: &[Op<T>])
+where
+    T: Ord + Clone + Debug,
+{
+    if check_sorted_vec(ops).is_err() {
+        let shrunk = shrink(ops.to_vec(), |ops| check_sorted_vec(ops).is_err());
+        let mismatch = check_sorted_vec(&shrunk).unwrap_err();
+        panic!(
+            "SortedVec model mismatch at {}\nops: {:?}",
+            mismatch, shrunk
+        );
+    }
+}
+
+/// Panics with a shrunk operation sequence if `check_sorted_set` fails.
+pub fn assert_sorted_set_model<T>(ops: &[Op<T>])
+where
+    T: Ord + Clone + Debug,
+{
+    if check_sorted_set(ops).is_err() {
+        let shrunk = shrink(ops.to_vec(), |ops| check_sorted_set(ops).is_err());
+        let mismatch = check_sorted_set(&shrunk).unwrap_err();
+        panic!(
+            "SortedSet model mismatch at {}\nops: {:?}",
+            mismatch, shrunk
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random operation sequence
+    fn ops(seed: u64, len: usize) -> Vec<Op<i32>> {
+        let mut state = seed;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        (0..len)
+            .map(|_| {
+                let value = (next() % 16) as i32 - 8;
+                match next() % 10 {
+                    0 | 1 => Op::Insert(value),
+                    2 => Op::FindOrInsert(value),
+                    3 => Op::Push(value),
+                    4 => Op::FindOrPush(value),
+                    5 => Op::RemoveItem(value),
+                    6 => Op::RemoveIndex(value.unsigned_abs() as usize),
+                    7 => Op::Pop,
+                    8 => Op::Dedup,
+                    _ if next() % 8 == 0 => Op::Clear,
+                    _ => Op::Insert(value),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_model() {
+        for seed in 1..64 {
+            assert_sorted_vec_model(&ops(seed, 200));
+            assert_sorted_set_model(&ops(seed, 200));
+        }
+    }
+
+    #[test]
+    fn test_shrink() {
+        // fails whenever both 3 and 7 were inserted
+        let fails = |ops: &[Op<i32>]| ops.contains(&Op::Insert(3)) && ops.contains(&Op::Insert(7));
+        let mut input = ops(5, 50);
+        input.insert(10, Op::Insert(3));
+        input.insert(40, Op::Insert(7));
+        let shrunk = shrink(input, fails);
+        assert_eq!(shrunk.len(), 2);
+        assert!(fails(&shrunk));
+    }
+}
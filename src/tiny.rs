@@ -0,0 +1,302 @@
+//! TinyVec-backed sorted vector (feature `tinyvec`).
+//!
+//! `SortedTinyVec<A>` is an ascending vector that stores up to `A::CAPACITY`
+//! elements inline and moves to the heap once it grows past that, like
+//! `small::SortedSmallVec`, but backed by `tinyvec` which uses no unsafe code
+//! in its storage. The element type must implement `Default`, which is used
+//! to fill the unused inline slots.
+//!
+//! ```
+//! use sorted_vec2::tiny::SortedTinyVec;
+//!
+//! let mut v = SortedTinyVec::<[u32; 4]>::new();
+//! v.insert(7);
+//! v.insert(3);
+//! v.push(9);
+//! assert_eq!(v[..], [3, 7, 9]);
+//! assert!(!v.is_heap());
+//! ```
+
+use std::borrow::Borrow;
+
+use ::tinyvec::{Array, TinyVec};
+
+use crate::storage::{grow, Storage};
+use crate::FindOrInsert;
+
+/// Vector sorted in ascending order, storing up to `A::CAPACITY` elements
+/// inline, may contain duplicates
+pub struct SortedTinyVec<A: Array>
+where
+    A::Item: Ord,
+{
+    vec: TinyVec<A>,
+}
+
+// derives would bound `A` instead of the element type
+impl<A: Array> Clone for SortedTinyVec<A>
+where
+    A::Item: Ord + Clone,
+{
+    fn clone(&self) -> Self {
+        SortedTinyVec {
+            vec: self.vec.clone(),
+        }
+    }
+}
+impl<A: Array> std::fmt::Debug for SortedTinyVec<A>
+where
+    A::Item: Ord + std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SortedTinyVec")
+            .field("vec", &self.vec)
+            .finish()
+    }
+}
+impl<A: Array> PartialEq for SortedTinyVec<A>
+where
+    A::Item: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.vec == other.vec
+    }
+}
+impl<A: Array> Eq for SortedTinyVec<A> where A::Item: Ord {}
+impl<A: Array> std::hash::Hash for SortedTinyVec<A>
+where
+    A::Item: Ord + std::hash::Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.vec.hash(state)
+    }
+}
+
+//
+//  impl SortedTinyVec
+//
+
+impl<A: Array> SortedTinyVec<A>
+where
+    A::Item: Ord,
+{
+    #[inline]
+    pub fn new() -> Self {
+        SortedTinyVec {
+            vec: TinyVec::default(),
+        }
+    }
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedTinyVec {
+            vec: TinyVec::with_capacity(capacity),
+        }
+    }
+    /// Uses `sort_unstable()` to sort in place.
+    #[inline]
+    pub fn from_unsorted(mut vec: TinyVec<A>) -> Self {
+        vec.sort_unstable();
+        SortedTinyVec { vec }
+    }
+    /// # Safety
+    ///
+    /// The caller must ensure that the provided vector is already sorted.
+    #[inline]
+    pub unsafe fn from_unsorted_unchecked(vec: TinyVec<A>) -> Self {
+        SortedTinyVec { vec }
+    }
+    /// Insert an element into sorted position, returning the order index at which
+    /// it was placed.
+    #[inline]
+    pub fn insert(&mut self, element: A::Item) -> usize {
+        grow(self.vec.insert_sorted(element))
+    }
+    /// Find the element and return the index with `Ok`, otherwise insert the
+    /// element and return the new element index with `Err`.
+    #[inline]
+    pub fn find_or_insert(&mut self, element: A::Item) -> FindOrInsert {
+        grow(self.vec.find_or_insert_sorted(element))
+    }
+    /// Same as insert, except performance is O(1) when the element belongs at the
+    /// back of the container.
+    #[inline]
+    pub fn push(&mut self, element: A::Item) -> usize {
+        grow(self.vec.push_sorted(element))
+    }
+    /// Reserves additional capacity, moving the elements to the heap if they
+    /// no longer fit inline. See tinyvec::TinyVec::reserve.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional);
+    }
+    /// Binary search for `x`, which may be any borrowed form of the element
+    /// type. See `slice::binary_search`.
+    #[inline]
+    pub fn binary_search<Q>(&self, x: &Q) -> Result<usize, usize>
+    where
+        A::Item: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.search(x)
+    }
+    /// Binary search for `x`, shadowing the linear `slice::contains` reached
+    /// through `Deref`. O(log n).
+    #[inline]
+    pub fn contains<Q>(&self, x: &Q) -> bool
+    where
+        A::Item: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search(x).is_ok()
+    }
+    #[inline]
+    pub fn remove_item<Q>(&mut self, item: &Q) -> Option<A::Item>
+    where
+        A::Item: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.vec.remove_sorted(item)
+    }
+    /// Panics if index is out of bounds
+    #[inline]
+    pub fn remove_index(&mut self, index: usize) -> A::Item {
+        self.vec.remove(index)
+    }
+    #[inline]
+    pub fn pop(&mut self) -> Option<A::Item> {
+        self.vec.pop()
+    }
+    #[inline]
+    pub fn clear(&mut self) {
+        self.vec.clear()
+    }
+    pub fn dedup(&mut self) {
+        if self.vec.is_empty() {
+            return;
+        }
+        // move the first of each run of equal elements to the front, leaving
+        // the duplicates at the back to be truncated
+        let mut write = 0;
+        for read in 1..self.vec.len() {
+            if self.vec[read] != self.vec[write] {
+                write += 1;
+                self.vec.swap(write, read);
+            }
+        }
+        self.vec.truncate(write + 1);
+    }
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&A::Item) -> bool,
+    {
+        self.vec.retain(|x| f(x))
+    }
+    /// Whether the elements have been moved to the heap
+    #[inline]
+    pub fn is_heap(&self) -> bool {
+        self.vec.is_heap()
+    }
+    /// The sorted elements as a `TinyVec`
+    #[inline]
+    pub fn into_inner(self) -> TinyVec<A> {
+        self.vec
+    }
+    /// The sorted elements as a `Vec`, reusing the heap allocation if the
+    /// elements have moved to the heap
+    #[inline]
+    pub fn into_vec(self) -> Vec<A::Item> {
+        match self.vec {
+            TinyVec::Inline(array) => array.into_iter().collect(),
+            TinyVec::Heap(vec) => vec,
+        }
+    }
+}
+
+impl<A: Array> Default for SortedTinyVec<A>
+where
+    A::Item: Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<A: Array> std::ops::Deref for SortedTinyVec<A>
+where
+    A::Item: Ord,
+{
+    type Target = TinyVec<A>;
+    fn deref(&self) -> &TinyVec<A> {
+        &self.vec
+    }
+}
+impl<A: Array> Extend<A::Item> for SortedTinyVec<A>
+where
+    A::Item: Ord,
+{
+    fn extend<I: IntoIterator<Item = A::Item>>(&mut self, iter: I) {
+        for t in iter {
+            let _ = self.insert(t);
+        }
+    }
+}
+impl<A: Array> FromIterator<A::Item> for SortedTinyVec<A>
+where
+    A::Item: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = A::Item>>(iter: I) -> Self {
+        Self::from_unsorted(iter.into_iter().collect())
+    }
+}
+impl<A: Array> From<TinyVec<A>> for SortedTinyVec<A>
+where
+    A::Item: Ord,
+{
+    fn from(unsorted: TinyVec<A>) -> Self {
+        Self::from_unsorted(unsorted)
+    }
+}
+impl<A: Array> IntoIterator for SortedTinyVec<A>
+where
+    A::Item: Ord,
+{
+    type Item = A::Item;
+    type IntoIter = ::tinyvec::TinyVecIterator<A>;
+    fn into_iter(self) -> ::tinyvec::TinyVecIterator<A> {
+        self.vec.into_iter()
+    }
+}
+impl<'a, A: Array> IntoIterator for &'a SortedTinyVec<A>
+where
+    A::Item: Ord,
+{
+    type Item = &'a A::Item;
+    type IntoIter = std::slice::Iter<'a, A::Item>;
+    fn into_iter(self) -> std::slice::Iter<'a, A::Item> {
+        self.vec.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiny() {
+        let mut v: SortedTinyVec<[i32; 4]> = [5, 1, 3].into_iter().collect();
+        assert_eq!(v.push(7), 3);
+        assert_eq!(v.find_or_insert(3), FindOrInsert::Found(1));
+        assert_eq!(v[..], [1, 3, 5, 7]);
+        assert!(!v.is_heap());
+        assert_eq!(v.insert(3), 1);
+        assert!(v.is_heap());
+        v.dedup();
+        assert_eq!(v[..], [1, 3, 5, 7]);
+        assert_eq!(v.remove_item(&5), Some(5));
+        v.retain(|x| *x != 1);
+        assert_eq!(v.clone().into_vec(), vec![3, 7]);
+        assert_eq!(v.into_iter().collect::<Vec<_>>(), vec![3, 7]);
+        let v = SortedTinyVec::<[i32; 4]>::from_unsorted([2, 1, 2].into_iter().collect());
+        assert_eq!(v.into_vec(), vec![1, 2, 2]);
+    }
+}
@@ -0,0 +1,150 @@
+//! `utoipa` OpenAPI schema support (feature `utoipa`).
+//!
+//! The containers are described as arrays of their elements, with
+//! `uniqueItems` set for the sets, and `SortedVecMap` as an object like other
+//! maps. OpenAPI has no keyword for ordering, so the ordering is stated in the
+//! description, and descending containers get a `Reverse` prefix on their
+//! schema name.
+//!
+//! ```
+//! use sorted_vec2::SortedSet;
+//! use utoipa::openapi::schema::Schema;
+//! use utoipa::openapi::RefOr;
+//! use utoipa::PartialSchema;
+//!
+//! match SortedSet::<u32>::schema() {
+//!     RefOr::T(Schema::Array(array)) => assert!(array.unique_items),
+//!     _ => unreachable!(),
+//! }
+//! ```
+
+use std::borrow::Cow;
+
+use ::utoipa::openapi::schema::{ArrayBuilder, ObjectBuilder, Schema};
+use ::utoipa::openapi::RefOr;
+use ::utoipa::{PartialSchema, ToSchema};
+
+use crate::map::SortedVecMap;
+use crate::{partial, Ascending, Descending, SortOrder, SortedSet, SortedVec};
+
+fn array_schema<T: ToSchema, O: SortOrder>(unique: bool) -> RefOr<Schema> {
+    let description = if unique {
+        format!("Array of unique items sorted in {} order", O::NAME)
+    } else {
+        format!("Array sorted in {} order", O::NAME)
+    };
+    RefOr::T(Schema::Array(
+        ArrayBuilder::new()
+            .items(T::schema())
+            .unique_items(unique)
+            .description(Some(description))
+            .build(),
+    ))
+}
+
+impl<T: Ord + ToSchema, O: SortOrder> PartialSchema for SortedVec<T, O> {
+    fn schema() -> RefOr<Schema> {
+        array_schema::<T, O>(false)
+    }
+}
+
+impl<T: Ord + ToSchema, O: SortOrder> ToSchema for SortedVec<T, O> {
+    fn name() -> Cow<'static, str> {
+        let prefix = if O::IS_DESCENDING { "Reverse" } else { "" };
+        format!("{}SortedVec_{}", prefix, T::name()).into()
+    }
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        T::schemas(schemas)
+    }
+}
+
+impl<T: Ord + ToSchema, O: SortOrder> PartialSchema for SortedSet<T, O> {
+    fn schema() -> RefOr<Schema> {
+        array_schema::<T, O>(true)
+    }
+}
+
+impl<T: Ord + ToSchema, O: SortOrder> ToSchema for SortedSet<T, O> {
+    fn name() -> Cow<'static, str> {
+        let prefix = if O::IS_DESCENDING { "Reverse" } else { "" };
+        format!("{}SortedSet_{}", prefix, T::name()).into()
+    }
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        T::schemas(schemas)
+    }
+}
+
+macro_rules! impl_partial {
+    ($($container:ident => $order:ty, $unique:expr),*) => {
+        $(
+            impl<T: PartialOrd + ToSchema> PartialSchema for partial::$container<T> {
+                fn schema() -> RefOr<Schema> {
+                    array_schema::<T, $order>($unique)
+                }
+            }
+            impl<T: PartialOrd + ToSchema> ToSchema for partial::$container<T> {
+                fn name() -> Cow<'static, str> {
+                    format!("{}_{}", stringify!($container), T::name()).into()
+                }
+                fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+                    T::schemas(schemas)
+                }
+            }
+        )*
+    };
+}
+
+impl_partial!(
+    SortedVec => Ascending, false,
+    SortedSet => Ascending, true,
+    ReverseSortedVec => Descending, false,
+    ReverseSortedSet => Descending, true
+);
+
+impl<K: Ord, V: ToSchema> PartialSchema for SortedVecMap<K, V> {
+    fn schema() -> RefOr<Schema> {
+        RefOr::T(Schema::Object(
+            ObjectBuilder::new()
+                .additional_properties(Some(V::schema()))
+                .description(Some("Map with keys sorted in ascending order"))
+                .build(),
+        ))
+    }
+}
+
+impl<K: Ord, V: ToSchema> ToSchema for SortedVecMap<K, V> {
+    fn name() -> Cow<'static, str> {
+        format!("SortedVecMap_{}", V::name()).into()
+    }
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        V::schemas(schemas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReverseSortedVec;
+
+    #[test]
+    fn test_to_schema() {
+        let RefOr::T(Schema::Array(array)) = ReverseSortedVec::<i64>::schema() else {
+            panic!("expected an array schema")
+        };
+        assert!(!array.unique_items);
+        assert_eq!(
+            array.description.as_deref(),
+            Some("Array sorted in descending order")
+        );
+        let RefOr::T(Schema::Array(array)) = partial::ReverseSortedSet::<f64>::schema() else {
+            panic!("expected an array schema")
+        };
+        assert!(array.unique_items);
+        assert_eq!(SortedSet::<String>::name(), "SortedSet_String");
+        assert_eq!(ReverseSortedVec::<i64>::name(), "ReverseSortedVec_i64");
+        assert!(matches!(
+            SortedVecMap::<String, u8>::schema(),
+            RefOr::T(Schema::Object(_))
+        ));
+    }
+}
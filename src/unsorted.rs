@@ -0,0 +1,183 @@
+//! Sort-on-deserialize for use with `#[serde(with = "sorted_vec2::unsorted")]`
+//! (feature `serde`).
+//!
+//! By default deserializing an unsorted sequence (or a set containing
+//! duplicates) is an error. Fields tagged with this module instead accept any
+//! sequence, sorting it and, for sets, removing duplicates. The containers are
+//! serialized as a plain sequence of their elements, also with the
+//! `serde-nontransparent` feature, so that the fields can be read back.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use sorted_vec2::{SortedSet, SortedVec};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Report {
+//!     #[serde(with = "sorted_vec2::unsorted")]
+//!     readings: SortedVec<u32>,
+//!     #[serde(with = "sorted_vec2::unsorted")]
+//!     tags: SortedSet<String>,
+//! }
+//! ```
+
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::{partial, SortOrder, SortedSet, SortedVec};
+
+/// Containers that can be deserialized from an unsorted sequence
+pub trait DeserializeUnsorted<'de>: Sized {
+    fn deserialize_unsorted<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+/// Containers serialized as a plain sequence of their elements
+pub trait SerializePlain {
+    fn serialize_plain<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+}
+
+impl<'de, T, O> DeserializeUnsorted<'de> for SortedVec<T, O>
+where
+    T: Ord + serde::Deserialize<'de>,
+    O: SortOrder,
+{
+    fn deserialize_unsorted<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        SortedVec::deserialize_unsorted(deserializer)
+    }
+}
+
+impl<'de, T, O> DeserializeUnsorted<'de> for SortedSet<T, O>
+where
+    T: Ord + serde::Deserialize<'de>,
+    O: SortOrder,
+{
+    fn deserialize_unsorted<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        SortedSet::deserialize_dedup_unsorted(deserializer)
+    }
+}
+
+impl<T, O> SerializePlain for SortedVec<T, O>
+where
+    T: Ord + Serialize,
+    O: SortOrder,
+{
+    fn serialize_plain<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<T, O> SerializePlain for SortedSet<T, O>
+where
+    T: Ord + Serialize,
+    O: SortOrder,
+{
+    fn serialize_plain<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+macro_rules! impl_partial {
+    ($($container:ident => $deserialize:ident),*) => {
+        $(
+            impl<'de, T> DeserializeUnsorted<'de> for partial::$container<T>
+            where
+                T: PartialOrd + serde::Deserialize<'de>,
+            {
+                fn deserialize_unsorted<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    partial::$container::$deserialize(deserializer)
+                }
+            }
+
+            impl<T> SerializePlain for partial::$container<T>
+            where
+                T: PartialOrd + Serialize,
+            {
+                fn serialize_plain<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    serializer.collect_seq(self.iter())
+                }
+            }
+        )*
+    };
+}
+
+impl_partial!(
+    SortedVec => deserialize_unsorted,
+    SortedSet => deserialize_dedup_unsorted,
+    ReverseSortedVec => deserialize_unsorted,
+    ReverseSortedSet => deserialize_dedup_unsorted
+);
+
+pub fn serialize<C, S>(container: &C, serializer: S) -> Result<S::Ok, S::Error>
+where
+    C: SerializePlain,
+    S: Serializer,
+{
+    container.serialize_plain(serializer)
+}
+
+pub fn deserialize<'de, C, D>(deserializer: D) -> Result<C, D::Error>
+where
+    C: DeserializeUnsorted<'de>,
+    D: Deserializer<'de>,
+{
+    C::deserialize_unsorted(deserializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Foo {
+        #[serde(with = "crate::unsorted")]
+        v: SortedVec<i32>,
+        #[serde(with = "crate::unsorted")]
+        s: SortedSet<i32>,
+        #[serde(with = "crate::unsorted")]
+        p: partial::ReverseSortedVec<f64>,
+    }
+
+    #[test]
+    fn test_unsorted() {
+        let foo: Foo = serde_json::from_str(r#"{"v":[3,1,3],"s":[3,1,3],"p":[1.5,2.5]}"#).unwrap();
+        assert_eq!(*foo.v, vec![1, 3, 3]);
+        assert_eq!(**foo.s, vec![1, 3]);
+        assert_eq!(*foo.p, vec![2.5, 1.5]);
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"v":[1,3,3],"s":[1,3],"p":[2.5,1.5]}"#
+        );
+    }
+
+    #[test]
+    fn test_unsorted_round_trip() {
+        let foo = Foo {
+            v: SortedVec::from_unsorted(vec![2, 1]),
+            s: SortedSet::from_unsorted(vec![5, 4, 5]),
+            p: partial::ReverseSortedVec::from_unsorted(vec![0.5, 1.5]),
+        };
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(serde_json::from_str::<Foo>(&json).unwrap(), foo);
+    }
+}